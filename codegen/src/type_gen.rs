@@ -189,6 +189,16 @@ fn parse_types() -> BTreeMap<u32, Type> {
         })
         .collect::<HashMap<_, _>>();
 
+    let multirange_elements = raw_ranges
+        .iter()
+        .map(|m| {
+            (
+                oids_by_name[&*m["rngmultitypid"]],
+                oids_by_name[&*m["rngtypid"]],
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
     let range_vector_re = Regex::new("(range|vector)$").unwrap();
     let array_re = Regex::new("^_(.*)").unwrap();
 
@@ -204,7 +214,12 @@ fn parse_types() -> BTreeMap<u32, Type> {
         let variant = snake_to_camel(&ident);
         let ident = ident.to_ascii_uppercase();
 
-        let kind = raw_type["typcategory"].clone();
+        // multiranges share the range category
+        let kind = if raw_type.get("typtype").map(|s| &**s) == Some("m") {
+            "M".to_string()
+        } else {
+            raw_type["typcategory"].clone()
+        };
 
         // we need to be able to pull composite fields and enum variants at runtime
         if kind == "C" || kind == "E" {
@@ -213,6 +228,7 @@ fn parse_types() -> BTreeMap<u32, Type> {
 
         let element = match &*kind {
             "R" => range_elements[&oid],
+            "M" => multirange_elements[&oid],
             "A" => oids_by_name[&raw_type["typelem"]],
             _ => 0,
         };
@@ -353,6 +369,7 @@ fn make_impl(w: &mut BufWriter<File>, types: &BTreeMap<u32, Type>) {
             "P" => "Pseudo".to_owned(),
             "A" => format!("Array(Type(Inner::{}))", types[&type_.element].variant),
             "R" => format!("Range(Type(Inner::{}))", types[&type_.element].variant),
+            "M" => format!("Multirange(Type(Inner::{}))", types[&type_.element].variant),
             _ => "Simple".to_owned(),
         };
 
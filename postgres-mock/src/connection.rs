@@ -0,0 +1,534 @@
+use crate::scram::ScramSha256;
+use crate::{Auth, Received, Response, Shared};
+use bytes::BytesMut;
+use fallible_iterator::FallibleIterator;
+use futures::channel::mpsc;
+use futures::StreamExt;
+use postgres_protocol::authentication::md5_hash;
+use postgres_protocol::authentication::sasl::SCRAM_SHA_256;
+use postgres_protocol::message::backend::{self, Field};
+use postgres_protocol::message::frontend::{Message, PasswordMessageBody, StartupMessage};
+use postgres_protocol::Oid;
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const IDLE: u8 = b'I';
+
+struct Statement {
+    query: String,
+    parameter_types: Vec<Oid>,
+}
+
+struct Portal {
+    query: String,
+    result_formats: Vec<i16>,
+    // the number of rows already returned by a suspended portal
+    offset: usize,
+}
+
+struct Connection<S> {
+    stream: S,
+    shared: Arc<Shared>,
+    read: BytesMut,
+    write: BytesMut,
+    process_id: i32,
+    cancel: mpsc::UnboundedReceiver<()>,
+    statements: HashMap<String, Statement>,
+    portals: HashMap<String, Portal>,
+}
+
+pub async fn handle<S>(mut stream: S, shared: Arc<Shared>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut read = BytesMut::new();
+    let parameters = match startup(&mut stream, &mut read, &shared).await {
+        Ok(Some(parameters)) => parameters,
+        Ok(None) | Err(_) => return,
+    };
+
+    let (process_id, secret_key, cancel) = shared.register();
+    let mut connection = Connection {
+        stream,
+        shared,
+        read,
+        write: BytesMut::new(),
+        process_id,
+        cancel,
+        statements: HashMap::new(),
+        portals: HashMap::new(),
+    };
+
+    let _ = connection.run(parameters, secret_key).await;
+    connection.shared.unregister(process_id);
+}
+
+// Handles the untagged messages at the start of a connection, returning the startup parameters if the client wants a
+// session.
+async fn startup<S>(
+    stream: &mut S,
+    read: &mut BytesMut,
+    shared: &Shared,
+) -> io::Result<Option<Vec<(String, String)>>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    loop {
+        let message = loop {
+            if let Some(message) = StartupMessage::parse(read)? {
+                break message;
+            }
+            if stream.read_buf(read).await? == 0 {
+                return Ok(None);
+            }
+        };
+
+        match message {
+            StartupMessage::Startup(body) => {
+                let parameters = body
+                    .parameters()
+                    .map(|(k, v)| Ok((k.to_string(), v.to_string())))
+                    .collect()?;
+                return Ok(Some(parameters));
+            }
+            // encryption is not supported
+            StartupMessage::SslRequest | StartupMessage::GssEncRequest => {
+                stream.write_all(b"N").await?;
+            }
+            StartupMessage::CancelRequest(body) => {
+                shared.record(Received::CancelRequest {
+                    process_id: body.process_id(),
+                    secret_key: body.secret_key(),
+                });
+                shared.cancel(body.process_id(), body.secret_key());
+                return Ok(None);
+            }
+            _ => return Ok(None),
+        }
+    }
+}
+
+fn error_fields<'a>(severity: &'a str, code: &'a str, message: &'a str) -> Vec<(u8, &'a str)> {
+    vec![
+        (b'S', severity),
+        (b'V', severity),
+        (b'C', code),
+        (b'M', message),
+    ]
+}
+
+impl<S> Connection<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    async fn read_message(&mut self) -> io::Result<Option<Message>> {
+        loop {
+            if let Some(message) = Message::parse(&mut self.read)? {
+                return Ok(Some(message));
+            }
+            if self.stream.read_buf(&mut self.read).await? == 0 {
+                return Ok(None);
+            }
+        }
+    }
+
+    async fn read_password(&mut self) -> io::Result<PasswordMessageBody> {
+        match self.read_message().await? {
+            Some(Message::PasswordMessage(body)) => Ok(body),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "expected a password message",
+            )),
+        }
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        self.stream.write_all(&self.write).await?;
+        self.write.clear();
+        Ok(())
+    }
+
+    fn error(&mut self, code: &str, message: &str) -> io::Result<()> {
+        backend::error_response(error_fields("ERROR", code, message), &mut self.write)
+    }
+
+    async fn fatal(&mut self, code: &str, message: &str) -> io::Result<()> {
+        backend::error_response(error_fields("FATAL", code, message), &mut self.write)?;
+        self.flush().await
+    }
+
+    async fn run(&mut self, parameters: Vec<(String, String)>, secret_key: i32) -> io::Result<()> {
+        let user = parameters
+            .iter()
+            .find(|(k, _)| k == "user")
+            .map(|(_, v)| v.clone());
+        self.shared.record(Received::Startup(parameters));
+
+        let user = match user {
+            Some(user) => user,
+            None => {
+                return self
+                    .fatal(
+                        "28000",
+                        "no PostgreSQL user name specified in startup packet",
+                    )
+                    .await;
+            }
+        };
+
+        if !self.authenticate(&user).await? {
+            let message = format!("password authentication failed for user \"{}\"", user);
+            return self.fatal("28P01", &message).await;
+        }
+
+        backend::authentication_ok(&mut self.write);
+        for (name, value) in &self.shared.parameters {
+            backend::parameter_status(name, value, &mut self.write)?;
+        }
+        backend::backend_key_data(self.process_id, secret_key, &mut self.write);
+        backend::ready_for_query(IDLE, &mut self.write);
+        self.flush().await?;
+
+        // set after an error in an extended query, until the next sync
+        let mut failed = false;
+        while let Some(message) = self.read_message().await? {
+            let received = received(&message)?;
+            self.shared.record(received);
+
+            match message {
+                Message::Query(body) => {
+                    let query = body.query()?.to_string();
+                    self.simple_query(&query).await?;
+                    backend::ready_for_query(IDLE, &mut self.write);
+                    self.flush().await?;
+                }
+                // fast-path calls can't be scripted, so fail them like a server without the function would
+                Message::FunctionCall(body) => {
+                    let message = format!("function with OID {} does not exist", body.oid());
+                    self.error("42883", &message)?;
+                    backend::ready_for_query(IDLE, &mut self.write);
+                    self.flush().await?;
+                }
+                Message::Sync => {
+                    failed = false;
+                    backend::ready_for_query(IDLE, &mut self.write);
+                    self.flush().await?;
+                }
+                Message::Flush => self.flush().await?,
+                Message::Terminate => return Ok(()),
+                Message::CopyData(_) | Message::CopyDone | Message::CopyFail(_) => {}
+                message if !failed => {
+                    failed = !self.extended_query(message).await?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn authenticate(&mut self, user: &str) -> io::Result<bool> {
+        match self.shared.auth.clone() {
+            Auth::Trust => Ok(true),
+            Auth::Md5(password) => {
+                let salt = self.process_id.to_be_bytes();
+                backend::authentication_md5_password(salt, &mut self.write);
+                self.flush().await?;
+
+                let expected = md5_hash(user.as_bytes(), password.as_bytes(), salt);
+                let body = self.read_password().await?;
+                Ok(body.password()? == expected.as_bytes())
+            }
+            Auth::ScramSha256(password) => {
+                backend::authentication_sasl(Some(SCRAM_SHA_256), &mut self.write)?;
+                self.flush().await?;
+
+                let body = self.read_password().await?;
+                let initial = body.sasl_initial_response()?;
+                if initial.mechanism() != SCRAM_SHA_256 {
+                    return Ok(false);
+                }
+                let (scram, server_first) =
+                    ScramSha256::new(&password, initial.data().unwrap_or(&[]))?;
+                backend::authentication_sasl_continue(server_first.as_bytes(), &mut self.write)?;
+                self.flush().await?;
+
+                let body = self.read_password().await?;
+                match scram.finish(body.data()) {
+                    Ok(server_final) => {
+                        backend::authentication_sasl_final(
+                            server_final.as_bytes(),
+                            &mut self.write,
+                        )?;
+                        Ok(true)
+                    }
+                    Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => Ok(false),
+                    Err(e) => Err(e),
+                }
+            }
+        }
+    }
+
+    fn response(&mut self, query: &str) -> io::Result<Option<Response>> {
+        match self.shared.responses.get(query) {
+            Some(response) => Ok(Some(response.clone())),
+            None => {
+                self.error(
+                    "42601",
+                    &format!("mock server has no response for query `{}`", query),
+                )?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn row_description(&mut self, response: &Response, formats: &[i16]) -> io::Result<()> {
+        if response.columns.is_empty() {
+            backend::no_data(&mut self.write);
+            return Ok(());
+        }
+
+        let fields = response
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| Field::new(&c.name, 0, 0, c.type_.oid(), -1, -1, format(formats, i)));
+        backend::row_description(fields, &mut self.write)
+    }
+
+    // Sends everything which precedes a response's rows, returning `false` if the query failed.
+    async fn begin(&mut self, response: &Response) -> io::Result<bool> {
+        for notice in &response.notices {
+            backend::notice_response(error_fields("NOTICE", "00000", notice), &mut self.write)?;
+        }
+        for (channel, payload) in &response.notifications {
+            backend::notification_response(self.process_id, channel, payload, &mut self.write)?;
+        }
+
+        if response.wait_for_cancel {
+            self.flush().await?;
+            if self.cancel.next().await.is_some() {
+                self.error("57014", "canceling statement due to user request")?;
+            }
+            return Ok(false);
+        }
+
+        if let Some((code, message)) = &response.error {
+            self.error(code, message)?;
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    fn finish(&mut self, response: &Response) -> io::Result<()> {
+        match &response.tag {
+            Some(tag) => backend::command_complete(tag, &mut self.write),
+            None => backend::command_complete(
+                &format!("SELECT {}", response.rows.len()),
+                &mut self.write,
+            ),
+        }
+    }
+
+    async fn simple_query(&mut self, query: &str) -> io::Result<()> {
+        if query.trim().is_empty() {
+            backend::empty_query_response(&mut self.write);
+            return Ok(());
+        }
+
+        let response = match self.response(query)? {
+            Some(response) => response,
+            None => return Ok(()),
+        };
+        if !self.begin(&response).await? {
+            return Ok(());
+        }
+
+        if !response.columns.is_empty() {
+            self.row_description(&response, &[])?;
+        }
+        for row in &response.rows {
+            backend::data_row(row.iter().map(|v| v.as_deref()), &mut self.write)?;
+        }
+        self.finish(&response)
+    }
+
+    // Returns `false` if the message failed, in which case messages are discarded until the next sync.
+    async fn extended_query(&mut self, message: Message) -> io::Result<bool> {
+        match message {
+            Message::Parse(body) => {
+                let query = body.query()?.to_string();
+                if self.response(&query)?.is_none() {
+                    return Ok(false);
+                }
+                let statement = Statement {
+                    query,
+                    parameter_types: body.parameter_types().collect()?,
+                };
+                self.statements.insert(body.name()?.to_string(), statement);
+                backend::parse_complete(&mut self.write);
+            }
+            Message::Bind(body) => {
+                let name = body.statement()?;
+                let query = match self.statements.get(name) {
+                    Some(statement) => statement.query.clone(),
+                    None => {
+                        let message = format!("prepared statement \"{}\" does not exist", name);
+                        self.error("26000", &message)?;
+                        return Ok(false);
+                    }
+                };
+                let portal = Portal {
+                    query,
+                    result_formats: body.result_formats().collect()?,
+                    offset: 0,
+                };
+                self.portals.insert(body.portal()?.to_string(), portal);
+                backend::bind_complete(&mut self.write);
+            }
+            Message::Describe(body) => {
+                let name = body.name()?;
+                if body.variant() == b'S' {
+                    let statement = match self.statements.get(name) {
+                        Some(statement) => statement,
+                        None => {
+                            let message = format!("prepared statement \"{}\" does not exist", name);
+                            self.error("26000", &message)?;
+                            return Ok(false);
+                        }
+                    };
+                    let response = self.shared.responses[&statement.query].clone();
+                    let len = statement
+                        .parameter_types
+                        .len()
+                        .max(response.parameters.len());
+                    let parameters = (0..len)
+                        .map(|i| match statement.parameter_types.get(i) {
+                            Some(&oid) if oid != 0 => oid,
+                            _ => response.parameters.get(i).map_or(0, |t| t.oid()),
+                        })
+                        .collect::<Vec<_>>();
+                    backend::parameter_description(parameters, &mut self.write)?;
+                    self.row_description(&response, &[])?;
+                } else {
+                    let (query, formats) = match self.portals.get(name) {
+                        Some(portal) => (portal.query.clone(), portal.result_formats.clone()),
+                        None => {
+                            let message = format!("portal \"{}\" does not exist", name);
+                            self.error("34000", &message)?;
+                            return Ok(false);
+                        }
+                    };
+                    let response = self.shared.responses[&query].clone();
+                    self.row_description(&response, &formats)?;
+                }
+            }
+            Message::Execute(body) => {
+                let name = body.portal()?;
+                let (query, offset) = match self.portals.get(name) {
+                    Some(portal) => (portal.query.clone(), portal.offset),
+                    None => {
+                        let message = format!("portal \"{}\" does not exist", name);
+                        self.error("34000", &message)?;
+                        return Ok(false);
+                    }
+                };
+                let response = self.shared.responses[&query].clone();
+                if offset == 0 && !self.begin(&response).await? {
+                    return Ok(false);
+                }
+
+                let remaining = &response.rows[offset..];
+                let max_rows = body.max_rows();
+                let end = if max_rows > 0 && (max_rows as usize) < remaining.len() {
+                    max_rows as usize
+                } else {
+                    remaining.len()
+                };
+                for row in &remaining[..end] {
+                    backend::data_row(row.iter().map(|v| v.as_deref()), &mut self.write)?;
+                }
+
+                if end < remaining.len() {
+                    if let Some(portal) = self.portals.get_mut(name) {
+                        portal.offset += end;
+                    }
+                    backend::portal_suspended(&mut self.write);
+                } else {
+                    self.finish(&response)?;
+                }
+            }
+            Message::Close(body) => {
+                let name = body.name()?;
+                if body.variant() == b'S' {
+                    self.statements.remove(name);
+                } else {
+                    self.portals.remove(name);
+                }
+                backend::close_complete(&mut self.write);
+            }
+            _ => {}
+        }
+
+        Ok(true)
+    }
+}
+
+fn format(formats: &[i16], i: usize) -> i16 {
+    match formats.len() {
+        0 => 0,
+        1 => formats[0],
+        _ => formats.get(i).cloned().unwrap_or(0),
+    }
+}
+
+fn received(message: &Message) -> io::Result<Received> {
+    let received = match message {
+        Message::Bind(body) => Received::Bind {
+            portal: body.portal()?.to_string(),
+            statement: body.statement()?.to_string(),
+            values: body.values().map(|v| Ok(v.map(|v| v.to_vec()))).collect()?,
+            result_formats: body.result_formats().collect()?,
+        },
+        Message::Close(body) => Received::Close {
+            variant: body.variant(),
+            name: body.name()?.to_string(),
+        },
+        Message::CopyData(body) => Received::CopyData(body.data().to_vec()),
+        Message::CopyDone => Received::CopyDone,
+        Message::CopyFail(body) => Received::CopyFail(body.message()?.to_string()),
+        Message::Describe(body) => Received::Describe {
+            variant: body.variant(),
+            name: body.name()?.to_string(),
+        },
+        Message::Execute(body) => Received::Execute {
+            portal: body.portal()?.to_string(),
+            max_rows: body.max_rows(),
+        },
+        Message::Flush => Received::Flush,
+        Message::FunctionCall(body) => Received::FunctionCall {
+            oid: body.oid(),
+            args: body.args().map(|v| Ok(v.map(|v| v.to_vec()))).collect()?,
+            result_format: body.result_format(),
+        },
+        Message::Parse(body) => Received::Parse {
+            name: body.name()?.to_string(),
+            query: body.query()?.to_string(),
+            parameter_types: body.parameter_types().collect()?,
+        },
+        Message::Query(body) => Received::Query(body.query()?.to_string()),
+        Message::Sync => Received::Sync,
+        Message::Terminate => Received::Terminate,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "unexpected message",
+            ))
+        }
+    };
+
+    Ok(received)
+}
@@ -0,0 +1,514 @@
+//! A scriptable, in-process Postgres server for tests.
+//!
+//! The server speaks enough of the frontend/backend protocol for `tokio-postgres` and `postgres` clients to connect,
+//! authenticate, and run simple and extended queries. Rather than executing SQL, it looks up each query's text in a
+//! table of canned responses, and it records every message it receives so tests can make assertions about what the
+//! client sent.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use postgres_mock::{Builder, Column, Received, Response};
+//! use postgres_types::Type;
+//!
+//! # async fn f() -> std::io::Result<()> {
+//! let server = Builder::new()
+//!     .respond(
+//!         "SELECT name FROM users",
+//!         Response::rows(vec![Column::new("name", Type::TEXT)])
+//!             .row(vec![Some("alice")])
+//!             .row(vec![Some("bob")]),
+//!     )
+//!     .start()
+//!     .await?;
+//!
+//! // connect a client to `server.connection_string()` and run the query...
+//!
+//! assert!(server
+//!     .received()
+//!     .contains(&Received::Query("SELECT name FROM users".to_string())));
+//! # Ok(())
+//! # }
+//! ```
+#![warn(rust_2018_idioms, clippy::all, missing_docs)]
+
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::future::{self, AbortHandle};
+use parking_lot::Mutex;
+use postgres_protocol::Oid;
+use postgres_types::Type;
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+
+mod connection;
+mod scram;
+
+/// The authentication method the server requires of clients.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Auth {
+    /// Accept all clients without a password.
+    Trust,
+    /// Require the given password, sent as an MD5 hash.
+    Md5(String),
+    /// Require the given password, verified with SCRAM-SHA-256.
+    ScramSha256(String),
+}
+
+/// A column of the rows returned by a response.
+#[derive(Debug, Clone)]
+pub struct Column {
+    name: String,
+    type_: Type,
+}
+
+impl Column {
+    /// Creates a new column.
+    pub fn new(name: &str, type_: Type) -> Column {
+        Column {
+            name: name.to_string(),
+            type_,
+        }
+    }
+}
+
+/// A canned response to a query.
+///
+/// Row values are sent to the client as-is, so they must already be encoded in the format the client will expect: the
+/// text format for `simple_query`, and the binary format for `query` and its relatives.
+#[derive(Debug, Clone)]
+pub struct Response {
+    parameters: Vec<Type>,
+    columns: Vec<Column>,
+    rows: Vec<Vec<Option<Bytes>>>,
+    tag: Option<String>,
+    notices: Vec<String>,
+    notifications: Vec<(String, String)>,
+    error: Option<(String, String)>,
+    wait_for_cancel: bool,
+}
+
+impl Response {
+    fn new() -> Response {
+        Response {
+            parameters: vec![],
+            columns: vec![],
+            rows: vec![],
+            tag: None,
+            notices: vec![],
+            notifications: vec![],
+            error: None,
+            wait_for_cancel: false,
+        }
+    }
+
+    /// Creates a response to a statement which returns no rows, completing with the given command tag.
+    ///
+    /// The tag is the string Postgres reports when the statement finishes, like `INSERT 0 1` or `CREATE TABLE`.
+    pub fn command(tag: &str) -> Response {
+        let mut response = Response::new();
+        response.tag = Some(tag.to_string());
+        response
+    }
+
+    /// Creates a response returning rows with the given columns.
+    ///
+    /// Rows are added with the `row` method.
+    pub fn rows(columns: Vec<Column>) -> Response {
+        let mut response = Response::new();
+        response.columns = columns;
+        response
+    }
+
+    /// Creates a response which fails with an `ErrorResponse` carrying the given SQLSTATE code and message.
+    pub fn error(code: &str, message: &str) -> Response {
+        let mut response = Response::new();
+        response.error = Some((code.to_string(), message.to_string()));
+        response
+    }
+
+    /// Creates a response which doesn't complete until the client cancels the query.
+    ///
+    /// The query then fails with a `query_canceled` error, just as a real server's would. Note that the server does
+    /// not discard cancellation requests which arrive while the connection is idle, so a request sent before the
+    /// query starts will still cancel it.
+    pub fn wait_for_cancel() -> Response {
+        let mut response = Response::new();
+        response.wait_for_cancel = true;
+        response
+    }
+
+    /// Adds a row to the response.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values doesn't match the number of columns.
+    pub fn row<I, T>(mut self, values: I) -> Response
+    where
+        I: IntoIterator<Item = Option<T>>,
+        T: AsRef<[u8]>,
+    {
+        let row = values
+            .into_iter()
+            .map(|v| v.map(|v| Bytes::copy_from_slice(v.as_ref())))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            row.len(),
+            self.columns.len(),
+            "row has the wrong number of values"
+        );
+        self.rows.push(row);
+        self
+    }
+
+    /// Sets the types of the query's parameters, reported when a client prepares it without specifying them.
+    pub fn parameters(mut self, types: Vec<Type>) -> Response {
+        self.parameters = types;
+        self
+    }
+
+    /// Sends a notice with the given message before the response.
+    pub fn notice(mut self, message: &str) -> Response {
+        self.notices.push(message.to_string());
+        self
+    }
+
+    /// Sends a notification on the given channel before the response.
+    pub fn notification(mut self, channel: &str, payload: &str) -> Response {
+        self.notifications
+            .push((channel.to_string(), payload.to_string()));
+        self
+    }
+}
+
+/// A message received by the server.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Received {
+    /// A new connection's startup parameters, such as `user` and `application_name`.
+    Startup(Vec<(String, String)>),
+    /// A request to cancel the query running on another connection.
+    CancelRequest {
+        /// The process ID of the connection.
+        process_id: i32,
+        /// The connection's secret key.
+        secret_key: i32,
+    },
+    /// A simple query.
+    Query(String),
+    /// A request to prepare a statement.
+    Parse {
+        /// The name of the statement.
+        name: String,
+        /// The text of the query.
+        query: String,
+        /// The parameter types specified by the client, where `0` leaves a type unspecified.
+        parameter_types: Vec<Oid>,
+    },
+    /// A request to bind parameters to a prepared statement, creating a portal.
+    Bind {
+        /// The name of the portal.
+        portal: String,
+        /// The name of the statement.
+        statement: String,
+        /// The parameter values, or `None` for `NULL`s.
+        values: Vec<Option<Vec<u8>>>,
+        /// The formats the client requested for the result columns.
+        result_formats: Vec<i16>,
+    },
+    /// A request to describe a statement (`b'S'`) or portal (`b'P'`).
+    Describe {
+        /// The kind of object to describe.
+        variant: u8,
+        /// The name of the object.
+        name: String,
+    },
+    /// A request to execute a portal.
+    Execute {
+        /// The name of the portal.
+        portal: String,
+        /// The maximum number of rows to return, or `0` for all of them.
+        max_rows: i32,
+    },
+    /// A request to close a statement (`b'S'`) or portal (`b'P'`).
+    Close {
+        /// The kind of object to close.
+        variant: u8,
+        /// The name of the object.
+        name: String,
+    },
+    /// A fast-path function call.
+    FunctionCall {
+        /// The OID of the function.
+        oid: Oid,
+        /// The arguments, or `None` for `NULL`s.
+        args: Vec<Option<Vec<u8>>>,
+        /// The format the client requested for the result.
+        result_format: i16,
+    },
+    /// A `Sync` message, ending an extended query.
+    Sync,
+    /// A `Flush` message.
+    Flush,
+    /// A chunk of `COPY` data.
+    CopyData(Vec<u8>),
+    /// The end of a `COPY`.
+    CopyDone,
+    /// A failed `COPY`, with the client's error message.
+    CopyFail(String),
+    /// A request to close the connection.
+    Terminate,
+}
+
+/// A builder for `MockServer`s.
+#[derive(Debug, Clone)]
+pub struct Builder {
+    auth: Auth,
+    parameters: Vec<(String, String)>,
+    responses: HashMap<String, Response>,
+}
+
+impl Default for Builder {
+    fn default() -> Builder {
+        Builder::new()
+    }
+}
+
+impl Builder {
+    /// Creates a new builder for a server which trusts all clients and has no canned responses.
+    pub fn new() -> Builder {
+        Builder {
+            auth: Auth::Trust,
+            parameters: vec![
+                ("server_version".to_string(), "13.0".to_string()),
+                ("server_encoding".to_string(), "UTF8".to_string()),
+                ("client_encoding".to_string(), "UTF8".to_string()),
+                ("DateStyle".to_string(), "ISO, MDY".to_string()),
+                ("integer_datetimes".to_string(), "on".to_string()),
+            ],
+            responses: HashMap::new(),
+        }
+    }
+
+    /// Sets the authentication method required of clients.
+    ///
+    /// Defaults to `Auth::Trust`.
+    pub fn auth(mut self, auth: Auth) -> Builder {
+        self.auth = auth;
+        self
+    }
+
+    /// Sets a parameter reported to clients when they connect, overriding any earlier value.
+    ///
+    /// By default, the server reports `server_version`, `server_encoding`, `client_encoding`, `DateStyle` and
+    /// `integer_datetimes`.
+    pub fn parameter(mut self, name: &str, value: &str) -> Builder {
+        self.parameters.retain(|(n, _)| n != name);
+        self.parameters.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sets the response to a query.
+    ///
+    /// The query text must match exactly. Queries without a response fail with a `syntax_error`.
+    pub fn respond(mut self, query: &str, response: Response) -> Builder {
+        self.responses.insert(query.to_string(), response);
+        self
+    }
+
+    /// Starts a server listening on a random port of the loopback interface.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub async fn start(self) -> io::Result<MockServer> {
+        let mut listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+        let addr = listener.local_addr()?;
+        let shared = self.shared();
+
+        let accept = {
+            let shared = shared.clone();
+            async move {
+                loop {
+                    if let Ok((stream, _)) = listener.accept().await {
+                        let _ = stream.set_nodelay(true);
+                        tokio::spawn(connection::handle(stream, shared.clone()));
+                    }
+                }
+            }
+        };
+
+        Ok(MockServer::spawn(shared, Listen::Tcp(addr), accept))
+    }
+
+    /// Starts a server listening on a Unix socket for the given port in a directory.
+    ///
+    /// Clients connect to the server by using the directory as their host. The socket file is removed when the server
+    /// is dropped. Must be called from within a Tokio runtime.
+    ///
+    /// Only available on Unix platforms.
+    #[cfg(unix)]
+    pub async fn start_unix<P>(self, dir: P, port: u16) -> io::Result<MockServer>
+    where
+        P: AsRef<Path>,
+    {
+        let dir = dir.as_ref().to_path_buf();
+        let path = dir.join(format!(".s.PGSQL.{}", port));
+        let mut listener = UnixListener::bind(&path)?;
+        let shared = self.shared();
+
+        let accept = {
+            let shared = shared.clone();
+            async move {
+                loop {
+                    if let Ok((stream, _)) = listener.accept().await {
+                        tokio::spawn(connection::handle(stream, shared.clone()));
+                    }
+                }
+            }
+        };
+
+        Ok(MockServer::spawn(
+            shared,
+            Listen::Unix { dir, path, port },
+            accept,
+        ))
+    }
+
+    fn shared(self) -> Arc<Shared> {
+        Arc::new(Shared {
+            auth: self.auth,
+            parameters: self.parameters,
+            responses: self.responses,
+            received: Mutex::new(vec![]),
+            backends: Mutex::new(HashMap::new()),
+            next_process_id: Mutex::new(1),
+        })
+    }
+}
+
+enum Listen {
+    Tcp(SocketAddr),
+    #[cfg(unix)]
+    Unix {
+        dir: PathBuf,
+        path: PathBuf,
+        port: u16,
+    },
+}
+
+pub(crate) struct Shared {
+    pub(crate) auth: Auth,
+    pub(crate) parameters: Vec<(String, String)>,
+    pub(crate) responses: HashMap<String, Response>,
+    received: Mutex<Vec<Received>>,
+    // process ID -> (secret key, cancellation channel)
+    backends: Mutex<HashMap<i32, (i32, mpsc::UnboundedSender<()>)>>,
+    next_process_id: Mutex<i32>,
+}
+
+impl Shared {
+    pub(crate) fn record(&self, message: Received) {
+        self.received.lock().push(message);
+    }
+
+    pub(crate) fn register(&self) -> (i32, i32, mpsc::UnboundedReceiver<()>) {
+        let process_id = {
+            let mut next = self.next_process_id.lock();
+            let process_id = *next;
+            *next += 1;
+            process_id
+        };
+        let secret_key = process_id.wrapping_mul(7919) ^ 0x5eed;
+        let (tx, rx) = mpsc::unbounded();
+        self.backends.lock().insert(process_id, (secret_key, tx));
+        (process_id, secret_key, rx)
+    }
+
+    pub(crate) fn unregister(&self, process_id: i32) {
+        self.backends.lock().remove(&process_id);
+    }
+
+    pub(crate) fn cancel(&self, process_id: i32, secret_key: i32) {
+        if let Some((key, tx)) = self.backends.lock().get(&process_id) {
+            if *key == secret_key {
+                let _ = tx.unbounded_send(());
+            }
+        }
+    }
+}
+
+/// A running mock server.
+///
+/// The server stops accepting connections when this is dropped, though connections which are already open are served
+/// until the client closes them.
+pub struct MockServer {
+    shared: Arc<Shared>,
+    listen: Listen,
+    abort: AbortHandle,
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.abort.abort();
+        #[cfg(unix)]
+        {
+            if let Listen::Unix { path, .. } = &self.listen {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+}
+
+impl MockServer {
+    fn spawn<F>(shared: Arc<Shared>, listen: Listen, accept: F) -> MockServer
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let (accept, abort) = future::abortable(accept);
+        tokio::spawn(accept);
+        MockServer {
+            shared,
+            listen,
+            abort,
+        }
+    }
+
+    /// Returns the port the server is listening on.
+    pub fn port(&self) -> u16 {
+        match &self.listen {
+            Listen::Tcp(addr) => addr.port(),
+            #[cfg(unix)]
+            Listen::Unix { port, .. } => *port,
+        }
+    }
+
+    /// Returns a connection string with the `host` and `port` of the server.
+    ///
+    /// The server accepts any user, but clients must still specify one.
+    pub fn connection_string(&self) -> String {
+        match &self.listen {
+            Listen::Tcp(addr) => format!("host={} port={}", addr.ip(), addr.port()),
+            #[cfg(unix)]
+            Listen::Unix { dir, port, .. } => format!("host={} port={}", dir.display(), port),
+        }
+    }
+
+    /// Returns the messages received by the server so far, across all connections, in the order they arrived.
+    ///
+    /// Authentication messages are not included.
+    pub fn received(&self) -> Vec<Received> {
+        self.shared.received.lock().clone()
+    }
+
+    /// Returns the messages received by the server so far, and clears its record of them.
+    pub fn take_received(&self) -> Vec<Received> {
+        std::mem::take(&mut *self.shared.received.lock())
+    }
+}
@@ -0,0 +1,156 @@
+//! The server side of a SCRAM-SHA-256 exchange.
+
+use hmac::{Hmac, Mac, NewMac};
+use sha2::digest::FixedOutput;
+use sha2::{Digest, Sha256};
+use std::io;
+use std::str;
+
+const SALT: &[u8] = b"postgres-mock salt";
+const ITERATIONS: u32 = 4096;
+const SERVER_NONCE: &str = "cG9zdGdyZXMtbW9jaw";
+
+// this mirrors the normalization done by the client
+fn normalize(pass: &[u8]) -> Vec<u8> {
+    let pass = match str::from_utf8(pass) {
+        Ok(pass) => pass,
+        Err(_) => return pass.to_vec(),
+    };
+
+    match stringprep::saslprep(pass) {
+        Ok(pass) => pass.into_owned().into_bytes(),
+        Err(_) => pass.as_bytes().to_vec(),
+    }
+}
+
+fn hi(str: &[u8], salt: &[u8], i: u32) -> [u8; 32] {
+    let mut hmac = Hmac::<Sha256>::new_varkey(str).expect("HMAC is able to accept all key sizes");
+    hmac.update(salt);
+    hmac.update(&[0, 0, 0, 1]);
+    let mut prev = hmac.finalize().into_bytes();
+
+    let mut hi = prev;
+
+    for _ in 1..i {
+        let mut hmac = Hmac::<Sha256>::new_varkey(str).expect("already checked above");
+        hmac.update(&prev);
+        prev = hmac.finalize().into_bytes();
+
+        for (hi, prev) in hi.iter_mut().zip(prev) {
+            *hi ^= prev;
+        }
+    }
+
+    hi.into()
+}
+
+fn hmac(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut hmac = Hmac::<Sha256>::new_varkey(key).expect("HMAC is able to accept all key sizes");
+    hmac.update(message);
+    hmac.finalize().into_bytes().to_vec()
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+pub struct ScramSha256 {
+    salted_password: [u8; 32],
+    gs2_header: String,
+    nonce: String,
+    auth_message: String,
+}
+
+impl ScramSha256 {
+    /// Processes the client-first message, returning the server-first message.
+    pub fn new(password: &str, client_first: &[u8]) -> io::Result<(ScramSha256, String)> {
+        let client_first =
+            str::from_utf8(client_first).map_err(|_| invalid("invalid client-first message"))?;
+
+        // gs2-header is `<cbind-flag>,[authzid],`
+        let mut parts = client_first.splitn(3, ',');
+        let cbind_flag = parts.next().unwrap_or("");
+        let authzid = parts.next().ok_or_else(|| invalid("missing GS2 header"))?;
+        let bare = parts.next().ok_or_else(|| invalid("missing GS2 header"))?;
+        if cbind_flag.starts_with('p') {
+            return Err(invalid("channel binding is not supported"));
+        }
+        let gs2_header = format!("{},{},", cbind_flag, authzid);
+
+        let client_nonce = bare
+            .split(',')
+            .find(|s| s.starts_with("r="))
+            .map(|s| &s[2..])
+            .ok_or_else(|| invalid("missing client nonce"))?;
+        let nonce = format!("{}{}", client_nonce, SERVER_NONCE);
+
+        let server_first = format!("r={},s={},i={}", nonce, base64::encode(SALT), ITERATIONS);
+
+        let scram = ScramSha256 {
+            salted_password: hi(&normalize(password.as_bytes()), SALT, ITERATIONS),
+            gs2_header,
+            nonce,
+            auth_message: format!("{},{}", bare, server_first),
+        };
+        Ok((scram, server_first))
+    }
+
+    /// Verifies the client-final message, returning the server-final message.
+    pub fn finish(mut self, client_final: &[u8]) -> io::Result<String> {
+        let client_final =
+            str::from_utf8(client_final).map_err(|_| invalid("invalid client-final message"))?;
+
+        let idx = client_final
+            .rfind(",p=")
+            .ok_or_else(|| invalid("missing client proof"))?;
+        let without_proof = &client_final[..idx];
+        let proof = base64::decode(&client_final[idx + 3..])
+            .map_err(|_| invalid("invalid client proof"))?;
+
+        let mut cbind = None;
+        let mut nonce = None;
+        for part in without_proof.split(',') {
+            let mut it = part.splitn(2, '=');
+            match (it.next(), it.next()) {
+                (Some("c"), Some(value)) => cbind = Some(value),
+                (Some("r"), Some(value)) => nonce = Some(value),
+                _ => {}
+            }
+        }
+        if cbind.and_then(|c| base64::decode(c).ok()) != Some(self.gs2_header.clone().into_bytes())
+        {
+            return Err(invalid("invalid channel binding"));
+        }
+        if nonce != Some(&self.nonce) {
+            return Err(invalid("invalid nonce"));
+        }
+
+        self.auth_message.push(',');
+        self.auth_message.push_str(without_proof);
+
+        let client_key = hmac(&self.salted_password, b"Client Key");
+        let mut hash = Sha256::default();
+        hash.update(&client_key);
+        let stored_key = hash.finalize_fixed();
+
+        let client_signature = hmac(&stored_key, self.auth_message.as_bytes());
+        if proof.len() != client_signature.len() {
+            return Err(invalid("invalid client proof"));
+        }
+        let expected = proof
+            .iter()
+            .zip(&client_signature)
+            .map(|(p, s)| p ^ s)
+            .collect::<Vec<_>>();
+        if expected != client_key {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "password authentication failed",
+            ));
+        }
+
+        let server_key = hmac(&self.salted_password, b"Server Key");
+        let server_signature = hmac(&server_key, self.auth_message.as_bytes());
+        Ok(format!("v={}", base64::encode(&server_signature)))
+    }
+}
@@ -45,16 +45,39 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! ```no_run
+//! use postgres_native_tls::MakeTlsConnector;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+//! let config = "host=db.example.com user=postgres sslmode=verify-full sslrootcert=database_cert.pem"
+//!     .parse::<tokio_postgres::Config>()?;
+//! let connector = MakeTlsConnector::from_config(&config)?;
+//!
+//! let connect_future = config.connect(connector);
+//!
+//! // ...
+//! # Ok(())
+//! # }
+//! ```
 #![doc(html_root_url = "https://docs.rs/postgres-native-tls/0.3")]
 #![warn(rust_2018_idioms, clippy::all, missing_docs)]
 
 use bytes::{Buf, BufMut};
+#[cfg(feature = "runtime")]
+use native_tls::{Certificate, Identity};
+#[cfg(feature = "runtime")]
+use std::error::Error;
 use std::future::Future;
 use std::io;
 use std::mem::MaybeUninit;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+#[cfg(feature = "runtime")]
+use std::{fs, str};
 use tokio::io::{AsyncRead, AsyncWrite};
+#[cfg(feature = "runtime")]
+use tokio_postgres::config::{Config, SslMode};
 use tokio_postgres::tls;
 #[cfg(feature = "runtime")]
 use tokio_postgres::tls::MakeTlsConnect;
@@ -63,6 +86,9 @@ use tokio_postgres::tls::{ChannelBinding, TlsConnect};
 #[cfg(test)]
 mod test;
 
+#[cfg(feature = "runtime")]
+const ALPN_PROTOCOL: &str = "postgresql";
+
 /// A `MakeTlsConnect` implementation using the `native-tls` crate.
 ///
 /// Requires the `runtime` Cargo feature (enabled by default).
@@ -73,9 +99,75 @@ pub struct MakeTlsConnector(native_tls::TlsConnector);
 #[cfg(feature = "runtime")]
 impl MakeTlsConnector {
     /// Creates a new connector.
+    ///
+    /// Connections using `sslnegotiation=direct` always fail, since `tokio-tls` doesn't expose the ALPN protocol
+    /// selected by the server, and direct negotiation is only secure if the protocol is checked.
     pub fn new(connector: native_tls::TlsConnector) -> MakeTlsConnector {
         MakeTlsConnector(connector)
     }
+
+    /// Creates a connector from the TLS settings of a configuration.
+    ///
+    /// The `sslrootcert` file is loaded in PEM format. If it is provided, only the certificate authorities it contains
+    /// are trusted. Otherwise, the system's trusted certificate authorities are used. `native-tls` can't check
+    /// certificate revocation lists, so an error is returned if `sslcrl` is set.
+    ///
+    /// If both `sslcert` and `sslkey` are set, they are loaded in PEM format, and the key must be an unencrypted
+    /// PKCS#8 key. If only `sslcert` is set, it is loaded as a DER-encoded PKCS#12 archive containing both the
+    /// certificate and its key, decrypted with `sslpassword`.
+    ///
+    /// The server's certificate is verified according to `sslmode`, following libpq's rules: `verify-full` checks
+    /// both the certificate chain and that the certificate matches the host being connected to, as a hostname or IP
+    /// address depending on the host. `verify-ca` checks only the certificate chain. The `prefer` and `require` modes
+    /// don't verify the certificate at all unless a root certificate file is provided, in which case they behave like
+    /// `verify-ca`.
+    pub fn from_config(config: &Config) -> Result<MakeTlsConnector, Box<dyn Error + Sync + Send>> {
+        let mut builder = native_tls::TlsConnector::builder();
+        builder.request_alpns(&[ALPN_PROTOCOL]);
+
+        if let Some(path) = config.get_ssl_root_cert() {
+            builder.disable_built_in_roots(true);
+            for cert in Certificate::stack_from_pem(&fs::read(path)?)? {
+                builder.add_root_certificate(cert);
+            }
+        }
+
+        if config.get_ssl_crl().is_some() {
+            return Err("sslcrl is not supported by native-tls".into());
+        }
+
+        match (config.get_ssl_cert(), config.get_ssl_key()) {
+            (Some(cert), Some(key)) => {
+                if config.get_ssl_password().is_some() {
+                    return Err("sslpassword is only supported with PKCS#12 identities".into());
+                }
+                let identity = Identity::from_pkcs8(&fs::read(cert)?, &fs::read(key)?)?;
+                builder.identity(identity);
+            }
+            (Some(archive), None) => {
+                let password = str::from_utf8(config.get_ssl_password().unwrap_or(&[]))?;
+                let identity = Identity::from_pkcs12(&fs::read(archive)?, password)?;
+                builder.identity(identity);
+            }
+            (None, Some(_)) => return Err("sslkey requires sslcert to be set".into()),
+            (None, None) => {}
+        }
+
+        match config.get_ssl_mode() {
+            SslMode::VerifyFull => {}
+            SslMode::VerifyCa => {
+                builder.danger_accept_invalid_hostnames(true);
+            }
+            _ if config.get_ssl_root_cert().is_some() => {
+                builder.danger_accept_invalid_hostnames(true);
+            }
+            _ => {
+                builder.danger_accept_invalid_certs(true);
+            }
+        }
+
+        Ok(MakeTlsConnector::new(builder.build()?))
+    }
 }
 
 #[cfg(feature = "runtime")]
@@ -199,4 +291,9 @@ where
         // FIXME https://github.com/tokio-rs/tokio/issues/1383
         ChannelBinding::none()
     }
+
+    // FIXME https://github.com/tokio-rs/tokio/issues/1383
+    fn alpn_protocol(&self) -> Option<Vec<u8>> {
+        None
+    }
 }
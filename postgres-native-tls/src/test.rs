@@ -1,11 +1,14 @@
 use futures::FutureExt;
 use native_tls::{self, Certificate};
-use tokio::net::TcpStream;
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpListener, TcpStream};
 use tokio_postgres::tls::TlsConnect;
 
 #[cfg(feature = "runtime")]
 use crate::MakeTlsConnector;
 use crate::TlsConnector;
+#[cfg(feature = "runtime")]
+use tokio_postgres::tls::MakeTlsConnect;
 
 async fn smoke_test<T>(s: &str, tls: T)
 where
@@ -72,6 +75,51 @@ async fn scram_user() {
     .await;
 }
 
+// Accepts a single connection and returns the first TLS record sent by the client, which should be its ClientHello.
+async fn direct_client_hello<T>(tls: T) -> Vec<u8>
+where
+    T: TlsConnect<TcpStream>,
+    T::Stream: 'static + Send,
+{
+    let mut listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0; 5];
+        stream.read_exact(&mut buf).await.unwrap();
+        let len = u16::from_be_bytes([buf[3], buf[4]]) as usize;
+        buf.resize(5 + len, 0);
+        stream.read_exact(&mut buf[5..]).await.unwrap();
+        buf
+    });
+
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let builder = "user=ssl_user dbname=postgres sslmode=require sslnegotiation=direct"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    builder.connect_raw(stream, tls).await.err().unwrap();
+
+    server.await.unwrap()
+}
+
+fn assert_direct_client_hello(record: &[u8]) {
+    // a handshake record rather than an SSLRequest, requesting the `postgresql` ALPN protocol
+    assert_eq!(record[0], 0x16);
+    assert!(record.windows(11).any(|w| w == b"\x0apostgresql"));
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn from_config_direct_ssl_negotiation() {
+    let config = "sslmode=require sslnegotiation=direct"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    let mut connector = MakeTlsConnector::from_config(&config).unwrap();
+    let tls = MakeTlsConnect::<TcpStream>::make_tls_connect(&mut connector, "localhost").unwrap();
+
+    assert_direct_client_hello(&direct_client_hello(tls).await);
+}
+
 #[tokio::test]
 #[cfg(feature = "runtime")]
 async fn runtime() {
@@ -98,3 +146,100 @@ async fn runtime() {
     assert_eq!(rows.len(), 1);
     assert_eq!(rows[0].get::<_, i32>(0), 1);
 }
+
+#[cfg(feature = "runtime")]
+async fn from_config_test(s: &str) -> Result<(), tokio_postgres::Error> {
+    let config = s.parse::<tokio_postgres::Config>().unwrap();
+    let connector = MakeTlsConnector::from_config(&config).unwrap();
+
+    let (client, connection) = config.connect(connector).await?;
+    let connection = connection.map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    let row = client.query_one("SELECT 1::INT4", &[]).await?;
+    assert_eq!(row.get::<_, i32>(0), 1);
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn from_config_verify_full() {
+    from_config_test(
+        "host=localhost port=5433 user=ssl_user dbname=postgres sslmode=verify-full
+         sslrootcert=../test/server.crt",
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn from_config_verify_full_wrong_host() {
+    from_config_test(
+        "host=127.0.0.1 port=5433 user=ssl_user dbname=postgres sslmode=verify-full
+         sslrootcert=../test/server.crt",
+    )
+    .await
+    .err()
+    .unwrap();
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn from_config_verify_full_multiple_hosts() {
+    // the certificate doesn't match the IP address, so only the second host can be connected to
+    from_config_test(
+        "host=127.0.0.1,localhost port=5433 user=ssl_user dbname=postgres sslmode=verify-full
+         sslrootcert=../test/server.crt",
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn from_config_pkcs12() {
+    from_config_test(
+        "host=localhost port=5433 user=ssl_user dbname=postgres sslmode=require
+         sslcert=../test/client.p12 sslpassword=password",
+    )
+    .await
+    .unwrap();
+}
+
+#[test]
+#[cfg(feature = "runtime")]
+fn from_config_pkcs12_wrong_password() {
+    let config = "sslcert=../test/client.p12 sslpassword=wrong"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    MakeTlsConnector::from_config(&config).err().unwrap();
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn from_config_verify_ca() {
+    from_config_test(
+        "host=127.0.0.1 port=5433 user=ssl_user dbname=postgres sslmode=verify-ca
+         sslrootcert=../test/server.crt",
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn from_config_verify_ca_untrusted() {
+    from_config_test("host=localhost port=5433 user=ssl_user dbname=postgres sslmode=verify-ca")
+        .await
+        .err()
+        .unwrap();
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn from_config_require_unverified() {
+    from_config_test("host=127.0.0.1 port=5433 user=ssl_user dbname=postgres sslmode=require")
+        .await
+        .unwrap();
+}
@@ -39,6 +39,21 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! ```no_run
+//! use postgres_openssl::MakeTlsConnector;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+//! let config = "host=db.example.com user=postgres sslmode=verify-full sslrootcert=database_cert.pem"
+//!     .parse::<tokio_postgres::Config>()?;
+//! let connector = MakeTlsConnector::from_config(&config)?;
+//!
+//! let connect_future = config.connect(connector);
+//!
+//! // ...
+//! # Ok(())
+//! # }
+//! ```
 #![doc(html_root_url = "https://docs.rs/postgres-openssl/0.3")]
 #![warn(rust_2018_idioms, clippy::all, missing_docs)]
 
@@ -48,9 +63,19 @@ use openssl::error::ErrorStack;
 use openssl::hash::MessageDigest;
 use openssl::nid::Nid;
 #[cfg(feature = "runtime")]
-use openssl::ssl::SslConnector;
+use openssl::pkey::PKey;
 use openssl::ssl::{ConnectConfiguration, SslRef};
+#[cfg(feature = "runtime")]
+use openssl::ssl::{SslConnector, SslFiletype, SslMethod, SslVerifyMode};
+#[cfg(feature = "runtime")]
+use openssl::x509::store::{X509Lookup, X509StoreBuilder};
+#[cfg(feature = "runtime")]
+use openssl::x509::verify::X509VerifyFlags;
+#[cfg(feature = "runtime")]
+use std::error::Error;
 use std::fmt::Debug;
+#[cfg(feature = "runtime")]
+use std::fs;
 use std::future::Future;
 use std::io;
 use std::mem::MaybeUninit;
@@ -60,6 +85,8 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_openssl::{HandshakeError, SslStream};
+#[cfg(feature = "runtime")]
+use tokio_postgres::config::{Config, SslMode};
 use tokio_postgres::tls;
 #[cfg(feature = "runtime")]
 use tokio_postgres::tls::MakeTlsConnect;
@@ -68,6 +95,10 @@ use tokio_postgres::tls::{ChannelBinding, TlsConnect};
 #[cfg(test)]
 mod test;
 
+// The `postgresql` ALPN protocol, in wire format.
+#[cfg(feature = "runtime")]
+const ALPN_PROTOCOLS: &[u8] = b"\x0apostgresql";
+
 /// A `MakeTlsConnect` implementation using the `openssl` crate.
 ///
 /// Requires the `runtime` Cargo feature (enabled by default).
@@ -88,6 +119,70 @@ impl MakeTlsConnector {
         }
     }
 
+    /// Creates a connector from the TLS settings of a configuration.
+    ///
+    /// The `sslrootcert`, `sslcert`, `sslkey` and `sslcrl` files are loaded in PEM format. An encrypted private key is
+    /// decrypted with `sslpassword`. If a root certificate file is provided, only the certificate authorities it
+    /// contains are trusted. Otherwise, the system's trusted certificate authorities are used.
+    ///
+    /// The server's certificate is verified according to `sslmode`, following libpq's rules: `verify-full` checks
+    /// both the certificate chain and that the certificate matches the host being connected to, as a hostname or IP
+    /// address depending on the host. `verify-ca` checks only the certificate chain. The `prefer` and `require` modes
+    /// don't verify the certificate at all unless a root certificate file is provided, in which case they behave like
+    /// `verify-ca`.
+    pub fn from_config(config: &Config) -> Result<MakeTlsConnector, Box<dyn Error + Sync + Send>> {
+        let mut builder = SslConnector::builder(SslMethod::tls())?;
+
+        if let Some(path) = config.get_ssl_root_cert() {
+            let mut store = X509StoreBuilder::new()?;
+            store
+                .add_lookup(X509Lookup::file())?
+                .load_cert_file(path, SslFiletype::PEM)?;
+            builder.set_cert_store(store.build());
+        }
+
+        if let Some(path) = config.get_ssl_crl() {
+            let store = builder.cert_store_mut();
+            store
+                .add_lookup(X509Lookup::file())?
+                .load_crl_file(path, SslFiletype::PEM)?;
+            store.set_flags(X509VerifyFlags::CRL_CHECK | X509VerifyFlags::CRL_CHECK_ALL)?;
+        }
+
+        if let Some(path) = config.get_ssl_cert() {
+            builder.set_certificate_chain_file(path)?;
+        }
+        if let Some(path) = config.get_ssl_key() {
+            // Always provide a passphrase so OpenSSL doesn't prompt for one on the terminal.
+            let passphrase = config.get_ssl_password().unwrap_or(&[]);
+            let key = PKey::private_key_from_pem_passphrase(&fs::read(path)?, passphrase)?;
+            builder.set_private_key(&key)?;
+            if config.get_ssl_cert().is_some() {
+                builder.check_private_key()?;
+            }
+        }
+
+        let verify_hostname = match config.get_ssl_mode() {
+            SslMode::VerifyFull => true,
+            SslMode::VerifyCa => false,
+            _ if config.get_ssl_root_cert().is_some() => false,
+            _ => {
+                builder.set_verify(SslVerifyMode::NONE);
+                false
+            }
+        };
+
+        let mut connector = MakeTlsConnector::new(builder.build());
+        if !verify_hostname {
+            connector.set_callback(|ssl, _| {
+                ssl.set_verify_hostname(false);
+                Ok(())
+            });
+        }
+
+        Ok(connector)
+    }
+
     /// Sets a callback used to apply per-connection configuration.
     ///
     /// The the callback is provided the domain name along with the `ConnectConfiguration`.
@@ -110,6 +205,7 @@ where
 
     fn make_tls_connect(&mut self, domain: &str) -> Result<TlsConnector, ErrorStack> {
         let mut ssl = self.connector.configure()?;
+        ssl.set_alpn_protos(ALPN_PROTOCOLS)?;
         (self.config)(&mut ssl, domain)?;
         Ok(TlsConnector::new(ssl, domain))
     }
@@ -123,6 +219,10 @@ pub struct TlsConnector {
 
 impl TlsConnector {
     /// Creates a new connector configured to connect to the specified domain.
+    ///
+    /// Connections using `sslnegotiation=direct` fail unless the configuration requests the `postgresql` ALPN
+    /// protocol with `SslRef::set_alpn_protos` and the server selects it. `MakeTlsConnector` requests it
+    /// automatically.
     pub fn new(ssl: ConnectConfiguration, domain: &str) -> TlsConnector {
         TlsConnector {
             ssl,
@@ -223,6 +323,10 @@ where
             None => ChannelBinding::none(),
         }
     }
+
+    fn alpn_protocol(&self) -> Option<Vec<u8>> {
+        self.0.ssl().selected_alpn_protocol().map(|p| p.to_vec())
+    }
 }
 
 fn tls_server_end_point(ssl: &SslRef) -> Option<Vec<u8>> {
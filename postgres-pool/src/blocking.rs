@@ -0,0 +1,409 @@
+//! A connection pool for the blocking `postgres` client.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use postgres::NoTls;
+//! use postgres_pool::Builder;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = "host=localhost user=postgres".parse::<postgres::Config>()?;
+//! let pool = Builder::new().max_size(4).build_blocking(config, NoTls)?;
+//!
+//! let mut client = pool.get()?;
+//! let row = client.query_one("SELECT 1::INT4", &[])?;
+//! let value: i32 = row.get(0);
+//! # Ok(())
+//! # }
+//! ```
+use crate::{Builder, Error, RESET_QUERY};
+use log::{debug, error};
+use parking_lot::{Condvar, Mutex};
+use postgres::Client;
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
+use tokio_postgres::Socket;
+
+struct Conn {
+    client: Client,
+    created: Instant,
+}
+
+struct Idle {
+    conn: Conn,
+    since: Instant,
+}
+
+struct State {
+    idle: VecDeque<Idle>,
+    // The number of open connections, including those which are checked out or still being established.
+    size: usize,
+    // Whether a thread is opening connections to bring the pool back up to its minimum size.
+    replenishing: bool,
+}
+
+struct Shared {
+    builder: Builder,
+    state: Mutex<State>,
+    cond: Condvar,
+}
+
+impl Shared {
+    fn min_size(&self) -> usize {
+        self.builder.min_size.min(self.builder.max_size)
+    }
+
+    fn expired(&self, conn: &Conn, now: Instant) -> bool {
+        match self.builder.max_lifetime {
+            Some(max_lifetime) => now.duration_since(conn.created) >= max_lifetime,
+            None => false,
+        }
+    }
+
+    fn put(&self, conn: Conn) {
+        self.state.lock().idle.push_back(Idle {
+            conn,
+            since: Instant::now(),
+        });
+        self.cond.notify_one();
+    }
+
+    fn discard(&self, conn: Conn) {
+        self.state.lock().size -= 1;
+        self.cond.notify_one();
+        drop(conn);
+    }
+
+    fn check(&self, conn: &mut Conn) -> bool {
+        if conn.client.is_closed() || self.expired(conn, Instant::now()) {
+            return false;
+        }
+
+        match &self.builder.test_query {
+            Some(query) => match conn.client.simple_query(query) {
+                Ok(_) => true,
+                Err(e) => {
+                    debug!("discarding connection which failed its test query: {}", e);
+                    false
+                }
+            },
+            None => true,
+        }
+    }
+
+    // There's no background thread to close connections, so this runs whenever a connection is requested.
+    fn reap(&self, state: &mut State) -> Vec<Conn> {
+        let now = Instant::now();
+        let min_size = self.min_size();
+        let mut reaped = vec![];
+
+        let mut i = 0;
+        while i < state.idle.len() {
+            let idle = &state.idle[i];
+            let close = idle.conn.client.is_closed()
+                || self.expired(&idle.conn, now)
+                || match self.builder.idle_timeout {
+                    Some(idle_timeout) => {
+                        now.duration_since(idle.since) >= idle_timeout && state.size > min_size
+                    }
+                    None => false,
+                };
+            if close {
+                reaped.push(state.idle.remove(i).unwrap().conn);
+                state.size -= 1;
+            } else {
+                i += 1;
+            }
+        }
+
+        reaped
+    }
+}
+
+/// A pool of blocking `postgres` clients.
+///
+/// The pool is reference counted, so cloning it produces another handle to the same set of connections. Unlike the
+/// asynchronous pool, it has no background task: expired and surplus idle connections are closed the next time a
+/// connection is requested, and a thread is then started to refill the pool to its minimum size.
+pub struct Pool<T> {
+    shared: Arc<Shared>,
+    config: Arc<postgres::Config>,
+    tls: T,
+}
+
+impl<T> Clone for Pool<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Pool<T> {
+        Pool {
+            shared: self.shared.clone(),
+            config: self.config.clone(),
+            tls: self.tls.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Pool<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Pool")
+            .field("builder", &self.shared.builder)
+            .field("size", &self.size())
+            .field("idle", &self.idle())
+            .finish()
+    }
+}
+
+impl<T> Pool<T>
+where
+    T: MakeTlsConnect<Socket> + Clone + 'static + Send,
+    T::TlsConnect: Send,
+    T::Stream: Send,
+    <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    pub(crate) fn new(
+        builder: Builder,
+        config: postgres::Config,
+        tls: T,
+    ) -> Result<Pool<T>, Error> {
+        let pool = Pool {
+            shared: Arc::new(Shared {
+                builder,
+                state: Mutex::new(State {
+                    idle: VecDeque::new(),
+                    size: 0,
+                    replenishing: false,
+                }),
+                cond: Condvar::new(),
+            }),
+            config: Arc::new(config),
+            tls,
+        };
+
+        for _ in 0..pool.shared.min_size() {
+            pool.shared.state.lock().size += 1;
+            let conn = pool.connect(None)?;
+            pool.shared.put(conn);
+        }
+
+        Ok(pool)
+    }
+
+    // The caller must have already counted the connection in the pool's size. If a deadline is provided, the
+    // socket-level connection attempts are limited to the time remaining before it.
+    fn connect(&self, deadline: Option<Instant>) -> Result<Conn, Error> {
+        let result = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                let mut config = (*self.config).clone();
+                let timeout = match config.get_connect_timeout() {
+                    Some(timeout) => remaining.min(*timeout),
+                    None => remaining,
+                };
+                if timeout.as_nanos() == 0 {
+                    Err(Error::Timeout)
+                } else {
+                    config.connect_timeout(timeout);
+                    config.connect(self.tls.clone()).map_err(|e| {
+                        if Instant::now() >= deadline {
+                            Error::Timeout
+                        } else {
+                            e.into()
+                        }
+                    })
+                }
+            }
+            None => self.config.connect(self.tls.clone()).map_err(Error::from),
+        };
+
+        match result {
+            Ok(client) => Ok(Conn {
+                client,
+                created: Instant::now(),
+            }),
+            Err(e) => {
+                self.shared.state.lock().size -= 1;
+                self.shared.cond.notify_one();
+                Err(e)
+            }
+        }
+    }
+
+    // Opens connections on a separate thread until the pool is back up to its minimum size, so that `get` isn't held
+    // up by them.
+    fn spawn_replenish(&self) {
+        {
+            let mut state = self.shared.state.lock();
+            if state.replenishing || state.size >= self.shared.min_size() {
+                return;
+            }
+            state.replenishing = true;
+        }
+
+        let pool = self.clone();
+        thread::spawn(move || {
+            pool.replenish();
+            pool.shared.state.lock().replenishing = false;
+        });
+    }
+
+    fn replenish(&self) {
+        loop {
+            {
+                let mut state = self.shared.state.lock();
+                if state.size >= self.shared.min_size() {
+                    break;
+                }
+                state.size += 1;
+            }
+
+            match self.connect(None) {
+                Ok(conn) => self.shared.put(conn),
+                Err(e) => {
+                    error!("error opening connection: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    // Hands out a connection unless the deadline passed while it was being opened or checked, in which case it's
+    // returned to the pool instead.
+    fn finish_get(&self, conn: Conn, deadline: Instant) -> Result<PooledClient, Error> {
+        if Instant::now() >= deadline {
+            self.shared.put(conn);
+            return Err(Error::Timeout);
+        }
+        Ok(PooledClient::new(conn, &self.shared))
+    }
+
+    /// Retrieves a connection from the pool, opening a new one if none are idle and the pool isn't full.
+    ///
+    /// Blocks until a connection is returned if the pool is full, returning `Error::Timeout` if the acquire timeout
+    /// elapses first.
+    pub fn get(&self) -> Result<PooledClient, Error> {
+        let deadline = Instant::now() + self.shared.builder.acquire_timeout;
+
+        loop {
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout);
+            }
+
+            let mut state = self.shared.state.lock();
+            let reaped = self.shared.reap(&mut state);
+            if !reaped.is_empty() {
+                drop(state);
+                drop(reaped);
+                self.shared.cond.notify_all();
+                self.spawn_replenish();
+                continue;
+            }
+
+            // Take the most recently used connection so that surplus connections are left to reach the idle timeout.
+            if let Some(idle) = state.idle.pop_back() {
+                drop(state);
+
+                let mut conn = idle.conn;
+                if self.shared.check(&mut conn) {
+                    return self.finish_get(conn, deadline);
+                }
+                self.shared.discard(conn);
+                continue;
+            }
+
+            if state.size < self.shared.builder.max_size {
+                state.size += 1;
+                drop(state);
+
+                let conn = self.connect(Some(deadline))?;
+                return self.finish_get(conn, deadline);
+            }
+
+            if self
+                .shared
+                .cond
+                .wait_until(&mut state, deadline)
+                .timed_out()
+            {
+                return Err(Error::Timeout);
+            }
+        }
+    }
+}
+
+impl<T> Pool<T> {
+    /// Returns the number of connections currently open, whether idle or in use.
+    pub fn size(&self) -> usize {
+        self.shared.state.lock().size
+    }
+
+    /// Returns the number of idle connections.
+    pub fn idle(&self) -> usize {
+        self.shared.state.lock().idle.len()
+    }
+}
+
+/// A client checked out from a blocking `Pool`.
+///
+/// It dereferences to a `postgres::Client`, and is returned to the pool when dropped.
+pub struct PooledClient {
+    conn: Option<Conn>,
+    shared: Arc<Shared>,
+}
+
+impl PooledClient {
+    fn new(conn: Conn, shared: &Arc<Shared>) -> PooledClient {
+        PooledClient {
+            conn: Some(conn),
+            shared: shared.clone(),
+        }
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        &self.conn.as_ref().unwrap().client
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Client {
+        &mut self.conn.as_mut().unwrap().client
+    }
+}
+
+impl fmt::Debug for PooledClient {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PooledClient").finish()
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        let mut conn = self.conn.take().unwrap();
+
+        if conn.client.is_closed() || self.shared.expired(&conn, Instant::now()) {
+            self.shared.discard(conn);
+            return;
+        }
+
+        if self.shared.builder.reset_on_return {
+            if let Err(e) = conn.client.batch_execute(RESET_QUERY) {
+                debug!("discarding connection which failed to reset: {}", e);
+                self.shared.discard(conn);
+                return;
+            }
+            conn.client.clear_statement_cache();
+            conn.client.clear_type_cache();
+        }
+
+        self.shared.put(conn);
+    }
+}
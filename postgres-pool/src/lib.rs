@@ -0,0 +1,555 @@
+//! Connection pools for `tokio-postgres` and `postgres`.
+//!
+//! A pool keeps a set of open connections to the database, handing them out to callers and taking them back once
+//! they've been dropped. Connections which have been closed, or which fail an optional test query, are discarded, and
+//! the session state of returned connections is reset with `DISCARD ALL` so they can be safely shared.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use postgres_pool::Builder;
+//! use std::time::Duration;
+//! use tokio_postgres::NoTls;
+//!
+//! # async fn f() -> Result<(), postgres_pool::Error> {
+//! let config = "host=localhost user=postgres".parse::<tokio_postgres::Config>()?;
+//! let pool = Builder::new()
+//!     .max_size(16)
+//!     .acquire_timeout(Duration::from_secs(5))
+//!     .build(config, NoTls)
+//!     .await?;
+//!
+//! let client = pool.get().await?;
+//! let row = client.query_one("SELECT 1::INT4", &[]).await?;
+//! let value: i32 = row.get(0);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The `blocking` module contains an equivalent pool of synchronous `postgres` clients.
+#![doc(html_root_url = "https://docs.rs/postgres-pool/0.1")]
+#![warn(rust_2018_idioms, clippy::all, missing_docs)]
+
+use log::{debug, error};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::error;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time;
+use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
+use tokio_postgres::{Client, Config, Socket};
+
+pub mod blocking;
+#[cfg(test)]
+mod test;
+
+const RESET_QUERY: &str = "DISCARD ALL";
+const REAP_PERIOD: Duration = Duration::from_secs(30);
+const MIN_REAP_PERIOD: Duration = Duration::from_millis(100);
+
+/// An error returned when acquiring a connection from a pool.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// No connection became available before the acquire timeout elapsed.
+    Timeout,
+    /// An error opening a new connection.
+    Postgres(tokio_postgres::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Timeout => fmt.write_str("timed out waiting for a connection"),
+            Error::Postgres(e) => fmt::Display::fmt(e, fmt),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Timeout => None,
+            Error::Postgres(e) => Some(e),
+        }
+    }
+}
+
+impl From<tokio_postgres::Error> for Error {
+    fn from(e: tokio_postgres::Error) -> Error {
+        Error::Postgres(e)
+    }
+}
+
+/// A builder for connection pools.
+#[derive(Debug, Clone)]
+pub struct Builder {
+    max_size: usize,
+    min_size: usize,
+    acquire_timeout: Duration,
+    idle_timeout: Option<Duration>,
+    max_lifetime: Option<Duration>,
+    test_query: Option<String>,
+    reset_on_return: bool,
+}
+
+impl Default for Builder {
+    fn default() -> Builder {
+        Builder::new()
+    }
+}
+
+impl Builder {
+    /// Creates a new builder with the default configuration.
+    pub fn new() -> Builder {
+        Builder {
+            max_size: 10,
+            min_size: 0,
+            acquire_timeout: Duration::from_secs(30),
+            idle_timeout: Some(Duration::from_secs(10 * 60)),
+            max_lifetime: Some(Duration::from_secs(30 * 60)),
+            test_query: None,
+            reset_on_return: true,
+        }
+    }
+
+    /// Sets the maximum number of connections managed by the pool.
+    ///
+    /// Defaults to 10.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_size` is 0.
+    pub fn max_size(mut self, max_size: usize) -> Builder {
+        assert!(max_size > 0, "max_size must be positive");
+        self.max_size = max_size;
+        self
+    }
+
+    /// Sets the number of connections the pool keeps open, even while they're idle.
+    ///
+    /// These connections are opened when the pool is built. The value is capped at the pool's maximum size. Defaults
+    /// to 0.
+    pub fn min_size(mut self, min_size: usize) -> Builder {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Sets the time to wait for a connection to become available before returning `Error::Timeout`.
+    ///
+    /// This includes the time taken to open a new connection. Defaults to 30 seconds.
+    pub fn acquire_timeout(mut self, acquire_timeout: Duration) -> Builder {
+        self.acquire_timeout = acquire_timeout;
+        self
+    }
+
+    /// Sets the time after which an idle connection is closed, as long as the pool holds more than its minimum number
+    /// of connections.
+    ///
+    /// Defaults to 10 minutes.
+    pub fn idle_timeout(mut self, idle_timeout: Option<Duration>) -> Builder {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Sets the maximum age of a connection, after which it is closed rather than being handed out again.
+    ///
+    /// Defaults to 30 minutes.
+    pub fn max_lifetime(mut self, max_lifetime: Option<Duration>) -> Builder {
+        self.max_lifetime = max_lifetime;
+        self
+    }
+
+    /// Sets a query used to check the health of an idle connection before it is handed out.
+    ///
+    /// Connections for which the query fails are discarded. Connections which the client already knows to be closed
+    /// are always discarded. Defaults to no query.
+    pub fn test_query(mut self, test_query: &str) -> Builder {
+        self.test_query = Some(test_query.to_string());
+        self
+    }
+
+    /// Determines if the session state of a connection is reset with `DISCARD ALL` when it is returned to the pool.
+    ///
+    /// This closes any prepared statements created with the connection, so they must not be used after it has been
+    /// returned. Defaults to `true`.
+    pub fn reset_on_return(mut self, reset_on_return: bool) -> Builder {
+        self.reset_on_return = reset_on_return;
+        self
+    }
+
+    /// Creates a pool of `tokio_postgres` clients, opening its minimum number of connections.
+    ///
+    /// Connections are driven by tasks spawned onto the current Tokio runtime.
+    pub async fn build<T>(self, config: Config, tls: T) -> Result<Pool<T>, Error>
+    where
+        T: MakeTlsConnect<Socket> + Clone + 'static + Sync + Send,
+        T::TlsConnect: Send,
+        T::Stream: Send,
+        <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+    {
+        let reap = self.idle_timeout.is_some() || self.max_lifetime.is_some() || self.min_size > 0;
+        let period = [self.idle_timeout, self.max_lifetime]
+            .iter()
+            .filter_map(|d| *d)
+            .fold(REAP_PERIOD, |a, b| a.min(b))
+            .max(MIN_REAP_PERIOD);
+
+        let inner = Arc::new(Inner {
+            shared: Arc::new(Shared {
+                semaphore: Arc::new(Semaphore::new(self.max_size)),
+                idle: Mutex::new(VecDeque::new()),
+                size: Arc::new(AtomicUsize::new(0)),
+                runtime: Handle::current(),
+                builder: self,
+            }),
+            config,
+            tls,
+        });
+
+        for _ in 0..inner.shared.min_size() {
+            let conn = inner.connect().await?;
+            inner.shared.put(conn);
+        }
+
+        if reap {
+            inner
+                .shared
+                .runtime
+                .spawn(reaper(Arc::downgrade(&inner), period));
+        }
+
+        Ok(Pool { inner })
+    }
+
+    /// Creates a pool of blocking `postgres` clients, opening its minimum number of connections.
+    pub fn build_blocking<T>(
+        self,
+        config: postgres::Config,
+        tls: T,
+    ) -> Result<blocking::Pool<T>, Error>
+    where
+        T: MakeTlsConnect<Socket> + Clone + 'static + Send,
+        T::TlsConnect: Send,
+        T::Stream: Send,
+        <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+    {
+        blocking::Pool::new(self, config, tls)
+    }
+}
+
+// Tracks the number of open connections, including those still being established.
+struct SizeGuard(Arc<AtomicUsize>);
+
+impl SizeGuard {
+    fn new(size: &Arc<AtomicUsize>) -> SizeGuard {
+        size.fetch_add(1, Ordering::SeqCst);
+        SizeGuard(size.clone())
+    }
+}
+
+impl Drop for SizeGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+struct Conn {
+    client: Client,
+    created: Instant,
+    _size: SizeGuard,
+}
+
+struct Idle {
+    conn: Conn,
+    since: Instant,
+}
+
+struct Shared {
+    builder: Builder,
+    // Each permit represents a connection which is checked out, or which is being opened or reset.
+    semaphore: Arc<Semaphore>,
+    idle: Mutex<VecDeque<Idle>>,
+    size: Arc<AtomicUsize>,
+    runtime: Handle,
+}
+
+impl Shared {
+    fn min_size(&self) -> usize {
+        self.builder.min_size.min(self.builder.max_size)
+    }
+
+    fn expired(&self, conn: &Conn, now: Instant) -> bool {
+        match self.builder.max_lifetime {
+            Some(max_lifetime) => now.duration_since(conn.created) >= max_lifetime,
+            None => false,
+        }
+    }
+
+    fn put(&self, conn: Conn) {
+        self.idle.lock().push_back(Idle {
+            conn,
+            since: Instant::now(),
+        });
+    }
+
+    async fn check(&self, conn: &Conn) -> bool {
+        if conn.client.is_closed() || self.expired(conn, Instant::now()) {
+            return false;
+        }
+
+        match &self.builder.test_query {
+            Some(query) => match conn.client.simple_query(query).await {
+                Ok(_) => true,
+                Err(e) => {
+                    debug!("discarding connection which failed its test query: {}", e);
+                    false
+                }
+            },
+            None => true,
+        }
+    }
+
+    fn reap(&self) {
+        let now = Instant::now();
+        let min_size = self.min_size();
+        let mut idle = self.idle.lock();
+        let mut size = self.size.load(Ordering::SeqCst);
+        // The oldest idle connections are at the front of the queue, so they're the ones closed first.
+        idle.retain(|idle| {
+            let keep = if idle.conn.client.is_closed() || self.expired(&idle.conn, now) {
+                false
+            } else {
+                match self.builder.idle_timeout {
+                    Some(idle_timeout) if now.duration_since(idle.since) >= idle_timeout => {
+                        size <= min_size
+                    }
+                    _ => true,
+                }
+            };
+            if !keep {
+                size -= 1;
+            }
+            keep
+        });
+    }
+}
+
+struct Inner<T> {
+    shared: Arc<Shared>,
+    config: Config,
+    tls: T,
+}
+
+impl<T> Inner<T>
+where
+    T: MakeTlsConnect<Socket> + Clone + 'static + Sync + Send,
+    T::TlsConnect: Send,
+    T::Stream: Send,
+    <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn connect(&self) -> Result<Conn, Error> {
+        let size = SizeGuard::new(&self.shared.size);
+        let (client, connection) = self.config.connect(self.tls.clone()).await?;
+        self.shared.runtime.spawn(async move {
+            if let Err(e) = connection.await {
+                error!("connection error: {}", e);
+            }
+        });
+
+        Ok(Conn {
+            client,
+            created: Instant::now(),
+            _size: size,
+        })
+    }
+
+    async fn get(&self) -> Result<PooledClient, Error> {
+        let permit = self.shared.semaphore.clone().acquire_owned().await;
+
+        loop {
+            // Take the most recently used connection so that surplus connections are left to reach the idle timeout.
+            let idle = self.shared.idle.lock().pop_back();
+            match idle {
+                Some(idle) => {
+                    if self.shared.check(&idle.conn).await {
+                        return Ok(PooledClient::new(idle.conn, permit, &self.shared));
+                    }
+                }
+                None => break,
+            }
+        }
+
+        let conn = self.connect().await?;
+        Ok(PooledClient::new(conn, permit, &self.shared))
+    }
+
+    async fn replenish(&self) {
+        while self.shared.size.load(Ordering::SeqCst) < self.shared.min_size() {
+            let permit = match self.shared.semaphore.clone().try_acquire_owned() {
+                Ok(permit) => permit,
+                Err(_) => break,
+            };
+
+            match self.connect().await {
+                Ok(conn) => self.shared.put(conn),
+                Err(e) => {
+                    error!("error opening connection: {}", e);
+                    break;
+                }
+            }
+            drop(permit);
+        }
+    }
+}
+
+async fn reaper<T>(inner: Weak<Inner<T>>, period: Duration)
+where
+    T: MakeTlsConnect<Socket> + Clone + 'static + Sync + Send,
+    T::TlsConnect: Send,
+    T::Stream: Send,
+    <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    let mut interval = time::interval(period);
+    loop {
+        interval.tick().await;
+
+        let inner = match inner.upgrade() {
+            Some(inner) => inner,
+            None => return,
+        };
+        inner.shared.reap();
+        inner.replenish().await;
+    }
+}
+
+/// A pool of asynchronous `tokio_postgres` clients.
+///
+/// The pool is reference counted, so cloning it produces another handle to the same set of connections.
+pub struct Pool<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Clone for Pool<T> {
+    fn clone(&self) -> Pool<T> {
+        Pool {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Pool<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Pool")
+            .field("builder", &self.inner.shared.builder)
+            .field("size", &self.size())
+            .field("idle", &self.idle())
+            .finish()
+    }
+}
+
+impl<T> Pool<T>
+where
+    T: MakeTlsConnect<Socket> + Clone + 'static + Sync + Send,
+    T::TlsConnect: Send,
+    T::Stream: Send,
+    <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    /// Retrieves a connection from the pool, opening a new one if none are idle and the pool isn't full.
+    ///
+    /// Waits for a connection to be returned if the pool is full, returning `Error::Timeout` if the acquire timeout
+    /// elapses first.
+    pub async fn get(&self) -> Result<PooledClient, Error> {
+        match time::timeout(self.inner.shared.builder.acquire_timeout, self.inner.get()).await {
+            Ok(r) => r,
+            Err(_) => Err(Error::Timeout),
+        }
+    }
+}
+
+impl<T> Pool<T> {
+    /// Returns the number of connections currently open, whether idle or in use.
+    pub fn size(&self) -> usize {
+        self.inner.shared.size.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of idle connections.
+    pub fn idle(&self) -> usize {
+        self.inner.shared.idle.lock().len()
+    }
+}
+
+/// A client checked out from a `Pool`.
+///
+/// It dereferences to a `tokio_postgres::Client`, and is returned to the pool when dropped.
+pub struct PooledClient {
+    conn: Option<Conn>,
+    permit: Option<OwnedSemaphorePermit>,
+    shared: Arc<Shared>,
+}
+
+impl PooledClient {
+    fn new(conn: Conn, permit: OwnedSemaphorePermit, shared: &Arc<Shared>) -> PooledClient {
+        PooledClient {
+            conn: Some(conn),
+            permit: Some(permit),
+            shared: shared.clone(),
+        }
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        &self.conn.as_ref().unwrap().client
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Client {
+        &mut self.conn.as_mut().unwrap().client
+    }
+}
+
+impl fmt::Debug for PooledClient {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PooledClient").finish()
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        let conn = self.conn.take().unwrap();
+        let permit = self.permit.take();
+
+        if conn.client.is_closed() || self.shared.expired(&conn, Instant::now()) {
+            return;
+        }
+
+        if !self.shared.builder.reset_on_return {
+            self.shared.put(conn);
+            return;
+        }
+
+        // The connection's permit is held until the reset completes, so it still counts against the pool's size.
+        let shared = self.shared.clone();
+        self.shared.runtime.spawn(async move {
+            match conn.client.batch_execute(RESET_QUERY).await {
+                Ok(()) => {
+                    conn.client.clear_statement_cache();
+                    conn.client.clear_type_cache();
+                    shared.put(conn);
+                }
+                Err(e) => debug!("discarding connection which failed to reset: {}", e),
+            }
+            drop(permit);
+        });
+    }
+}
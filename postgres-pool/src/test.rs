@@ -0,0 +1,256 @@
+use crate::{Builder, Error, Pool};
+use std::time::Duration;
+use tokio::time;
+use tokio_postgres::tls::NoTls;
+
+const CONFIG: &str = "host=localhost port=5433 user=postgres";
+
+async fn pool(builder: Builder) -> Pool<NoTls> {
+    builder.build(CONFIG.parse().unwrap(), NoTls).await.unwrap()
+}
+
+fn blocking_pool(builder: Builder) -> crate::blocking::Pool<NoTls> {
+    builder
+        .build_blocking(CONFIG.parse().unwrap(), NoTls)
+        .unwrap()
+}
+
+async fn backend_pid(pool: &Pool<NoTls>) -> i32 {
+    let client = pool.get().await.unwrap();
+    let row = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap();
+    row.get(0)
+}
+
+// Returned connections are reset on a spawned task, so wait for them to get back into the pool.
+async fn wait_for_idle(pool: &Pool<NoTls>, idle: usize) {
+    while pool.idle() != idle {
+        time::delay_for(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn reuse() {
+    let pool = pool(Builder::new()).await;
+
+    let pid = backend_pid(&pool).await;
+    wait_for_idle(&pool, 1).await;
+    assert_eq!(backend_pid(&pool).await, pid);
+    assert_eq!(pool.size(), 1);
+}
+
+#[tokio::test]
+async fn acquire_timeout() {
+    let pool = pool(
+        Builder::new()
+            .max_size(1)
+            .acquire_timeout(Duration::from_millis(100)),
+    )
+    .await;
+
+    let client = pool.get().await.unwrap();
+    match pool.get().await {
+        Err(Error::Timeout) => {}
+        r => panic!("unexpected result {:?}", r),
+    }
+
+    let pool2 = pool.clone();
+    let get = tokio::spawn(async move { pool2.get().await.map(|_| ()) });
+    drop(client);
+    get.await.unwrap().unwrap();
+    assert_eq!(pool.size(), 1);
+}
+
+#[tokio::test]
+async fn reset_on_return() {
+    let pool = pool(Builder::new().max_size(1)).await;
+
+    let client = pool.get().await.unwrap();
+    client
+        .batch_execute("SET application_name = 'pooled'; CREATE TEMPORARY TABLE foo (id INT)")
+        .await
+        .unwrap();
+    drop(client);
+
+    let client = pool.get().await.unwrap();
+    let row = client
+        .query_one(
+            "SELECT current_setting('application_name'), to_regclass('pg_temp.foo') IS NULL",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &str>(0), "");
+    assert!(row.get::<_, bool>(1));
+}
+
+#[tokio::test]
+async fn reset_clears_type_cache() {
+    let pool = pool(Builder::new().max_size(1)).await;
+
+    for _ in 0..2 {
+        let client = pool.get().await.unwrap();
+        client
+            .batch_execute("CREATE TYPE pg_temp.mood AS ENUM ('sad', 'happy')")
+            .await
+            .unwrap();
+        let row = client.query_one("SELECT 'happy'::mood", &[]).await.unwrap();
+        assert_eq!(row.columns()[0].type_().name(), "mood");
+    }
+}
+
+#[tokio::test]
+async fn closed_connection_discarded() {
+    let pool = pool(Builder::new().max_size(1)).await;
+
+    let pid = backend_pid(&pool).await;
+    wait_for_idle(&pool, 1).await;
+
+    let (client, connection) = pool.inner.config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+    client
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .await
+        .unwrap();
+    while !pool.inner.shared.idle.lock()[0].conn.client.is_closed() {
+        time::delay_for(Duration::from_millis(10)).await;
+    }
+
+    assert_ne!(backend_pid(&pool).await, pid);
+}
+
+#[tokio::test]
+async fn test_query() {
+    let pool = pool(Builder::new().max_size(1).test_query("SELECT 1")).await;
+
+    let pid = backend_pid(&pool).await;
+    wait_for_idle(&pool, 1).await;
+    assert_eq!(backend_pid(&pool).await, pid);
+    wait_for_idle(&pool, 1).await;
+
+    let pool = self::pool(Builder::new().max_size(1).test_query("SELECT nope")).await;
+
+    let pid = backend_pid(&pool).await;
+    wait_for_idle(&pool, 1).await;
+    assert_ne!(backend_pid(&pool).await, pid);
+}
+
+#[tokio::test]
+async fn max_lifetime() {
+    let pool = pool(Builder::new().max_lifetime(Some(Duration::from_millis(100)))).await;
+
+    let pid = backend_pid(&pool).await;
+    time::delay_for(Duration::from_millis(200)).await;
+    assert_ne!(backend_pid(&pool).await, pid);
+}
+
+#[tokio::test]
+async fn min_size_and_idle_timeout() {
+    let pool = pool(
+        Builder::new()
+            .min_size(1)
+            .idle_timeout(Some(Duration::from_millis(100))),
+    )
+    .await;
+    assert_eq!(pool.size(), 1);
+
+    let a = pool.get().await.unwrap();
+    let b = pool.get().await.unwrap();
+    assert_eq!(pool.size(), 2);
+    drop(a);
+    drop(b);
+    wait_for_idle(&pool, 2).await;
+
+    // the reaper closes the surplus connection, but keeps the minimum open
+    while pool.size() != 1 {
+        time::delay_for(Duration::from_millis(10)).await;
+    }
+    time::delay_for(Duration::from_millis(300)).await;
+    assert_eq!(pool.size(), 1);
+}
+
+#[test]
+fn blocking_reuse() {
+    let pool = blocking_pool(Builder::new());
+
+    let pid = {
+        let mut client = pool.get().unwrap();
+        client
+            .batch_execute("SET application_name = 'pooled'")
+            .unwrap();
+        client
+            .query_one("SELECT pg_backend_pid()", &[])
+            .unwrap()
+            .get::<_, i32>(0)
+    };
+    assert_eq!(pool.idle(), 1);
+
+    let mut client = pool.get().unwrap();
+    let row = client
+        .query_one(
+            "SELECT pg_backend_pid(), current_setting('application_name')",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(row.get::<_, i32>(0), pid);
+    assert_eq!(row.get::<_, &str>(1), "");
+}
+
+#[test]
+fn blocking_acquire_timeout() {
+    let pool = blocking_pool(
+        Builder::new()
+            .max_size(1)
+            .acquire_timeout(Duration::from_millis(100)),
+    );
+
+    let client = pool.get().unwrap();
+    match pool.get() {
+        Err(Error::Timeout) => {}
+        r => panic!("unexpected result {:?}", r),
+    }
+    drop(client);
+    pool.get().unwrap();
+}
+
+#[test]
+fn blocking_min_size_and_idle_timeout() {
+    let pool = blocking_pool(
+        Builder::new()
+            .min_size(1)
+            .idle_timeout(Some(Duration::from_millis(100))),
+    );
+    assert_eq!(pool.size(), 1);
+
+    let a = pool.get().unwrap();
+    let b = pool.get().unwrap();
+    drop(a);
+    drop(b);
+    assert_eq!(pool.size(), 2);
+
+    std::thread::sleep(Duration::from_millis(200));
+    let _client = pool.get().unwrap();
+    assert_eq!(pool.size(), 1);
+}
+
+#[test]
+fn blocking_min_size_after_reap() {
+    let pool = blocking_pool(
+        Builder::new()
+            .min_size(2)
+            .max_lifetime(Some(Duration::from_millis(100))),
+    );
+    assert_eq!(pool.idle(), 2);
+
+    std::thread::sleep(Duration::from_millis(200));
+    let client = pool.get().unwrap();
+    // the pool is refilled in the background
+    while pool.idle() != 1 {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(pool.size(), 2);
+    drop(client);
+    assert_eq!(pool.idle(), 2);
+}
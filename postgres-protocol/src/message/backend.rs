@@ -1,7 +1,8 @@
+//! Backend message parsing and serialization.
 #![allow(missing_docs)]
 
 use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
-use bytes::{Bytes, BytesMut};
+use bytes::{BufMut, Bytes, BytesMut};
 use fallible_iterator::FallibleIterator;
 use memchr::memchr;
 use std::cmp;
@@ -9,7 +10,8 @@ use std::io::{self, Read};
 use std::ops::Range;
 use std::str;
 
-use crate::Oid;
+use crate::message::frontend::{write_body, write_counted, write_cstr};
+use crate::{FromUsize, Oid};
 
 pub const PARSE_COMPLETE_TAG: u8 = b'1';
 pub const BIND_COMPLETE_TAG: u8 = b'2';
@@ -22,7 +24,9 @@ pub const DATA_ROW_TAG: u8 = b'D';
 pub const ERROR_RESPONSE_TAG: u8 = b'E';
 pub const COPY_IN_RESPONSE_TAG: u8 = b'G';
 pub const COPY_OUT_RESPONSE_TAG: u8 = b'H';
+pub const COPY_BOTH_RESPONSE_TAG: u8 = b'W';
 pub const EMPTY_QUERY_RESPONSE_TAG: u8 = b'I';
+pub const FUNCTION_CALL_RESPONSE_TAG: u8 = b'V';
 pub const BACKEND_KEY_DATA_TAG: u8 = b'K';
 pub const NO_DATA_TAG: u8 = b'n';
 pub const NOTICE_RESPONSE_TAG: u8 = b'N';
@@ -33,6 +37,34 @@ pub const PARAMETER_DESCRIPTION_TAG: u8 = b't';
 pub const ROW_DESCRIPTION_TAG: u8 = b'T';
 pub const READY_FOR_QUERY_TAG: u8 = b'Z';
 
+// replication message tags
+pub const XLOG_DATA_TAG: u8 = b'w';
+pub const PRIMARY_KEEPALIVE_TAG: u8 = b'k';
+
+// logical replication message tags
+pub const BEGIN_TAG: u8 = b'B';
+pub const COMMIT_TAG: u8 = b'C';
+pub const ORIGIN_TAG: u8 = b'O';
+pub const RELATION_TAG: u8 = b'R';
+pub const TYPE_TAG: u8 = b'Y';
+pub const INSERT_TAG: u8 = b'I';
+pub const UPDATE_TAG: u8 = b'U';
+pub const DELETE_TAG: u8 = b'D';
+pub const TRUNCATE_TAG: u8 = b'T';
+pub const TUPLE_NEW_TAG: u8 = b'N';
+pub const TUPLE_KEY_TAG: u8 = b'K';
+pub const TUPLE_OLD_TAG: u8 = b'O';
+pub const TUPLE_DATA_NULL_TAG: u8 = b'n';
+pub const TUPLE_DATA_TOAST_TAG: u8 = b'u';
+pub const TUPLE_DATA_TEXT_TAG: u8 = b't';
+pub const TUPLE_DATA_BINARY_TAG: u8 = b'b';
+
+// replica identity tags
+pub const REPLICA_IDENTITY_DEFAULT_TAG: u8 = b'd';
+pub const REPLICA_IDENTITY_NOTHING_TAG: u8 = b'n';
+pub const REPLICA_IDENTITY_FULL_TAG: u8 = b'f';
+pub const REPLICA_IDENTITY_INDEX_TAG: u8 = b'i';
+
 #[derive(Debug, Copy, Clone)]
 pub struct Header {
     tag: u8,
@@ -93,9 +125,11 @@ pub enum Message {
     CopyDone,
     CopyInResponse(CopyInResponseBody),
     CopyOutResponse(CopyOutResponseBody),
+    CopyBothResponse(CopyBothResponseBody),
     DataRow(DataRowBody),
     EmptyQueryResponse,
     ErrorResponse(ErrorResponseBody),
+    FunctionCallResponse(FunctionCallResponseBody),
     NoData,
     NoticeResponse(NoticeResponseBody),
     NotificationResponse(NotificationResponseBody),
@@ -190,7 +224,26 @@ impl Message {
                     storage,
                 })
             }
+            COPY_BOTH_RESPONSE_TAG => {
+                let format = buf.read_u8()?;
+                let len = buf.read_u16::<BigEndian>()?;
+                let storage = buf.read_all();
+                Message::CopyBothResponse(CopyBothResponseBody {
+                    format,
+                    len,
+                    storage,
+                })
+            }
             EMPTY_QUERY_RESPONSE_TAG => Message::EmptyQueryResponse,
+            FUNCTION_CALL_RESPONSE_TAG => {
+                let len = buf.read_i32::<BigEndian>()?;
+                let value = if len < 0 {
+                    None
+                } else {
+                    Some(buf.read_buf(len as usize)?)
+                };
+                Message::FunctionCallResponse(FunctionCallResponseBody { value })
+            }
             BACKEND_KEY_DATA_TAG => {
                 let process_id = buf.read_i32::<BigEndian>()?;
                 let secret_key = buf.read_i32::<BigEndian>()?;
@@ -278,9 +331,9 @@ impl Message {
     }
 }
 
-struct Buffer {
-    bytes: Bytes,
-    idx: usize,
+pub(crate) struct Buffer {
+    pub(crate) bytes: Bytes,
+    pub(crate) idx: usize,
 }
 
 impl Buffer {
@@ -290,12 +343,12 @@ impl Buffer {
     }
 
     #[inline]
-    fn is_empty(&self) -> bool {
+    pub(crate) fn is_empty(&self) -> bool {
         self.slice().is_empty()
     }
 
     #[inline]
-    fn read_cstr(&mut self) -> io::Result<Bytes> {
+    pub(crate) fn read_cstr(&mut self) -> io::Result<Bytes> {
         match memchr(0, self.slice()) {
             Some(pos) => {
                 let start = self.idx;
@@ -312,7 +365,21 @@ impl Buffer {
     }
 
     #[inline]
-    fn read_all(&mut self) -> Bytes {
+    pub(crate) fn read_buf(&mut self, len: usize) -> io::Result<Bytes> {
+        if self.slice().len() < len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "unexpected EOF",
+            ));
+        }
+
+        let buf = self.bytes.slice(self.idx..self.idx + len);
+        self.idx += len;
+        Ok(buf)
+    }
+
+    #[inline]
+    pub(crate) fn read_all(&mut self) -> Bytes {
         let buf = self.bytes.slice(self.idx..);
         self.idx = self.bytes.len();
         buf
@@ -524,6 +591,27 @@ impl CopyOutResponseBody {
     }
 }
 
+pub struct CopyBothResponseBody {
+    storage: Bytes,
+    len: u16,
+    format: u8,
+}
+
+impl CopyBothResponseBody {
+    #[inline]
+    pub fn format(&self) -> u8 {
+        self.format
+    }
+
+    #[inline]
+    pub fn column_formats(&self) -> ColumnFormats<'_> {
+        ColumnFormats {
+            remaining: self.len,
+            buf: &self.storage,
+        }
+    }
+}
+
 pub struct DataRowBody {
     storage: Bytes,
     len: u16,
@@ -651,6 +739,23 @@ impl<'a> ErrorField<'a> {
     }
 }
 
+pub struct FunctionCallResponseBody {
+    value: Option<Bytes>,
+}
+
+impl FunctionCallResponseBody {
+    /// The result of the function call, or `None` if it was `NULL`.
+    #[inline]
+    pub fn value(&self) -> Option<&[u8]> {
+        self.value.as_deref()
+    }
+
+    #[inline]
+    pub fn into_value(self) -> Option<Bytes> {
+        self.value
+    }
+}
+
 pub struct NoticeResponseBody {
     storage: Bytes,
 }
@@ -832,6 +937,28 @@ pub struct Field<'a> {
 }
 
 impl<'a> Field<'a> {
+    /// Creates a new field, for use with `row_description`.
+    #[inline]
+    pub fn new(
+        name: &'a str,
+        table_oid: Oid,
+        column_id: i16,
+        type_oid: Oid,
+        type_size: i16,
+        type_modifier: i32,
+        format: i16,
+    ) -> Field<'a> {
+        Field {
+            name,
+            table_oid,
+            column_id,
+            type_oid,
+            type_size,
+            type_modifier,
+            format,
+        }
+    }
+
     #[inline]
     pub fn name(&self) -> &'a str {
         self.name
@@ -868,8 +995,1063 @@ impl<'a> Field<'a> {
     }
 }
 
+/// An enum representing Postgres backend replication messages.
+///
+/// These messages are sent by the server inside of `CopyData` messages once a connection has entered streaming
+/// replication mode.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum ReplicationMessage<D> {
+    XLogData(XLogDataBody<D>),
+    PrimaryKeepAlive(PrimaryKeepAliveBody),
+}
+
+impl ReplicationMessage<Bytes> {
+    #[inline]
+    pub fn parse(buf: &Bytes) -> io::Result<ReplicationMessage<Bytes>> {
+        let mut buf = Buffer {
+            bytes: buf.clone(),
+            idx: 0,
+        };
+
+        let tag = buf.read_u8()?;
+
+        let message = match tag {
+            XLOG_DATA_TAG => {
+                let wal_start = buf.read_u64::<BigEndian>()?;
+                let wal_end = buf.read_u64::<BigEndian>()?;
+                let timestamp = buf.read_i64::<BigEndian>()?;
+                let data = buf.read_all();
+                ReplicationMessage::XLogData(XLogDataBody {
+                    wal_start,
+                    wal_end,
+                    timestamp,
+                    data,
+                })
+            }
+            PRIMARY_KEEPALIVE_TAG => {
+                let wal_end = buf.read_u64::<BigEndian>()?;
+                let timestamp = buf.read_i64::<BigEndian>()?;
+                let reply = buf.read_u8()?;
+                ReplicationMessage::PrimaryKeepAlive(PrimaryKeepAliveBody {
+                    wal_end,
+                    timestamp,
+                    reply,
+                })
+            }
+            tag => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown replication message tag `{}`", tag),
+                ));
+            }
+        };
+
+        if !buf.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid message length: expected buffer to be empty",
+            ));
+        }
+
+        Ok(message)
+    }
+}
+
+#[derive(Debug)]
+pub struct XLogDataBody<D> {
+    wal_start: u64,
+    wal_end: u64,
+    timestamp: i64,
+    data: D,
+}
+
+impl<D> XLogDataBody<D> {
+    #[inline]
+    pub fn wal_start(&self) -> u64 {
+        self.wal_start
+    }
+
+    #[inline]
+    pub fn wal_end(&self) -> u64 {
+        self.wal_end
+    }
+
+    #[inline]
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    #[inline]
+    pub fn data(&self) -> &D {
+        &self.data
+    }
+
+    #[inline]
+    pub fn into_data(self) -> D {
+        self.data
+    }
+
+    pub fn map_data<F, D2, E>(self, f: F) -> Result<XLogDataBody<D2>, E>
+    where
+        F: FnOnce(D) -> Result<D2, E>,
+    {
+        let data = f(self.data)?;
+        Ok(XLogDataBody {
+            wal_start: self.wal_start,
+            wal_end: self.wal_end,
+            timestamp: self.timestamp,
+            data,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct PrimaryKeepAliveBody {
+    wal_end: u64,
+    timestamp: i64,
+    reply: u8,
+}
+
+impl PrimaryKeepAliveBody {
+    #[inline]
+    pub fn wal_end(&self) -> u64 {
+        self.wal_end
+    }
+
+    #[inline]
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    #[inline]
+    pub fn reply(&self) -> u8 {
+        self.reply
+    }
+}
+
+/// An enum representing the messages of the `pgoutput` logical decoding plugin.
+///
+/// These messages are carried in the data of `XLogData` replication messages.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum LogicalReplicationMessage {
+    Begin(BeginBody),
+    Commit(CommitBody),
+    Origin(OriginBody),
+    Relation(RelationBody),
+    Type(TypeBody),
+    Insert(InsertBody),
+    Update(UpdateBody),
+    Delete(DeleteBody),
+    Truncate(TruncateBody),
+}
+
+impl LogicalReplicationMessage {
+    #[inline]
+    pub fn parse(buf: &Bytes) -> io::Result<LogicalReplicationMessage> {
+        let mut buf = Buffer {
+            bytes: buf.clone(),
+            idx: 0,
+        };
+
+        let tag = buf.read_u8()?;
+
+        let message = match tag {
+            BEGIN_TAG => {
+                let final_lsn = buf.read_u64::<BigEndian>()?;
+                let timestamp = buf.read_i64::<BigEndian>()?;
+                let xid = buf.read_u32::<BigEndian>()?;
+                LogicalReplicationMessage::Begin(BeginBody {
+                    final_lsn,
+                    timestamp,
+                    xid,
+                })
+            }
+            COMMIT_TAG => {
+                let flags = buf.read_i8()?;
+                let commit_lsn = buf.read_u64::<BigEndian>()?;
+                let end_lsn = buf.read_u64::<BigEndian>()?;
+                let timestamp = buf.read_i64::<BigEndian>()?;
+                LogicalReplicationMessage::Commit(CommitBody {
+                    flags,
+                    commit_lsn,
+                    end_lsn,
+                    timestamp,
+                })
+            }
+            ORIGIN_TAG => {
+                let commit_lsn = buf.read_u64::<BigEndian>()?;
+                let name = buf.read_cstr()?;
+                LogicalReplicationMessage::Origin(OriginBody { commit_lsn, name })
+            }
+            RELATION_TAG => {
+                let rel_id = buf.read_u32::<BigEndian>()?;
+                let namespace = buf.read_cstr()?;
+                let name = buf.read_cstr()?;
+                let replica_identity = match buf.read_u8()? {
+                    REPLICA_IDENTITY_DEFAULT_TAG => ReplicaIdentity::Default,
+                    REPLICA_IDENTITY_NOTHING_TAG => ReplicaIdentity::Nothing,
+                    REPLICA_IDENTITY_FULL_TAG => ReplicaIdentity::Full,
+                    REPLICA_IDENTITY_INDEX_TAG => ReplicaIdentity::Index,
+                    tag => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("unknown replica identity tag `{}`", tag),
+                        ));
+                    }
+                };
+                let column_len = buf.read_i16::<BigEndian>()?;
+
+                let mut columns = Vec::with_capacity(column_len as usize);
+                for _ in 0..column_len {
+                    columns.push(Column::parse(&mut buf)?);
+                }
+
+                LogicalReplicationMessage::Relation(RelationBody {
+                    rel_id,
+                    namespace,
+                    name,
+                    replica_identity,
+                    columns,
+                })
+            }
+            TYPE_TAG => {
+                let id = buf.read_u32::<BigEndian>()?;
+                let namespace = buf.read_cstr()?;
+                let name = buf.read_cstr()?;
+                LogicalReplicationMessage::Type(TypeBody {
+                    id,
+                    namespace,
+                    name,
+                })
+            }
+            INSERT_TAG => {
+                let rel_id = buf.read_u32::<BigEndian>()?;
+                let tag = buf.read_u8()?;
+
+                let tuple = match tag {
+                    TUPLE_NEW_TAG => Tuple::parse(&mut buf)?,
+                    tag => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("unexpected tuple tag `{}`", tag),
+                        ));
+                    }
+                };
+
+                LogicalReplicationMessage::Insert(InsertBody { rel_id, tuple })
+            }
+            UPDATE_TAG => {
+                let rel_id = buf.read_u32::<BigEndian>()?;
+                let tag = buf.read_u8()?;
+
+                let mut key_tuple = None;
+                let mut old_tuple = None;
+
+                let new_tuple = match tag {
+                    TUPLE_NEW_TAG => Tuple::parse(&mut buf)?,
+                    TUPLE_OLD_TAG | TUPLE_KEY_TAG => {
+                        if tag == TUPLE_OLD_TAG {
+                            old_tuple = Some(Tuple::parse(&mut buf)?);
+                        } else {
+                            key_tuple = Some(Tuple::parse(&mut buf)?);
+                        }
+
+                        match buf.read_u8()? {
+                            TUPLE_NEW_TAG => Tuple::parse(&mut buf)?,
+                            tag => {
+                                return Err(io::Error::new(
+                                    io::ErrorKind::InvalidInput,
+                                    format!("unexpected tuple tag `{}`", tag),
+                                ));
+                            }
+                        }
+                    }
+                    tag => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("unknown tuple tag `{}`", tag),
+                        ));
+                    }
+                };
+
+                LogicalReplicationMessage::Update(UpdateBody {
+                    rel_id,
+                    key_tuple,
+                    old_tuple,
+                    new_tuple,
+                })
+            }
+            DELETE_TAG => {
+                let rel_id = buf.read_u32::<BigEndian>()?;
+                let tag = buf.read_u8()?;
+
+                let mut key_tuple = None;
+                let mut old_tuple = None;
+
+                match tag {
+                    TUPLE_OLD_TAG => old_tuple = Some(Tuple::parse(&mut buf)?),
+                    TUPLE_KEY_TAG => key_tuple = Some(Tuple::parse(&mut buf)?),
+                    tag => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("unknown tuple tag `{}`", tag),
+                        ));
+                    }
+                }
+
+                LogicalReplicationMessage::Delete(DeleteBody {
+                    rel_id,
+                    key_tuple,
+                    old_tuple,
+                })
+            }
+            TRUNCATE_TAG => {
+                let len = buf.read_i32::<BigEndian>()?;
+                let options = buf.read_i8()?;
+
+                let mut rel_ids = Vec::with_capacity(len as usize);
+                for _ in 0..len {
+                    rel_ids.push(buf.read_u32::<BigEndian>()?);
+                }
+
+                LogicalReplicationMessage::Truncate(TruncateBody { options, rel_ids })
+            }
+            tag => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown logical replication message tag `{}`", tag),
+                ));
+            }
+        };
+
+        if !buf.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid message length: expected buffer to be empty",
+            ));
+        }
+
+        Ok(message)
+    }
+}
+
+/// A row as it appears in the replication stream.
+#[derive(Debug)]
+pub struct Tuple(Vec<TupleData>);
+
+impl Tuple {
+    #[inline]
+    pub fn tuple_data(&self) -> &[TupleData] {
+        &self.0
+    }
+
+    fn parse(buf: &mut Buffer) -> io::Result<Tuple> {
+        let col_len = buf.read_i16::<BigEndian>()?;
+        let mut tuple = Vec::with_capacity(col_len as usize);
+        for _ in 0..col_len {
+            tuple.push(TupleData::parse(buf)?);
+        }
+
+        Ok(Tuple(tuple))
+    }
+}
+
+/// A column as it appears in the replication stream.
+#[derive(Debug)]
+pub struct Column {
+    flags: i8,
+    name: Bytes,
+    type_id: Oid,
+    type_modifier: i32,
+}
+
+impl Column {
+    #[inline]
+    pub fn flags(&self) -> i8 {
+        self.flags
+    }
+
+    #[inline]
+    pub fn name(&self) -> io::Result<&str> {
+        get_str(&self.name)
+    }
+
+    #[inline]
+    pub fn type_id(&self) -> Oid {
+        self.type_id
+    }
+
+    #[inline]
+    pub fn type_modifier(&self) -> i32 {
+        self.type_modifier
+    }
+
+    fn parse(buf: &mut Buffer) -> io::Result<Column> {
+        Ok(Column {
+            flags: buf.read_i8()?,
+            name: buf.read_cstr()?,
+            type_id: buf.read_u32::<BigEndian>()?,
+            type_modifier: buf.read_i32::<BigEndian>()?,
+        })
+    }
+}
+
+/// The data of an individual column as it appears in the replication stream.
+#[derive(Debug)]
+pub enum TupleData {
+    /// Represents a NULL value.
+    Null,
+    /// Represents an unchanged TOASTed value (the actual value is not sent).
+    UnchangedToast,
+    /// Column data as text formatted value.
+    Text(Bytes),
+    /// Column data as binary formatted value.
+    Binary(Bytes),
+}
+
+impl TupleData {
+    fn parse(buf: &mut Buffer) -> io::Result<TupleData> {
+        let type_tag = buf.read_u8()?;
+
+        let tuple = match type_tag {
+            TUPLE_DATA_NULL_TAG => TupleData::Null,
+            TUPLE_DATA_TOAST_TAG => TupleData::UnchangedToast,
+            TUPLE_DATA_TEXT_TAG => {
+                let len = buf.read_i32::<BigEndian>()?;
+                TupleData::Text(buf.read_buf(len as usize)?)
+            }
+            TUPLE_DATA_BINARY_TAG => {
+                let len = buf.read_i32::<BigEndian>()?;
+                TupleData::Binary(buf.read_buf(len as usize)?)
+            }
+            tag => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown tuple data tag `{}`", tag),
+                ));
+            }
+        };
+
+        Ok(tuple)
+    }
+}
+
+/// A BEGIN statement.
+#[derive(Debug)]
+pub struct BeginBody {
+    final_lsn: u64,
+    timestamp: i64,
+    xid: u32,
+}
+
+impl BeginBody {
+    #[inline]
+    pub fn final_lsn(&self) -> u64 {
+        self.final_lsn
+    }
+
+    #[inline]
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    #[inline]
+    pub fn xid(&self) -> u32 {
+        self.xid
+    }
+}
+
+/// A COMMIT statement.
+#[derive(Debug)]
+pub struct CommitBody {
+    flags: i8,
+    commit_lsn: u64,
+    end_lsn: u64,
+    timestamp: i64,
+}
+
+impl CommitBody {
+    #[inline]
+    pub fn flags(&self) -> i8 {
+        self.flags
+    }
+
+    #[inline]
+    pub fn commit_lsn(&self) -> u64 {
+        self.commit_lsn
+    }
+
+    #[inline]
+    pub fn end_lsn(&self) -> u64 {
+        self.end_lsn
+    }
+
+    #[inline]
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+/// The origin of a transaction, sent when it was not generated locally.
+#[derive(Debug)]
+pub struct OriginBody {
+    commit_lsn: u64,
+    name: Bytes,
+}
+
+impl OriginBody {
+    #[inline]
+    pub fn commit_lsn(&self) -> u64 {
+        self.commit_lsn
+    }
+
+    #[inline]
+    pub fn name(&self) -> io::Result<&str> {
+        get_str(&self.name)
+    }
+}
+
+/// Describes the REPLICA IDENTITY setting of a table.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReplicaIdentity {
+    /// The primary key, if one exists.
+    Default,
+    /// No replica identity.
+    Nothing,
+    /// All columns of the row.
+    Full,
+    /// The columns of a specific unique index.
+    Index,
+}
+
+/// The schema of a table, sent before the first change to it in a session and whenever it changes.
+#[derive(Debug)]
+pub struct RelationBody {
+    rel_id: u32,
+    namespace: Bytes,
+    name: Bytes,
+    replica_identity: ReplicaIdentity,
+    columns: Vec<Column>,
+}
+
+impl RelationBody {
+    #[inline]
+    pub fn rel_id(&self) -> u32 {
+        self.rel_id
+    }
+
+    #[inline]
+    pub fn namespace(&self) -> io::Result<&str> {
+        get_str(&self.namespace)
+    }
+
+    #[inline]
+    pub fn name(&self) -> io::Result<&str> {
+        get_str(&self.name)
+    }
+
+    #[inline]
+    pub fn replica_identity(&self) -> &ReplicaIdentity {
+        &self.replica_identity
+    }
+
+    #[inline]
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+}
+
+/// A user defined type referenced by a subsequent relation.
+#[derive(Debug)]
+pub struct TypeBody {
+    id: Oid,
+    namespace: Bytes,
+    name: Bytes,
+}
+
+impl TypeBody {
+    #[inline]
+    pub fn id(&self) -> Oid {
+        self.id
+    }
+
+    #[inline]
+    pub fn namespace(&self) -> io::Result<&str> {
+        get_str(&self.namespace)
+    }
+
+    #[inline]
+    pub fn name(&self) -> io::Result<&str> {
+        get_str(&self.name)
+    }
+}
+
+/// An INSERT statement.
+#[derive(Debug)]
+pub struct InsertBody {
+    rel_id: u32,
+    tuple: Tuple,
+}
+
+impl InsertBody {
+    #[inline]
+    pub fn rel_id(&self) -> u32 {
+        self.rel_id
+    }
+
+    #[inline]
+    pub fn tuple(&self) -> &Tuple {
+        &self.tuple
+    }
+}
+
+/// An UPDATE statement.
+#[derive(Debug)]
+pub struct UpdateBody {
+    rel_id: u32,
+    old_tuple: Option<Tuple>,
+    key_tuple: Option<Tuple>,
+    new_tuple: Tuple,
+}
+
+impl UpdateBody {
+    #[inline]
+    pub fn rel_id(&self) -> u32 {
+        self.rel_id
+    }
+
+    /// The old values of the row, present if the table's replica identity is `FULL`.
+    #[inline]
+    pub fn old_tuple(&self) -> Option<&Tuple> {
+        self.old_tuple.as_ref()
+    }
+
+    /// The old values of the replica identity columns, present if any of them changed.
+    #[inline]
+    pub fn key_tuple(&self) -> Option<&Tuple> {
+        self.key_tuple.as_ref()
+    }
+
+    #[inline]
+    pub fn new_tuple(&self) -> &Tuple {
+        &self.new_tuple
+    }
+}
+
+/// A DELETE statement.
+#[derive(Debug)]
+pub struct DeleteBody {
+    rel_id: u32,
+    old_tuple: Option<Tuple>,
+    key_tuple: Option<Tuple>,
+}
+
+impl DeleteBody {
+    #[inline]
+    pub fn rel_id(&self) -> u32 {
+        self.rel_id
+    }
+
+    /// The old values of the row, present if the table's replica identity is `FULL`.
+    #[inline]
+    pub fn old_tuple(&self) -> Option<&Tuple> {
+        self.old_tuple.as_ref()
+    }
+
+    /// The old values of the replica identity columns, present otherwise.
+    #[inline]
+    pub fn key_tuple(&self) -> Option<&Tuple> {
+        self.key_tuple.as_ref()
+    }
+}
+
+/// A TRUNCATE statement.
+#[derive(Debug)]
+pub struct TruncateBody {
+    options: i8,
+    rel_ids: Vec<u32>,
+}
+
+impl TruncateBody {
+    /// A bitmask of `CASCADE` (1) and `RESTART IDENTITY` (2).
+    #[inline]
+    pub fn options(&self) -> i8 {
+        self.options
+    }
+
+    #[inline]
+    pub fn rel_ids(&self) -> &[u32] {
+        &self.rel_ids
+    }
+}
+
+#[inline]
+fn write_authentication<F>(code: i32, buf: &mut BytesMut, f: F) -> io::Result<()>
+where
+    F: FnOnce(&mut BytesMut) -> io::Result<()>,
+{
+    buf.put_u8(AUTHENTICATION_TAG);
+    write_body(buf, |buf| {
+        buf.put_i32(code);
+        f(buf)
+    })
+}
+
+#[inline]
+pub fn authentication_ok(buf: &mut BytesMut) {
+    write_authentication(0, buf, |_| Ok(())).unwrap();
+}
+
+#[inline]
+pub fn authentication_kerberos_v5(buf: &mut BytesMut) {
+    write_authentication(2, buf, |_| Ok(())).unwrap();
+}
+
+#[inline]
+pub fn authentication_cleartext_password(buf: &mut BytesMut) {
+    write_authentication(3, buf, |_| Ok(())).unwrap();
+}
+
+#[inline]
+pub fn authentication_md5_password(salt: [u8; 4], buf: &mut BytesMut) {
+    write_authentication(5, buf, |buf| {
+        buf.put_slice(&salt);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[inline]
+pub fn authentication_scm_credential(buf: &mut BytesMut) {
+    write_authentication(6, buf, |_| Ok(())).unwrap();
+}
+
+#[inline]
+pub fn authentication_gss(buf: &mut BytesMut) {
+    write_authentication(7, buf, |_| Ok(())).unwrap();
+}
+
+#[inline]
+pub fn authentication_gss_continue(data: &[u8], buf: &mut BytesMut) -> io::Result<()> {
+    write_authentication(8, buf, |buf| {
+        buf.put_slice(data);
+        Ok(())
+    })
+}
+
+#[inline]
+pub fn authentication_sspi(buf: &mut BytesMut) {
+    write_authentication(9, buf, |_| Ok(())).unwrap();
+}
+
+#[inline]
+pub fn authentication_sasl<'a, I>(mechanisms: I, buf: &mut BytesMut) -> io::Result<()>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    write_authentication(10, buf, |buf| {
+        for mechanism in mechanisms {
+            write_cstr(mechanism.as_bytes(), buf)?;
+        }
+        buf.put_u8(0);
+        Ok(())
+    })
+}
+
+#[inline]
+pub fn authentication_sasl_continue(data: &[u8], buf: &mut BytesMut) -> io::Result<()> {
+    write_authentication(11, buf, |buf| {
+        buf.put_slice(data);
+        Ok(())
+    })
+}
+
+#[inline]
+pub fn authentication_sasl_final(data: &[u8], buf: &mut BytesMut) -> io::Result<()> {
+    write_authentication(12, buf, |buf| {
+        buf.put_slice(data);
+        Ok(())
+    })
+}
+
+#[inline]
+pub fn backend_key_data(process_id: i32, secret_key: i32, buf: &mut BytesMut) {
+    buf.put_u8(BACKEND_KEY_DATA_TAG);
+    write_body(buf, |buf| {
+        buf.put_i32(process_id);
+        buf.put_i32(secret_key);
+        Ok::<_, io::Error>(())
+    })
+    .unwrap();
+}
+
+#[inline]
+pub fn bind_complete(buf: &mut BytesMut) {
+    buf.put_u8(BIND_COMPLETE_TAG);
+    write_body(buf, |_| Ok::<(), io::Error>(())).unwrap();
+}
+
+#[inline]
+pub fn close_complete(buf: &mut BytesMut) {
+    buf.put_u8(CLOSE_COMPLETE_TAG);
+    write_body(buf, |_| Ok::<(), io::Error>(())).unwrap();
+}
+
+#[inline]
+pub fn command_complete(tag: &str, buf: &mut BytesMut) -> io::Result<()> {
+    buf.put_u8(COMMAND_COMPLETE_TAG);
+    write_body(buf, |buf| write_cstr(tag.as_bytes(), buf))
+}
+
+#[inline]
+pub fn copy_data(data: &[u8], buf: &mut BytesMut) -> io::Result<()> {
+    buf.put_u8(COPY_DATA_TAG);
+    write_body(buf, |buf| {
+        buf.put_slice(data);
+        Ok(())
+    })
+}
+
+#[inline]
+pub fn copy_done(buf: &mut BytesMut) {
+    buf.put_u8(COPY_DONE_TAG);
+    write_body(buf, |_| Ok::<(), io::Error>(())).unwrap();
+}
+
+#[inline]
+fn write_copy_response<I>(
+    tag: u8,
+    format: u8,
+    column_formats: I,
+    buf: &mut BytesMut,
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = u16>,
+{
+    buf.put_u8(tag);
+    write_body(buf, |buf| {
+        buf.put_u8(format);
+        write_counted(
+            column_formats,
+            |f, buf| {
+                buf.put_u16(f);
+                Ok::<_, io::Error>(())
+            },
+            buf,
+        )
+    })
+}
+
+#[inline]
+pub fn copy_in_response<I>(format: u8, column_formats: I, buf: &mut BytesMut) -> io::Result<()>
+where
+    I: IntoIterator<Item = u16>,
+{
+    write_copy_response(COPY_IN_RESPONSE_TAG, format, column_formats, buf)
+}
+
+#[inline]
+pub fn copy_out_response<I>(format: u8, column_formats: I, buf: &mut BytesMut) -> io::Result<()>
+where
+    I: IntoIterator<Item = u16>,
+{
+    write_copy_response(COPY_OUT_RESPONSE_TAG, format, column_formats, buf)
+}
+
+#[inline]
+pub fn copy_both_response<I>(format: u8, column_formats: I, buf: &mut BytesMut) -> io::Result<()>
+where
+    I: IntoIterator<Item = u16>,
+{
+    write_copy_response(COPY_BOTH_RESPONSE_TAG, format, column_formats, buf)
+}
+
+#[inline]
+pub fn data_row<'a, I>(values: I, buf: &mut BytesMut) -> io::Result<()>
+where
+    I: IntoIterator<Item = Option<&'a [u8]>>,
+{
+    buf.put_u8(DATA_ROW_TAG);
+    write_body(buf, |buf| {
+        write_counted(
+            values,
+            |v, buf| match v {
+                Some(v) => {
+                    buf.put_i32(i32::from_usize(v.len())?);
+                    buf.put_slice(v);
+                    Ok(())
+                }
+                None => {
+                    buf.put_i32(-1);
+                    Ok::<_, io::Error>(())
+                }
+            },
+            buf,
+        )
+    })
+}
+
+#[inline]
+pub fn empty_query_response(buf: &mut BytesMut) {
+    buf.put_u8(EMPTY_QUERY_RESPONSE_TAG);
+    write_body(buf, |_| Ok::<(), io::Error>(())).unwrap();
+}
+
+#[inline]
+fn write_fields<'a, I>(tag: u8, fields: I, buf: &mut BytesMut) -> io::Result<()>
+where
+    I: IntoIterator<Item = (u8, &'a str)>,
+{
+    buf.put_u8(tag);
+    write_body(buf, |buf| {
+        for (type_, value) in fields {
+            if type_ == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "field type cannot be 0",
+                ));
+            }
+            buf.put_u8(type_);
+            write_cstr(value.as_bytes(), buf)?;
+        }
+        buf.put_u8(0);
+        Ok(())
+    })
+}
+
+/// Writes an `ErrorResponse` message from its fields, identified by type codes such as `b'S'` and `b'M'`.
+#[inline]
+pub fn error_response<'a, I>(fields: I, buf: &mut BytesMut) -> io::Result<()>
+where
+    I: IntoIterator<Item = (u8, &'a str)>,
+{
+    write_fields(ERROR_RESPONSE_TAG, fields, buf)
+}
+
+#[inline]
+pub fn function_call_response(value: Option<&[u8]>, buf: &mut BytesMut) -> io::Result<()> {
+    buf.put_u8(FUNCTION_CALL_RESPONSE_TAG);
+    write_body(buf, |buf| {
+        match value {
+            Some(value) => {
+                buf.put_i32(i32::from_usize(value.len())?);
+                buf.put_slice(value);
+            }
+            None => buf.put_i32(-1),
+        }
+        Ok(())
+    })
+}
+
+#[inline]
+pub fn no_data(buf: &mut BytesMut) {
+    buf.put_u8(NO_DATA_TAG);
+    write_body(buf, |_| Ok::<(), io::Error>(())).unwrap();
+}
+
+/// Writes a `NoticeResponse` message from its fields, identified by type codes such as `b'S'` and `b'M'`.
+#[inline]
+pub fn notice_response<'a, I>(fields: I, buf: &mut BytesMut) -> io::Result<()>
+where
+    I: IntoIterator<Item = (u8, &'a str)>,
+{
+    write_fields(NOTICE_RESPONSE_TAG, fields, buf)
+}
+
+#[inline]
+pub fn notification_response(
+    process_id: i32,
+    channel: &str,
+    message: &str,
+    buf: &mut BytesMut,
+) -> io::Result<()> {
+    buf.put_u8(NOTIFICATION_RESPONSE_TAG);
+    write_body(buf, |buf| {
+        buf.put_i32(process_id);
+        write_cstr(channel.as_bytes(), buf)?;
+        write_cstr(message.as_bytes(), buf)
+    })
+}
+
+#[inline]
+pub fn parameter_description<I>(parameters: I, buf: &mut BytesMut) -> io::Result<()>
+where
+    I: IntoIterator<Item = Oid>,
+{
+    buf.put_u8(PARAMETER_DESCRIPTION_TAG);
+    write_body(buf, |buf| {
+        write_counted(
+            parameters,
+            |oid, buf| {
+                buf.put_u32(oid);
+                Ok::<_, io::Error>(())
+            },
+            buf,
+        )
+    })
+}
+
+#[inline]
+pub fn parameter_status(name: &str, value: &str, buf: &mut BytesMut) -> io::Result<()> {
+    buf.put_u8(PARAMETER_STATUS_TAG);
+    write_body(buf, |buf| {
+        write_cstr(name.as_bytes(), buf)?;
+        write_cstr(value.as_bytes(), buf)
+    })
+}
+
+#[inline]
+pub fn parse_complete(buf: &mut BytesMut) {
+    buf.put_u8(PARSE_COMPLETE_TAG);
+    write_body(buf, |_| Ok::<(), io::Error>(())).unwrap();
+}
+
+#[inline]
+pub fn portal_suspended(buf: &mut BytesMut) {
+    buf.put_u8(PORTAL_SUSPENDED_TAG);
+    write_body(buf, |_| Ok::<(), io::Error>(())).unwrap();
+}
+
+/// Writes a `ReadyForQuery` message with a transaction status of `b'I'` (idle), `b'T'` (in a transaction) or `b'E'`
+/// (in a failed transaction).
+#[inline]
+pub fn ready_for_query(status: u8, buf: &mut BytesMut) {
+    buf.put_u8(READY_FOR_QUERY_TAG);
+    write_body(buf, |buf| {
+        buf.put_u8(status);
+        Ok::<_, io::Error>(())
+    })
+    .unwrap();
+}
+
+#[inline]
+pub fn row_description<'a, I>(fields: I, buf: &mut BytesMut) -> io::Result<()>
+where
+    I: IntoIterator<Item = Field<'a>>,
+{
+    buf.put_u8(ROW_DESCRIPTION_TAG);
+    write_body(buf, |buf| {
+        write_counted(
+            fields,
+            |field, buf| {
+                write_cstr(field.name.as_bytes(), buf)?;
+                buf.put_u32(field.table_oid);
+                buf.put_i16(field.column_id);
+                buf.put_u32(field.type_oid);
+                buf.put_i16(field.type_size);
+                buf.put_i32(field.type_modifier);
+                buf.put_i16(field.format);
+                Ok::<_, io::Error>(())
+            },
+            buf,
+        )
+    })
+}
+
 #[inline]
-fn find_null(buf: &[u8], start: usize) -> io::Result<usize> {
+pub(crate) fn find_null(buf: &[u8], start: usize) -> io::Result<usize> {
     match memchr(0, &buf[start..]) {
         Some(pos) => Ok(pos + start),
         None => Err(io::Error::new(
@@ -880,6 +2062,6 @@ fn find_null(buf: &[u8], start: usize) -> io::Result<usize> {
 }
 
 #[inline]
-fn get_str(buf: &[u8]) -> io::Result<&str> {
+pub(crate) fn get_str(buf: &[u8]) -> io::Result<&str> {
     str::from_utf8(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
 }
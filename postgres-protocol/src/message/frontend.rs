@@ -1,17 +1,40 @@
-//! Frontend message serialization.
+//! Frontend message serialization and parsing.
 #![allow(missing_docs)]
 
-use byteorder::{BigEndian, ByteOrder};
-use bytes::{Buf, BufMut, BytesMut};
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use fallible_iterator::FallibleIterator;
 use std::convert::TryFrom;
 use std::error::Error;
 use std::io;
 use std::marker;
 
+use crate::message::backend::{find_null, get_str, Buffer};
 use crate::{write_nullable, FromUsize, IsNull, Oid};
 
+pub const BIND_TAG: u8 = b'B';
+pub const CLOSE_TAG: u8 = b'C';
+pub const COPY_DATA_TAG: u8 = b'd';
+pub const COPY_DONE_TAG: u8 = b'c';
+pub const COPY_FAIL_TAG: u8 = b'f';
+pub const DESCRIBE_TAG: u8 = b'D';
+pub const EXECUTE_TAG: u8 = b'E';
+pub const FLUSH_TAG: u8 = b'H';
+pub const FUNCTION_CALL_TAG: u8 = b'F';
+pub const PARSE_TAG: u8 = b'P';
+pub const PASSWORD_MESSAGE_TAG: u8 = b'p';
+pub const QUERY_TAG: u8 = b'Q';
+pub const SYNC_TAG: u8 = b'S';
+pub const TERMINATE_TAG: u8 = b'X';
+
+// request codes of the untagged messages sent at the start of a connection
+pub const PROTOCOL_VERSION: i32 = 196_608;
+pub const CANCEL_REQUEST_CODE: i32 = 80_877_102;
+pub const SSL_REQUEST_CODE: i32 = 80_877_103;
+pub const GSSENC_REQUEST_CODE: i32 = 80_877_104;
+
 #[inline]
-fn write_body<F, E>(buf: &mut BytesMut, f: F) -> Result<(), E>
+pub(crate) fn write_body<F, E>(buf: &mut BytesMut, f: F) -> Result<(), E>
 where
     F: FnOnce(&mut BytesMut) -> Result<(), E>,
     E: From<io::Error>,
@@ -93,7 +116,11 @@ where
 }
 
 #[inline]
-fn write_counted<I, T, F, E>(items: I, mut serializer: F, buf: &mut BytesMut) -> Result<(), E>
+pub(crate) fn write_counted<I, T, F, E>(
+    items: I,
+    mut serializer: F,
+    buf: &mut BytesMut,
+) -> Result<(), E>
 where
     I: IntoIterator<Item = T>,
     F: FnMut(T, &mut BytesMut) -> Result<(), E>,
@@ -115,7 +142,7 @@ where
 #[inline]
 pub fn cancel_request(process_id: i32, secret_key: i32, buf: &mut BytesMut) {
     write_body(buf, |buf| {
-        buf.put_i32(80_877_102);
+        buf.put_i32(CANCEL_REQUEST_CODE);
         buf.put_i32(process_id);
         buf.put_i32(secret_key);
         Ok::<_, io::Error>(())
@@ -191,6 +218,52 @@ pub fn execute(portal: &str, max_rows: i32, buf: &mut BytesMut) -> io::Result<()
     })
 }
 
+/// Writes a `FunctionCall` message, which calls a function through the "fast-path" interface.
+///
+/// `formats` gives the formats of the arguments, which are `NULL` when `None`.
+#[inline]
+pub fn function_call<'a, I, J>(
+    oid: Oid,
+    formats: I,
+    args: J,
+    result_format: i16,
+    buf: &mut BytesMut,
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = i16>,
+    J: IntoIterator<Item = Option<&'a [u8]>>,
+{
+    buf.put_u8(FUNCTION_CALL_TAG);
+    write_body(buf, |buf| {
+        buf.put_u32(oid);
+        write_counted(
+            formats,
+            |f, buf| {
+                buf.put_i16(f);
+                Ok::<_, io::Error>(())
+            },
+            buf,
+        )?;
+        write_counted(
+            args,
+            |arg, buf| match arg {
+                Some(arg) => {
+                    buf.put_i32(i32::from_usize(arg.len())?);
+                    buf.put_slice(arg);
+                    Ok(())
+                }
+                None => {
+                    buf.put_i32(-1);
+                    Ok::<_, io::Error>(())
+                }
+            },
+            buf,
+        )?;
+        buf.put_i16(result_format);
+        Ok(())
+    })
+}
+
 #[inline]
 pub fn parse<I>(name: &str, query: &str, param_types: I, buf: &mut BytesMut) -> io::Result<()>
 where
@@ -245,10 +318,28 @@ pub fn sasl_response(data: &[u8], buf: &mut BytesMut) -> io::Result<()> {
     })
 }
 
+/// Writes a standby status update, which is sent to the server in a `CopyData` message during streaming replication.
+#[inline]
+pub fn standby_status_update(
+    write_lsn: u64,
+    flush_lsn: u64,
+    apply_lsn: u64,
+    timestamp: i64,
+    reply: u8,
+    buf: &mut BytesMut,
+) {
+    buf.put_u8(b'r');
+    buf.put_u64(write_lsn);
+    buf.put_u64(flush_lsn);
+    buf.put_u64(apply_lsn);
+    buf.put_i64(timestamp);
+    buf.put_u8(reply);
+}
+
 #[inline]
 pub fn ssl_request(buf: &mut BytesMut) {
     write_body(buf, |buf| {
-        buf.put_i32(80_877_103);
+        buf.put_i32(SSL_REQUEST_CODE);
         Ok::<_, io::Error>(())
     })
     .unwrap();
@@ -260,7 +351,7 @@ where
     I: IntoIterator<Item = (&'a str, &'a str)>,
 {
     write_body(buf, |buf| {
-        buf.put_i32(196_608);
+        buf.put_i32(PROTOCOL_VERSION);
         for (key, value) in parameters {
             write_cstr(key.as_bytes(), buf)?;
             write_cstr(value.as_bytes(), buf)?;
@@ -283,7 +374,7 @@ pub fn terminate(buf: &mut BytesMut) {
 }
 
 #[inline]
-fn write_cstr(s: &[u8], buf: &mut BytesMut) -> Result<(), io::Error> {
+pub(crate) fn write_cstr(s: &[u8], buf: &mut BytesMut) -> Result<(), io::Error> {
     if s.contains(&0) {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
@@ -294,3 +385,651 @@ fn write_cstr(s: &[u8], buf: &mut BytesMut) -> Result<(), io::Error> {
     buf.put_u8(0);
     Ok(())
 }
+
+/// An enum representing the untagged messages which Postgres frontends send at the start of a connection.
+#[non_exhaustive]
+pub enum StartupMessage {
+    Startup(StartupBody),
+    SslRequest,
+    GssEncRequest,
+    CancelRequest(CancelRequestBody),
+}
+
+impl StartupMessage {
+    #[inline]
+    pub fn parse(buf: &mut BytesMut) -> io::Result<Option<StartupMessage>> {
+        if buf.len() < 4 {
+            let to_read = 4 - buf.len();
+            buf.reserve(to_read);
+            return Ok(None);
+        }
+
+        let len = (&buf[..4]).read_u32::<BigEndian>().unwrap();
+
+        if len < 8 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid message length: parsing u32",
+            ));
+        }
+
+        let total_len = len as usize;
+        if buf.len() < total_len {
+            let to_read = total_len - buf.len();
+            buf.reserve(to_read);
+            return Ok(None);
+        }
+
+        let mut buf = Buffer {
+            bytes: buf.split_to(total_len).freeze(),
+            idx: 4,
+        };
+
+        let message = match buf.read_i32::<BigEndian>()? {
+            CANCEL_REQUEST_CODE => {
+                let process_id = buf.read_i32::<BigEndian>()?;
+                let secret_key = buf.read_i32::<BigEndian>()?;
+                StartupMessage::CancelRequest(CancelRequestBody {
+                    process_id,
+                    secret_key,
+                })
+            }
+            SSL_REQUEST_CODE => StartupMessage::SslRequest,
+            GSSENC_REQUEST_CODE => StartupMessage::GssEncRequest,
+            // the minor version may be newer than 0, in which case the server negotiates it down
+            version if version >> 16 == 3 => {
+                let storage = buf.read_all();
+                if storage.last() != Some(&0) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "invalid message: startup parameters are not terminated",
+                    ));
+                }
+                StartupMessage::Startup(StartupBody { version, storage })
+            }
+            version => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unsupported protocol version `{}`", version),
+                ));
+            }
+        };
+
+        if !buf.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid message length: expected buffer to be empty",
+            ));
+        }
+
+        Ok(Some(message))
+    }
+}
+
+pub struct StartupBody {
+    version: i32,
+    storage: Bytes,
+}
+
+impl StartupBody {
+    #[inline]
+    pub fn protocol_version(&self) -> i32 {
+        self.version
+    }
+
+    #[inline]
+    pub fn parameters(&self) -> StartupParameters<'_> {
+        StartupParameters(&self.storage)
+    }
+}
+
+pub struct StartupParameters<'a>(&'a [u8]);
+
+impl<'a> FallibleIterator for StartupParameters<'a> {
+    type Item = (&'a str, &'a str);
+    type Error = io::Error;
+
+    #[inline]
+    fn next(&mut self) -> io::Result<Option<(&'a str, &'a str)>> {
+        let key_end = find_null(self.0, 0)?;
+        if key_end == 0 {
+            if self.0.len() != 1 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "invalid message length: expected to be at end of iterator for startup parameters",
+                ));
+            }
+            return Ok(None);
+        }
+
+        let value_end = find_null(self.0, key_end + 1)?;
+        let key = get_str(&self.0[..key_end])?;
+        let value = get_str(&self.0[key_end + 1..value_end])?;
+        self.0 = &self.0[value_end + 1..];
+        Ok(Some((key, value)))
+    }
+}
+
+pub struct CancelRequestBody {
+    process_id: i32,
+    secret_key: i32,
+}
+
+impl CancelRequestBody {
+    #[inline]
+    pub fn process_id(&self) -> i32 {
+        self.process_id
+    }
+
+    #[inline]
+    pub fn secret_key(&self) -> i32 {
+        self.secret_key
+    }
+}
+
+/// An enum representing Postgres frontend messages.
+#[non_exhaustive]
+pub enum Message {
+    Bind(BindBody),
+    Close(CloseBody),
+    CopyData(CopyDataBody),
+    CopyDone,
+    CopyFail(CopyFailBody),
+    Describe(DescribeBody),
+    Execute(ExecuteBody),
+    Flush,
+    FunctionCall(FunctionCallBody),
+    Parse(ParseBody),
+    PasswordMessage(PasswordMessageBody),
+    Query(QueryBody),
+    Sync,
+    Terminate,
+}
+
+impl Message {
+    #[inline]
+    pub fn parse(buf: &mut BytesMut) -> io::Result<Option<Message>> {
+        if buf.len() < 5 {
+            let to_read = 5 - buf.len();
+            buf.reserve(to_read);
+            return Ok(None);
+        }
+
+        let tag = buf[0];
+        let len = (&buf[1..5]).read_u32::<BigEndian>().unwrap();
+
+        if len < 4 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid message length: parsing u32",
+            ));
+        }
+
+        let total_len = len as usize + 1;
+        if buf.len() < total_len {
+            let to_read = total_len - buf.len();
+            buf.reserve(to_read);
+            return Ok(None);
+        }
+
+        let mut buf = Buffer {
+            bytes: buf.split_to(total_len).freeze(),
+            idx: 5,
+        };
+
+        let message = match tag {
+            BIND_TAG => {
+                let portal = buf.read_cstr()?;
+                let statement = buf.read_cstr()?;
+                let len = buf.read_u16::<BigEndian>()?;
+                let formats = buf.read_buf(len as usize * 2)?;
+                let values_len = buf.read_u16::<BigEndian>()?;
+                let values_start = buf.idx;
+                for _ in 0..values_len {
+                    let len = buf.read_i32::<BigEndian>()?;
+                    if len > 0 {
+                        buf.read_buf(len as usize)?;
+                    }
+                }
+                let values = buf.bytes.slice(values_start..buf.idx);
+                let len = buf.read_u16::<BigEndian>()?;
+                let result_formats = buf.read_buf(len as usize * 2)?;
+                Message::Bind(BindBody {
+                    portal,
+                    statement,
+                    formats,
+                    values,
+                    values_len,
+                    result_formats,
+                })
+            }
+            CLOSE_TAG => {
+                let variant = buf.read_u8()?;
+                let name = buf.read_cstr()?;
+                Message::Close(CloseBody { variant, name })
+            }
+            COPY_DATA_TAG => {
+                let storage = buf.read_all();
+                Message::CopyData(CopyDataBody { storage })
+            }
+            COPY_DONE_TAG => Message::CopyDone,
+            COPY_FAIL_TAG => {
+                let message = buf.read_cstr()?;
+                Message::CopyFail(CopyFailBody { message })
+            }
+            DESCRIBE_TAG => {
+                let variant = buf.read_u8()?;
+                let name = buf.read_cstr()?;
+                Message::Describe(DescribeBody { variant, name })
+            }
+            EXECUTE_TAG => {
+                let portal = buf.read_cstr()?;
+                let max_rows = buf.read_i32::<BigEndian>()?;
+                Message::Execute(ExecuteBody { portal, max_rows })
+            }
+            FLUSH_TAG => Message::Flush,
+            FUNCTION_CALL_TAG => {
+                let oid = buf.read_u32::<BigEndian>()?;
+                let len = buf.read_u16::<BigEndian>()?;
+                let formats = buf.read_buf(len as usize * 2)?;
+                let args_len = buf.read_u16::<BigEndian>()?;
+                let args_start = buf.idx;
+                for _ in 0..args_len {
+                    let len = buf.read_i32::<BigEndian>()?;
+                    if len > 0 {
+                        buf.read_buf(len as usize)?;
+                    }
+                }
+                let args = buf.bytes.slice(args_start..buf.idx);
+                let result_format = buf.read_i16::<BigEndian>()?;
+                Message::FunctionCall(FunctionCallBody {
+                    oid,
+                    formats,
+                    args,
+                    args_len,
+                    result_format,
+                })
+            }
+            PARSE_TAG => {
+                let name = buf.read_cstr()?;
+                let query = buf.read_cstr()?;
+                let len = buf.read_u16::<BigEndian>()?;
+                let parameter_types = buf.read_buf(len as usize * 4)?;
+                Message::Parse(ParseBody {
+                    name,
+                    query,
+                    parameter_types,
+                })
+            }
+            PASSWORD_MESSAGE_TAG => {
+                let storage = buf.read_all();
+                Message::PasswordMessage(PasswordMessageBody { storage })
+            }
+            QUERY_TAG => {
+                let query = buf.read_cstr()?;
+                Message::Query(QueryBody { query })
+            }
+            SYNC_TAG => Message::Sync,
+            TERMINATE_TAG => Message::Terminate,
+            tag => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown message tag `{}`", tag),
+                ));
+            }
+        };
+
+        if !buf.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid message length: expected buffer to be empty",
+            ));
+        }
+
+        Ok(Some(message))
+    }
+}
+
+pub struct BindBody {
+    portal: Bytes,
+    statement: Bytes,
+    formats: Bytes,
+    values: Bytes,
+    values_len: u16,
+    result_formats: Bytes,
+}
+
+impl BindBody {
+    #[inline]
+    pub fn portal(&self) -> io::Result<&str> {
+        get_str(&self.portal)
+    }
+
+    #[inline]
+    pub fn statement(&self) -> io::Result<&str> {
+        get_str(&self.statement)
+    }
+
+    #[inline]
+    pub fn formats(&self) -> Formats<'_> {
+        Formats(&self.formats)
+    }
+
+    #[inline]
+    pub fn values(&self) -> BindValues<'_> {
+        BindValues {
+            buf: &self.values,
+            remaining: self.values_len,
+        }
+    }
+
+    #[inline]
+    pub fn result_formats(&self) -> Formats<'_> {
+        Formats(&self.result_formats)
+    }
+}
+
+pub struct Formats<'a>(&'a [u8]);
+
+impl<'a> FallibleIterator for Formats<'a> {
+    type Item = i16;
+    type Error = io::Error;
+
+    #[inline]
+    fn next(&mut self) -> io::Result<Option<i16>> {
+        if self.0.is_empty() {
+            return Ok(None);
+        }
+
+        self.0.read_i16::<BigEndian>().map(Some)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.len() / 2;
+        (len, Some(len))
+    }
+}
+
+pub struct BindValues<'a> {
+    buf: &'a [u8],
+    remaining: u16,
+}
+
+impl<'a> FallibleIterator for BindValues<'a> {
+    type Item = Option<&'a [u8]>;
+    type Error = io::Error;
+
+    #[inline]
+    fn next(&mut self) -> io::Result<Option<Option<&'a [u8]>>> {
+        if self.remaining == 0 {
+            if self.buf.is_empty() {
+                return Ok(None);
+            } else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "invalid message length: bind values are not drained",
+                ));
+            }
+        }
+
+        self.remaining -= 1;
+        let len = self.buf.read_i32::<BigEndian>()?;
+        if len < 0 {
+            Ok(Some(None))
+        } else {
+            let len = len as usize;
+            if self.buf.len() < len {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "unexpected EOF",
+                ));
+            }
+            let (value, rest) = self.buf.split_at(len);
+            self.buf = rest;
+            Ok(Some(Some(value)))
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining as usize;
+        (len, Some(len))
+    }
+}
+
+pub struct CloseBody {
+    variant: u8,
+    name: Bytes,
+}
+
+impl CloseBody {
+    #[inline]
+    pub fn variant(&self) -> u8 {
+        self.variant
+    }
+
+    #[inline]
+    pub fn name(&self) -> io::Result<&str> {
+        get_str(&self.name)
+    }
+}
+
+pub struct CopyDataBody {
+    storage: Bytes,
+}
+
+impl CopyDataBody {
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.storage
+    }
+
+    #[inline]
+    pub fn into_bytes(self) -> Bytes {
+        self.storage
+    }
+}
+
+pub struct CopyFailBody {
+    message: Bytes,
+}
+
+impl CopyFailBody {
+    #[inline]
+    pub fn message(&self) -> io::Result<&str> {
+        get_str(&self.message)
+    }
+}
+
+pub struct DescribeBody {
+    variant: u8,
+    name: Bytes,
+}
+
+impl DescribeBody {
+    #[inline]
+    pub fn variant(&self) -> u8 {
+        self.variant
+    }
+
+    #[inline]
+    pub fn name(&self) -> io::Result<&str> {
+        get_str(&self.name)
+    }
+}
+
+pub struct ExecuteBody {
+    portal: Bytes,
+    max_rows: i32,
+}
+
+impl ExecuteBody {
+    #[inline]
+    pub fn portal(&self) -> io::Result<&str> {
+        get_str(&self.portal)
+    }
+
+    #[inline]
+    pub fn max_rows(&self) -> i32 {
+        self.max_rows
+    }
+}
+
+pub struct FunctionCallBody {
+    oid: Oid,
+    formats: Bytes,
+    args: Bytes,
+    args_len: u16,
+    result_format: i16,
+}
+
+impl FunctionCallBody {
+    #[inline]
+    pub fn oid(&self) -> Oid {
+        self.oid
+    }
+
+    #[inline]
+    pub fn formats(&self) -> Formats<'_> {
+        Formats(&self.formats)
+    }
+
+    #[inline]
+    pub fn args(&self) -> BindValues<'_> {
+        BindValues {
+            buf: &self.args,
+            remaining: self.args_len,
+        }
+    }
+
+    #[inline]
+    pub fn result_format(&self) -> i16 {
+        self.result_format
+    }
+}
+
+pub struct ParseBody {
+    name: Bytes,
+    query: Bytes,
+    parameter_types: Bytes,
+}
+
+impl ParseBody {
+    #[inline]
+    pub fn name(&self) -> io::Result<&str> {
+        get_str(&self.name)
+    }
+
+    #[inline]
+    pub fn query(&self) -> io::Result<&str> {
+        get_str(&self.query)
+    }
+
+    #[inline]
+    pub fn parameter_types(&self) -> ParameterTypes<'_> {
+        ParameterTypes(&self.parameter_types)
+    }
+}
+
+pub struct ParameterTypes<'a>(&'a [u8]);
+
+impl<'a> FallibleIterator for ParameterTypes<'a> {
+    type Item = Oid;
+    type Error = io::Error;
+
+    #[inline]
+    fn next(&mut self) -> io::Result<Option<Oid>> {
+        if self.0.is_empty() {
+            return Ok(None);
+        }
+
+        self.0.read_u32::<BigEndian>().map(Some)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.len() / 4;
+        (len, Some(len))
+    }
+}
+
+/// The body of a `p` message.
+///
+/// The same tag is used for passwords, SASL responses and GSSAPI or SSPI responses, so the message can only be
+/// interpreted by a server which knows which kind of authentication it requested.
+pub struct PasswordMessageBody {
+    storage: Bytes,
+}
+
+impl PasswordMessageBody {
+    /// Returns the raw contents of the message, as sent in SASL, GSSAPI and SSPI responses.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.storage
+    }
+
+    /// Interprets the message as a cleartext or MD5-hashed password.
+    #[inline]
+    pub fn password(&self) -> io::Result<&[u8]> {
+        let end = find_null(&self.storage, 0)?;
+        if end + 1 != self.storage.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid message length: expected password to be at end of message",
+            ));
+        }
+        Ok(&self.storage[..end])
+    }
+
+    /// Interprets the message as the initial response of a SASL exchange.
+    #[inline]
+    pub fn sasl_initial_response(&self) -> io::Result<SaslInitialResponseBody<'_>> {
+        let mut buf = &self.storage[..];
+        let mechanism_end = find_null(buf, 0)?;
+        let mechanism = get_str(&buf[..mechanism_end])?;
+        buf = &buf[mechanism_end + 1..];
+        let len = buf.read_i32::<BigEndian>()?;
+        let data = if len < 0 {
+            None
+        } else if buf.len() == len as usize {
+            Some(buf)
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid message length: wrong SASL response length",
+            ));
+        };
+
+        Ok(SaslInitialResponseBody { mechanism, data })
+    }
+}
+
+pub struct SaslInitialResponseBody<'a> {
+    mechanism: &'a str,
+    data: Option<&'a [u8]>,
+}
+
+impl<'a> SaslInitialResponseBody<'a> {
+    #[inline]
+    pub fn mechanism(&self) -> &'a str {
+        self.mechanism
+    }
+
+    /// The initial client response, or `None` if the mechanism doesn't use one.
+    #[inline]
+    pub fn data(&self) -> Option<&'a [u8]> {
+        self.data
+    }
+}
+
+pub struct QueryBody {
+    query: Bytes,
+}
+
+impl QueryBody {
+    #[inline]
+    pub fn query(&self) -> io::Result<&str> {
+        get_str(&self.query)
+    }
+}
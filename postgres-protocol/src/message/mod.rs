@@ -6,3 +6,6 @@
 
 pub mod backend;
 pub mod frontend;
+
+#[cfg(test)]
+mod test;
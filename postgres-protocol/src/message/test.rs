@@ -0,0 +1,496 @@
+use bytes::BytesMut;
+use fallible_iterator::FallibleIterator;
+
+use super::backend::{self, Field};
+use super::frontend::{self, StartupMessage};
+use crate::IsNull;
+
+fn parse_frontend(buf: &mut BytesMut) -> frontend::Message {
+    let message = frontend::Message::parse(buf).unwrap().unwrap();
+    assert!(buf.is_empty());
+    message
+}
+
+fn parse_backend(buf: &mut BytesMut) -> backend::Message {
+    let message = backend::Message::parse(buf).unwrap().unwrap();
+    assert!(buf.is_empty());
+    message
+}
+
+#[test]
+fn startup_message() {
+    let mut buf = BytesMut::new();
+    frontend::startup_message(vec![("user", "postgres"), ("database", "foo")], &mut buf).unwrap();
+
+    // partial messages are left in the buffer
+    let mut partial = BytesMut::from(&buf[..buf.len() - 1]);
+    assert!(StartupMessage::parse(&mut partial).unwrap().is_none());
+    assert_eq!(partial.len(), buf.len() - 1);
+
+    let body = match StartupMessage::parse(&mut buf).unwrap().unwrap() {
+        StartupMessage::Startup(body) => body,
+        _ => panic!("unexpected message"),
+    };
+    assert!(buf.is_empty());
+    assert_eq!(body.protocol_version(), 196_608);
+    let parameters = body.parameters().collect::<Vec<_>>().unwrap();
+    assert_eq!(parameters, [("user", "postgres"), ("database", "foo")]);
+}
+
+#[test]
+fn ssl_request() {
+    let mut buf = BytesMut::new();
+    frontend::ssl_request(&mut buf);
+    match StartupMessage::parse(&mut buf).unwrap().unwrap() {
+        StartupMessage::SslRequest => {}
+        _ => panic!("unexpected message"),
+    }
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn cancel_request() {
+    let mut buf = BytesMut::new();
+    frontend::cancel_request(42, -7, &mut buf);
+    match StartupMessage::parse(&mut buf).unwrap().unwrap() {
+        StartupMessage::CancelRequest(body) => {
+            assert_eq!(body.process_id(), 42);
+            assert_eq!(body.secret_key(), -7);
+        }
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[test]
+fn bind() {
+    let mut buf = BytesMut::new();
+    frontend::bind(
+        "portal",
+        "stmt",
+        vec![1, 0],
+        vec![Some(&b"\x00\x01"[..]), None],
+        |v, buf| match v {
+            Some(v) => {
+                buf.extend_from_slice(v);
+                Ok(IsNull::No)
+            }
+            None => Ok(IsNull::Yes),
+        },
+        Some(1),
+        &mut buf,
+    )
+    .map_err(|_| ())
+    .unwrap();
+
+    let body = match parse_frontend(&mut buf) {
+        frontend::Message::Bind(body) => body,
+        _ => panic!("unexpected message"),
+    };
+    assert_eq!(body.portal().unwrap(), "portal");
+    assert_eq!(body.statement().unwrap(), "stmt");
+    assert_eq!(body.formats().collect::<Vec<_>>().unwrap(), [1, 0]);
+    assert_eq!(
+        body.values().collect::<Vec<_>>().unwrap(),
+        [Some(&b"\x00\x01"[..]), None]
+    );
+    assert_eq!(body.result_formats().collect::<Vec<_>>().unwrap(), [1]);
+}
+
+#[test]
+fn extended_query() {
+    let mut buf = BytesMut::new();
+    frontend::parse("stmt", "SELECT $1", Some(23), &mut buf).unwrap();
+    frontend::describe(b'S', "stmt", &mut buf).unwrap();
+    frontend::execute("portal", 10, &mut buf).unwrap();
+    frontend::close(b'P', "portal", &mut buf).unwrap();
+    frontend::sync(&mut buf);
+
+    match frontend::Message::parse(&mut buf).unwrap().unwrap() {
+        frontend::Message::Parse(body) => {
+            assert_eq!(body.name().unwrap(), "stmt");
+            assert_eq!(body.query().unwrap(), "SELECT $1");
+            assert_eq!(body.parameter_types().collect::<Vec<_>>().unwrap(), [23]);
+        }
+        _ => panic!("unexpected message"),
+    }
+    match frontend::Message::parse(&mut buf).unwrap().unwrap() {
+        frontend::Message::Describe(body) => {
+            assert_eq!(body.variant(), b'S');
+            assert_eq!(body.name().unwrap(), "stmt");
+        }
+        _ => panic!("unexpected message"),
+    }
+    match frontend::Message::parse(&mut buf).unwrap().unwrap() {
+        frontend::Message::Execute(body) => {
+            assert_eq!(body.portal().unwrap(), "portal");
+            assert_eq!(body.max_rows(), 10);
+        }
+        _ => panic!("unexpected message"),
+    }
+    match frontend::Message::parse(&mut buf).unwrap().unwrap() {
+        frontend::Message::Close(body) => {
+            assert_eq!(body.variant(), b'P');
+            assert_eq!(body.name().unwrap(), "portal");
+        }
+        _ => panic!("unexpected message"),
+    }
+    match parse_frontend(&mut buf) {
+        frontend::Message::Sync => {}
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[test]
+fn simple_query_and_copy() {
+    let mut buf = BytesMut::new();
+    frontend::query("SELECT 1", &mut buf).unwrap();
+    frontend::CopyData::new(&b"1\t2\n"[..])
+        .unwrap()
+        .write(&mut buf);
+    frontend::copy_done(&mut buf);
+    frontend::copy_fail("oops", &mut buf).unwrap();
+    frontend::terminate(&mut buf);
+
+    match frontend::Message::parse(&mut buf).unwrap().unwrap() {
+        frontend::Message::Query(body) => assert_eq!(body.query().unwrap(), "SELECT 1"),
+        _ => panic!("unexpected message"),
+    }
+    match frontend::Message::parse(&mut buf).unwrap().unwrap() {
+        frontend::Message::CopyData(body) => assert_eq!(body.data(), b"1\t2\n"),
+        _ => panic!("unexpected message"),
+    }
+    match frontend::Message::parse(&mut buf).unwrap().unwrap() {
+        frontend::Message::CopyDone => {}
+        _ => panic!("unexpected message"),
+    }
+    match frontend::Message::parse(&mut buf).unwrap().unwrap() {
+        frontend::Message::CopyFail(body) => assert_eq!(body.message().unwrap(), "oops"),
+        _ => panic!("unexpected message"),
+    }
+    match parse_frontend(&mut buf) {
+        frontend::Message::Terminate => {}
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[test]
+fn password_messages() {
+    let mut buf = BytesMut::new();
+    frontend::password_message(b"hunter2", &mut buf).unwrap();
+    match parse_frontend(&mut buf) {
+        frontend::Message::PasswordMessage(body) => {
+            assert_eq!(body.password().unwrap(), b"hunter2")
+        }
+        _ => panic!("unexpected message"),
+    }
+
+    frontend::sasl_initial_response("SCRAM-SHA-256", b"n,,n=,r=abc", &mut buf).unwrap();
+    match parse_frontend(&mut buf) {
+        frontend::Message::PasswordMessage(body) => {
+            let body = body.sasl_initial_response().unwrap();
+            assert_eq!(body.mechanism(), "SCRAM-SHA-256");
+            assert_eq!(body.data(), Some(&b"n,,n=,r=abc"[..]));
+        }
+        _ => panic!("unexpected message"),
+    }
+
+    frontend::sasl_response(b"c=biws", &mut buf).unwrap();
+    match parse_frontend(&mut buf) {
+        frontend::Message::PasswordMessage(body) => assert_eq!(body.data(), b"c=biws"),
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[test]
+fn authentication() {
+    let mut buf = BytesMut::new();
+    backend::authentication_ok(&mut buf);
+    backend::authentication_kerberos_v5(&mut buf);
+    backend::authentication_cleartext_password(&mut buf);
+    backend::authentication_scm_credential(&mut buf);
+    backend::authentication_gss(&mut buf);
+    backend::authentication_sspi(&mut buf);
+
+    for _ in 0..6 {
+        match backend::Message::parse(&mut buf).unwrap().unwrap() {
+            backend::Message::AuthenticationOk
+            | backend::Message::AuthenticationKerberosV5
+            | backend::Message::AuthenticationCleartextPassword
+            | backend::Message::AuthenticationScmCredential
+            | backend::Message::AuthenticationGss
+            | backend::Message::AuthenticationSspi => {}
+            _ => panic!("unexpected message"),
+        }
+    }
+    assert!(buf.is_empty());
+
+    backend::authentication_md5_password([1, 2, 3, 4], &mut buf);
+    match parse_backend(&mut buf) {
+        backend::Message::AuthenticationMd5Password(body) => assert_eq!(body.salt(), [1, 2, 3, 4]),
+        _ => panic!("unexpected message"),
+    }
+
+    backend::authentication_gss_continue(b"token", &mut buf).unwrap();
+    match parse_backend(&mut buf) {
+        backend::Message::AuthenticationGssContinue(body) => assert_eq!(body.data(), b"token"),
+        _ => panic!("unexpected message"),
+    }
+
+    backend::authentication_sasl(vec!["SCRAM-SHA-256-PLUS", "SCRAM-SHA-256"], &mut buf).unwrap();
+    match parse_backend(&mut buf) {
+        backend::Message::AuthenticationSasl(body) => assert_eq!(
+            body.mechanisms().collect::<Vec<_>>().unwrap(),
+            ["SCRAM-SHA-256-PLUS", "SCRAM-SHA-256"]
+        ),
+        _ => panic!("unexpected message"),
+    }
+
+    backend::authentication_sasl_continue(b"r=abc", &mut buf).unwrap();
+    match parse_backend(&mut buf) {
+        backend::Message::AuthenticationSaslContinue(body) => assert_eq!(body.data(), b"r=abc"),
+        _ => panic!("unexpected message"),
+    }
+
+    backend::authentication_sasl_final(b"v=xyz", &mut buf).unwrap();
+    match parse_backend(&mut buf) {
+        backend::Message::AuthenticationSaslFinal(body) => assert_eq!(body.data(), b"v=xyz"),
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[test]
+fn startup_responses() {
+    let mut buf = BytesMut::new();
+    backend::parameter_status("server_version", "13.0", &mut buf).unwrap();
+    backend::backend_key_data(42, -7, &mut buf);
+    backend::ready_for_query(b'I', &mut buf);
+
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::ParameterStatus(body) => {
+            assert_eq!(body.name().unwrap(), "server_version");
+            assert_eq!(body.value().unwrap(), "13.0");
+        }
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::BackendKeyData(body) => {
+            assert_eq!(body.process_id(), 42);
+            assert_eq!(body.secret_key(), -7);
+        }
+        _ => panic!("unexpected message"),
+    }
+    match parse_backend(&mut buf) {
+        backend::Message::ReadyForQuery(body) => assert_eq!(body.status(), b'I'),
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[test]
+fn query_responses() {
+    let mut buf = BytesMut::new();
+    backend::parse_complete(&mut buf);
+    backend::bind_complete(&mut buf);
+    backend::parameter_description(vec![23, 25], &mut buf).unwrap();
+    backend::row_description(
+        vec![
+            Field::new("id", 16384, 1, 23, 4, -1, 1),
+            Field::new("name", 0, 0, 25, -1, -1, 0),
+        ],
+        &mut buf,
+    )
+    .unwrap();
+    backend::data_row(vec![Some(&b"\x00\x00\x00\x01"[..]), None], &mut buf).unwrap();
+    backend::portal_suspended(&mut buf);
+    backend::command_complete("SELECT 1", &mut buf).unwrap();
+    backend::no_data(&mut buf);
+    backend::empty_query_response(&mut buf);
+    backend::close_complete(&mut buf);
+
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::ParseComplete => {}
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::BindComplete => {}
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::ParameterDescription(body) => {
+            assert_eq!(body.parameters().collect::<Vec<_>>().unwrap(), [23, 25])
+        }
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::RowDescription(body) => {
+            let fields = body.fields().collect::<Vec<_>>().unwrap();
+            assert_eq!(fields.len(), 2);
+            assert_eq!(fields[0].name(), "id");
+            assert_eq!(fields[0].table_oid(), 16384);
+            assert_eq!(fields[0].column_id(), 1);
+            assert_eq!(fields[0].type_oid(), 23);
+            assert_eq!(fields[0].type_size(), 4);
+            assert_eq!(fields[0].type_modifier(), -1);
+            assert_eq!(fields[0].format(), 1);
+            assert_eq!(fields[1].name(), "name");
+            assert_eq!(fields[1].type_size(), -1);
+        }
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::DataRow(body) => {
+            let values = body
+                .ranges()
+                .map(|r| Ok(r.map(|r| &body.buffer()[r])))
+                .collect::<Vec<_>>()
+                .unwrap();
+            assert_eq!(values, [Some(&b"\x00\x00\x00\x01"[..]), None]);
+        }
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::PortalSuspended => {}
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::CommandComplete(body) => assert_eq!(body.tag().unwrap(), "SELECT 1"),
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::NoData => {}
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::EmptyQueryResponse => {}
+        _ => panic!("unexpected message"),
+    }
+    match parse_backend(&mut buf) {
+        backend::Message::CloseComplete => {}
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[test]
+fn copy_responses() {
+    let mut buf = BytesMut::new();
+    backend::copy_in_response(1, vec![1, 1], &mut buf).unwrap();
+    backend::copy_out_response(0, vec![0], &mut buf).unwrap();
+    backend::copy_both_response(0, None, &mut buf).unwrap();
+    backend::copy_data(b"1\t2\n", &mut buf).unwrap();
+    backend::copy_done(&mut buf);
+
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::CopyInResponse(body) => {
+            assert_eq!(body.format(), 1);
+            assert_eq!(body.column_formats().collect::<Vec<_>>().unwrap(), [1, 1]);
+        }
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::CopyOutResponse(body) => {
+            assert_eq!(body.format(), 0);
+            assert_eq!(body.column_formats().collect::<Vec<_>>().unwrap(), [0]);
+        }
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::CopyBothResponse(body) => {
+            assert_eq!(body.column_formats().count().unwrap(), 0)
+        }
+        _ => panic!("unexpected message"),
+    }
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::CopyData(body) => assert_eq!(body.data(), b"1\t2\n"),
+        _ => panic!("unexpected message"),
+    }
+    match parse_backend(&mut buf) {
+        backend::Message::CopyDone => {}
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[test]
+fn error_and_notice_responses() {
+    let fields = vec![(b'S', "ERROR"), (b'C', "42P01"), (b'M', "no such table")];
+
+    let mut buf = BytesMut::new();
+    backend::error_response(fields.clone(), &mut buf).unwrap();
+    match parse_backend(&mut buf) {
+        backend::Message::ErrorResponse(body) => {
+            let parsed = body
+                .fields()
+                .map(|f| Ok((f.type_(), f.value().to_string())))
+                .collect::<Vec<_>>()
+                .unwrap();
+            let expected = fields
+                .iter()
+                .map(|&(t, v)| (t, v.to_string()))
+                .collect::<Vec<_>>();
+            assert_eq!(parsed, expected);
+        }
+        _ => panic!("unexpected message"),
+    }
+
+    backend::notice_response(vec![(b'M', "hello")], &mut buf).unwrap();
+    match parse_backend(&mut buf) {
+        backend::Message::NoticeResponse(body) => {
+            let field = body.fields().next().unwrap().unwrap();
+            assert_eq!(field.type_(), b'M');
+            assert_eq!(field.value(), "hello");
+        }
+        _ => panic!("unexpected message"),
+    }
+
+    assert!(backend::error_response(vec![(0, "bogus")], &mut buf).is_err());
+}
+
+#[test]
+fn notification_response() {
+    let mut buf = BytesMut::new();
+    backend::notification_response(42, "chan", "payload", &mut buf).unwrap();
+    match parse_backend(&mut buf) {
+        backend::Message::NotificationResponse(body) => {
+            assert_eq!(body.process_id(), 42);
+            assert_eq!(body.channel().unwrap(), "chan");
+            assert_eq!(body.message().unwrap(), "payload");
+        }
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[test]
+fn function_call() {
+    let mut buf = BytesMut::new();
+    frontend::function_call(
+        952,
+        Some(1),
+        vec![Some(&b"\x00\x00\x00\x01"[..]), None],
+        1,
+        &mut buf,
+    )
+    .unwrap();
+    match parse_frontend(&mut buf) {
+        frontend::Message::FunctionCall(body) => {
+            assert_eq!(body.oid(), 952);
+            assert_eq!(body.formats().collect::<Vec<_>>().unwrap(), [1]);
+            assert_eq!(
+                body.args().collect::<Vec<_>>().unwrap(),
+                [Some(&b"\x00\x00\x00\x01"[..]), None]
+            );
+            assert_eq!(body.result_format(), 1);
+        }
+        _ => panic!("unexpected message"),
+    }
+
+    backend::function_call_response(Some(b"hello"), &mut buf).unwrap();
+    backend::function_call_response(None, &mut buf).unwrap();
+    match backend::Message::parse(&mut buf).unwrap().unwrap() {
+        backend::Message::FunctionCallResponse(body) => {
+            assert_eq!(body.value(), Some(&b"hello"[..]))
+        }
+        _ => panic!("unexpected message"),
+    }
+    match parse_backend(&mut buf) {
+        backend::Message::FunctionCallResponse(body) => assert_eq!(body.value(), None),
+        _ => panic!("unexpected message"),
+    }
+}
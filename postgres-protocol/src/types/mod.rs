@@ -22,6 +22,13 @@ const RANGE_EMPTY: u8 = 0b0000_0001;
 const PGSQL_AF_INET: u8 = 2;
 const PGSQL_AF_INET6: u8 = 3;
 
+const NUMERIC_POS: u16 = 0x0000;
+const NUMERIC_NEG: u16 = 0x4000;
+const NUMERIC_NAN: u16 = 0xC000;
+const NUMERIC_PINF: u16 = 0xD000;
+const NUMERIC_NINF: u16 = 0xF000;
+const NUMERIC_DSCALE_MAX: u16 = 0x3FFF;
+
 /// Serializes a `BOOL` value.
 #[inline]
 pub fn bool_to_sql(v: bool, buf: &mut BytesMut) {
@@ -142,6 +149,22 @@ pub fn int8_from_sql(mut buf: &[u8]) -> Result<i64, StdBox<dyn Error + Sync + Se
     Ok(v)
 }
 
+/// Serializes a `PG_LSN` value.
+#[inline]
+pub fn lsn_to_sql(v: u64, buf: &mut BytesMut) {
+    buf.put_u64(v);
+}
+
+/// Deserializes a `PG_LSN` value.
+#[inline]
+pub fn lsn_from_sql(mut buf: &[u8]) -> Result<u64, StdBox<dyn Error + Sync + Send>> {
+    let v = buf.read_u64::<BigEndian>()?;
+    if !buf.is_empty() {
+        return Err("invalid buffer size".into());
+    }
+    Ok(v)
+}
+
 /// Serializes a `FLOAT4` value.
 #[inline]
 pub fn float4_to_sql(v: f32, buf: &mut BytesMut) {
@@ -407,6 +430,61 @@ pub fn time_from_sql(mut buf: &[u8]) -> Result<i64, StdBox<dyn Error + Sync + Se
     Ok(v)
 }
 
+/// Serializes an `INTERVAL` value.
+///
+/// The time component is in microseconds, and is stored separately from the month and day components since their
+/// lengths vary.
+#[inline]
+pub fn interval_to_sql(months: i32, days: i32, microseconds: i64, buf: &mut BytesMut) {
+    buf.put_i64(microseconds);
+    buf.put_i32(days);
+    buf.put_i32(months);
+}
+
+/// Deserializes an `INTERVAL` value.
+#[inline]
+pub fn interval_from_sql(mut buf: &[u8]) -> Result<Interval, StdBox<dyn Error + Sync + Send>> {
+    let microseconds = buf.read_i64::<BigEndian>()?;
+    let days = buf.read_i32::<BigEndian>()?;
+    let months = buf.read_i32::<BigEndian>()?;
+    if !buf.is_empty() {
+        return Err("invalid message length".into());
+    }
+
+    Ok(Interval {
+        microseconds,
+        days,
+        months,
+    })
+}
+
+/// A Postgres interval.
+pub struct Interval {
+    microseconds: i64,
+    days: i32,
+    months: i32,
+}
+
+impl Interval {
+    /// Returns the time component in microseconds.
+    #[inline]
+    pub fn microseconds(&self) -> i64 {
+        self.microseconds
+    }
+
+    /// Returns the number of days.
+    #[inline]
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    /// Returns the number of months.
+    #[inline]
+    pub fn months(&self) -> i32 {
+        self.months
+    }
+}
+
 /// Serializes a `MACADDR` value.
 #[inline]
 pub fn macaddr_to_sql(v: [u8; 6], buf: &mut BytesMut) {
@@ -781,6 +859,105 @@ pub enum Range<'a> {
     Nonempty(RangeBound<Option<&'a [u8]>>, RangeBound<Option<&'a [u8]>>),
 }
 
+/// Serializes a multirange value.
+///
+/// Each range is serialized by `serializer`, for example with `range_to_sql`.
+#[inline]
+pub fn multirange_to_sql<T, I, F>(
+    ranges: I,
+    mut serializer: F,
+    buf: &mut BytesMut,
+) -> Result<(), StdBox<dyn Error + Sync + Send>>
+where
+    I: IntoIterator<Item = T>,
+    F: FnMut(T, &mut BytesMut) -> Result<(), StdBox<dyn Error + Sync + Send>>,
+{
+    let count_idx = buf.len();
+    buf.put_i32(0);
+
+    let mut count = 0;
+    for range in ranges {
+        count += 1;
+        let base = buf.len();
+        buf.put_i32(0);
+        serializer(range, buf)?;
+        let len = i32::from_usize(buf.len() - base - 4)?;
+        BigEndian::write_i32(&mut buf[base..], len);
+    }
+
+    let count = i32::from_usize(count)?;
+    BigEndian::write_i32(&mut buf[count_idx..], count);
+
+    Ok(())
+}
+
+/// Deserializes a multirange value.
+#[inline]
+pub fn multirange_from_sql<'a>(
+    mut buf: &'a [u8],
+) -> Result<Multirange<'a>, StdBox<dyn Error + Sync + Send>> {
+    let count = buf.read_i32::<BigEndian>()?;
+    if count < 0 {
+        return Err("invalid multirange range count".into());
+    }
+
+    Ok(Multirange { count, buf })
+}
+
+/// A Postgres multirange.
+pub struct Multirange<'a> {
+    count: i32,
+    buf: &'a [u8],
+}
+
+impl<'a> Multirange<'a> {
+    /// Returns an iterator over the ranges of the multirange.
+    #[inline]
+    pub fn ranges(&self) -> MultirangeRanges<'a> {
+        MultirangeRanges {
+            remaining: self.count,
+            buf: self.buf,
+        }
+    }
+}
+
+/// An iterator over the ranges of a multirange.
+pub struct MultirangeRanges<'a> {
+    remaining: i32,
+    buf: &'a [u8],
+}
+
+impl<'a> FallibleIterator for MultirangeRanges<'a> {
+    type Item = Range<'a>;
+    type Error = StdBox<dyn Error + Sync + Send>;
+
+    #[inline]
+    fn next(&mut self) -> Result<Option<Range<'a>>, StdBox<dyn Error + Sync + Send>> {
+        if self.remaining == 0 {
+            if !self.buf.is_empty() {
+                return Err("invalid message length: multirange not drained".into());
+            }
+            return Ok(None);
+        }
+        self.remaining -= 1;
+
+        let len = self.buf.read_i32::<BigEndian>()?;
+        if len < 0 || self.buf.len() < len as usize {
+            return Err("invalid message size".into());
+        }
+        let (range, tail) = self.buf.split_at(len as usize);
+        self.buf = tail;
+
+        range_from_sql(range).map(Some)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining as usize;
+        (len, Some(len))
+    }
+}
+
 /// Serializes a point value.
 #[inline]
 pub fn point_to_sql(x: f64, y: f64, buf: &mut BytesMut) {
@@ -1045,3 +1222,165 @@ impl Inet {
         self.netmask
     }
 }
+
+/// The sign of a Postgres numeric.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NumericSign {
+    /// A positive value or zero.
+    Positive,
+    /// A negative value.
+    Negative,
+    /// Not a number.
+    NaN,
+    /// Positive infinity.
+    PositiveInfinity,
+    /// Negative infinity.
+    NegativeInfinity,
+}
+
+/// Serializes a `NUMERIC` value.
+///
+/// The digits are in base 10000, most significant first, and `weight` is the power of 10000 of the first digit. The
+/// digits must be empty for the special values.
+#[inline]
+pub fn numeric_to_sql<I>(
+    sign: NumericSign,
+    weight: i16,
+    scale: u16,
+    digits: I,
+    buf: &mut BytesMut,
+) -> Result<(), StdBox<dyn Error + Sync + Send>>
+where
+    I: IntoIterator<Item = i16>,
+{
+    if scale > NUMERIC_DSCALE_MAX {
+        return Err("numeric scale out of range".into());
+    }
+    let sign = match sign {
+        NumericSign::Positive => NUMERIC_POS,
+        NumericSign::Negative => NUMERIC_NEG,
+        NumericSign::NaN => NUMERIC_NAN,
+        NumericSign::PositiveInfinity => NUMERIC_PINF,
+        NumericSign::NegativeInfinity => NUMERIC_NINF,
+    };
+
+    let ndigits_idx = buf.len();
+    buf.put_i16(0);
+    buf.put_i16(weight);
+    buf.put_u16(sign);
+    buf.put_u16(scale);
+
+    let mut ndigits = 0;
+    for digit in digits {
+        if !(0..10000).contains(&digit) {
+            return Err("numeric digit out of range".into());
+        }
+        ndigits += 1;
+        buf.put_i16(digit);
+    }
+    if ndigits != 0 && (sign == NUMERIC_NAN || sign == NUMERIC_PINF || sign == NUMERIC_NINF) {
+        return Err("special numeric values cannot have digits".into());
+    }
+
+    let ndigits = i16::from_usize(ndigits)?;
+    BigEndian::write_i16(&mut buf[ndigits_idx..], ndigits);
+
+    Ok(())
+}
+
+/// Deserializes a `NUMERIC` value.
+#[inline]
+pub fn numeric_from_sql(mut buf: &[u8]) -> Result<Numeric<'_>, StdBox<dyn Error + Sync + Send>> {
+    let ndigits = buf.read_i16::<BigEndian>()?;
+    let weight = buf.read_i16::<BigEndian>()?;
+    let sign = match buf.read_u16::<BigEndian>()? {
+        NUMERIC_POS => NumericSign::Positive,
+        NUMERIC_NEG => NumericSign::Negative,
+        NUMERIC_NAN => NumericSign::NaN,
+        NUMERIC_PINF => NumericSign::PositiveInfinity,
+        NUMERIC_NINF => NumericSign::NegativeInfinity,
+        _ => return Err("invalid numeric sign".into()),
+    };
+    let scale = buf.read_u16::<BigEndian>()?;
+    if scale > NUMERIC_DSCALE_MAX {
+        return Err("invalid numeric scale".into());
+    }
+
+    if ndigits < 0 {
+        return Err("invalid numeric digit count".into());
+    }
+    if buf.len() != ndigits as usize * 2 {
+        return Err("invalid message length: numeric digits mismatch".into());
+    }
+
+    Ok(Numeric {
+        sign,
+        weight,
+        scale,
+        digits: buf,
+    })
+}
+
+/// A Postgres numeric.
+pub struct Numeric<'a> {
+    sign: NumericSign,
+    weight: i16,
+    scale: u16,
+    digits: &'a [u8],
+}
+
+impl<'a> Numeric<'a> {
+    /// Returns the sign of the value.
+    #[inline]
+    pub fn sign(&self) -> NumericSign {
+        self.sign
+    }
+
+    /// Returns the power of 10000 of the first digit.
+    #[inline]
+    pub fn weight(&self) -> i16 {
+        self.weight
+    }
+
+    /// Returns the number of decimal digits after the decimal point.
+    #[inline]
+    pub fn scale(&self) -> u16 {
+        self.scale
+    }
+
+    /// Returns an iterator over the base 10000 digits of the value, most significant first.
+    #[inline]
+    pub fn digits(&self) -> NumericDigits<'a> {
+        NumericDigits { buf: self.digits }
+    }
+}
+
+/// An iterator over the digits of a Postgres numeric.
+pub struct NumericDigits<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> FallibleIterator for NumericDigits<'a> {
+    type Item = i16;
+    type Error = StdBox<dyn Error + Sync + Send>;
+
+    #[inline]
+    fn next(&mut self) -> Result<Option<i16>, StdBox<dyn Error + Sync + Send>> {
+        if self.buf.is_empty() {
+            return Ok(None);
+        }
+
+        let digit = self.buf.read_i16::<BigEndian>()?;
+        if !(0..10000).contains(&digit) {
+            return Err("invalid numeric digit".into());
+        }
+
+        Ok(Some(digit))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.buf.len() / 2;
+        (len, Some(len))
+    }
+}
@@ -37,6 +37,13 @@ fn int8() {
     assert_eq!(int8_from_sql(&buf).unwrap(), 0x0102_0304_0506_0708);
 }
 
+#[test]
+fn lsn() {
+    let mut buf = BytesMut::new();
+    lsn_to_sql(0x0102_0304_0506_0708, &mut buf);
+    assert_eq!(lsn_from_sql(&buf).unwrap(), 0x0102_0304_0506_0708);
+}
+
 #[test]
 #[allow(clippy::float_cmp)]
 fn float4() {
@@ -53,6 +60,90 @@ fn float8() {
     assert_eq!(float8_from_sql(&buf).unwrap(), 10343.95);
 }
 
+#[test]
+fn interval() {
+    let mut buf = BytesMut::new();
+    interval_to_sql(14, 2, -1_000_001, &mut buf);
+    let interval = interval_from_sql(&buf).unwrap();
+    assert_eq!(interval.microseconds(), -1_000_001);
+    assert_eq!(interval.days(), 2);
+    assert_eq!(interval.months(), 14);
+
+    assert!(interval_from_sql(&buf[..12]).is_err());
+}
+
+#[test]
+fn multirange() {
+    let mut buf = BytesMut::new();
+    multirange_to_sql(
+        vec![(1, 3), (5, 8)],
+        |(lower, upper), buf| {
+            range_to_sql(
+                |buf| {
+                    int4_to_sql(lower, buf);
+                    Ok(RangeBound::Inclusive(IsNull::No))
+                },
+                |buf| {
+                    int4_to_sql(upper, buf);
+                    Ok(RangeBound::Exclusive(IsNull::No))
+                },
+                buf,
+            )
+        },
+        &mut buf,
+    )
+    .unwrap();
+
+    let multirange = multirange_from_sql(&buf).unwrap();
+    let ranges = multirange
+        .ranges()
+        .map(|range| match range {
+            Range::Nonempty(RangeBound::Inclusive(Some(l)), RangeBound::Exclusive(Some(u))) => {
+                Ok((int4_from_sql(l)?, int4_from_sql(u)?))
+            }
+            _ => panic!("unexpected range"),
+        })
+        .collect::<Vec<_>>()
+        .unwrap();
+    assert_eq!(ranges, vec![(1, 3), (5, 8)]);
+
+    let mut buf = BytesMut::new();
+    multirange_to_sql(Vec::<()>::new(), |_, _| Ok(()), &mut buf).unwrap();
+    assert_eq!(
+        multirange_from_sql(&buf).unwrap().ranges().count().unwrap(),
+        0
+    );
+}
+
+#[test]
+fn numeric() {
+    let mut buf = BytesMut::new();
+    numeric_to_sql(NumericSign::Negative, 1, 3, vec![12, 3456, 7890], &mut buf).unwrap();
+    assert_eq!(
+        &buf[..],
+        &[0, 3, 0, 1, 0x40, 0, 0, 3, 0, 12, 0x0d, 0x80, 0x1e, 0xd2][..]
+    );
+    let numeric = numeric_from_sql(&buf).unwrap();
+    assert_eq!(numeric.sign(), NumericSign::Negative);
+    assert_eq!(numeric.weight(), 1);
+    assert_eq!(numeric.scale(), 3);
+    assert_eq!(
+        numeric.digits().collect::<Vec<_>>().unwrap(),
+        vec![12, 3456, 7890]
+    );
+
+    let mut buf = BytesMut::new();
+    numeric_to_sql(NumericSign::NaN, 0, 0, vec![], &mut buf).unwrap();
+    let numeric = numeric_from_sql(&buf).unwrap();
+    assert_eq!(numeric.sign(), NumericSign::NaN);
+    assert_eq!(numeric.digits().count().unwrap(), 0);
+
+    let mut buf = BytesMut::new();
+    assert!(numeric_to_sql(NumericSign::Positive, 0, 0, vec![10000], &mut buf).is_err());
+    let mut buf = BytesMut::new();
+    assert!(numeric_to_sql(NumericSign::PositiveInfinity, 0, 0, vec![1], &mut buf).is_err());
+}
+
 #[test]
 fn hstore() {
     let mut map = HashMap::new();
@@ -0,0 +1,315 @@
+//! TLS support for `tokio-postgres` and `postgres` via `rustls`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use postgres_rustls::MakeTlsConnector;
+//! use rustls::ClientConfig;
+//! use std::fs::File;
+//! use std::io::BufReader;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut config = ClientConfig::new();
+//! let mut certs = BufReader::new(File::open("database_cert.pem")?);
+//! config.root_store.add_pem_file(&mut certs).map_err(|()| "invalid certificate")?;
+//! let connector = MakeTlsConnector::new(config);
+//!
+//! let connect_future = tokio_postgres::connect(
+//!     "host=localhost user=postgres sslmode=require",
+//!     connector,
+//! );
+//!
+//! // ...
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ```no_run
+//! use postgres_rustls::MakeTlsConnector;
+//! use rustls::ClientConfig;
+//! use std::fs::File;
+//! use std::io::BufReader;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut config = ClientConfig::new();
+//! let mut certs = BufReader::new(File::open("database_cert.pem")?);
+//! config.root_store.add_pem_file(&mut certs).map_err(|()| "invalid certificate")?;
+//! let connector = MakeTlsConnector::new(config);
+//!
+//! let client = postgres::Client::connect(
+//!     "host=localhost user=postgres sslmode=require",
+//!     connector,
+//! )?;
+//!
+//! // ...
+//! # Ok(())
+//! # }
+//! ```
+#![doc(html_root_url = "https://docs.rs/postgres-rustls/0.1")]
+#![warn(rust_2018_idioms, clippy::all, missing_docs)]
+
+use bytes::{Buf, BufMut};
+use ring::digest;
+use rustls::{ClientConfig, Session};
+use std::convert::Infallible;
+use std::future::Future;
+use std::io;
+use std::mem::MaybeUninit;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_postgres::tls;
+#[cfg(feature = "runtime")]
+use tokio_postgres::tls::MakeTlsConnect;
+use tokio_postgres::tls::{ChannelBinding, TlsConnect};
+use webpki::DNSNameRef;
+
+#[cfg(test)]
+mod test;
+
+const ALPN_PROTOCOL: &[u8] = b"postgresql";
+
+/// A `MakeTlsConnect` implementation using the `rustls` crate.
+///
+/// Requires the `runtime` Cargo feature (enabled by default).
+#[cfg(feature = "runtime")]
+#[derive(Clone)]
+pub struct MakeTlsConnector {
+    config: Arc<ClientConfig>,
+}
+
+#[cfg(feature = "runtime")]
+impl MakeTlsConnector {
+    /// Creates a new connector.
+    ///
+    /// If the configuration doesn't specify any ALPN protocols, the `postgresql` protocol is used.
+    pub fn new(mut config: ClientConfig) -> MakeTlsConnector {
+        if config.alpn_protocols.is_empty() {
+            config.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+        }
+
+        MakeTlsConnector {
+            config: Arc::new(config),
+        }
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl<S> MakeTlsConnect<S> for MakeTlsConnector
+where
+    S: AsyncRead + AsyncWrite + Unpin + 'static + Send,
+{
+    type Stream = TlsStream<S>;
+    type TlsConnect = TlsConnector;
+    type Error = Infallible;
+
+    fn make_tls_connect(&mut self, domain: &str) -> Result<TlsConnector, Infallible> {
+        Ok(TlsConnector::new(self.config.clone(), domain))
+    }
+}
+
+/// A `TlsConnect` implementation using the `rustls` crate.
+pub struct TlsConnector {
+    connector: tokio_rustls::TlsConnector,
+    domain: String,
+}
+
+impl TlsConnector {
+    /// Creates a new connector configured to connect to the specified domain.
+    ///
+    /// `rustls` only supports verifying certificates against DNS names, so the TLS handshake fails if the domain is not
+    /// a valid one. If the configuration doesn't specify any ALPN protocols, the `postgresql` protocol is used.
+    pub fn new(config: Arc<ClientConfig>, domain: &str) -> TlsConnector {
+        let config = if config.alpn_protocols.is_empty() {
+            let mut config = (*config).clone();
+            config.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+            Arc::new(config)
+        } else {
+            config
+        };
+
+        TlsConnector {
+            connector: tokio_rustls::TlsConnector::from(config),
+            domain: domain.to_string(),
+        }
+    }
+}
+
+impl<S> TlsConnect<S> for TlsConnector
+where
+    S: AsyncRead + AsyncWrite + Unpin + 'static + Send,
+{
+    type Stream = TlsStream<S>;
+    type Error = io::Error;
+    #[allow(clippy::type_complexity)]
+    type Future = Pin<Box<dyn Future<Output = io::Result<TlsStream<S>>> + Send>>;
+
+    fn connect(self, stream: S) -> Self::Future {
+        // The domain is only checked here, since connections which don't use TLS, like those over Unix sockets, are
+        // still made with a connector.
+        Box::pin(async move {
+            let domain = DNSNameRef::try_from_ascii_str(&self.domain)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            let stream = self.connector.connect(domain, stream).await?;
+            Ok(TlsStream(stream))
+        })
+    }
+}
+
+/// The stream returned by `TlsConnector`.
+pub struct TlsStream<S>(tokio_rustls::client::TlsStream<S>);
+
+impl<S> AsyncRead for TlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    unsafe fn prepare_uninitialized_buffer(&self, buf: &mut [MaybeUninit<u8>]) -> bool {
+        self.0.prepare_uninitialized_buffer(buf)
+    }
+
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+
+    fn poll_read_buf<B: BufMut>(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut B,
+    ) -> Poll<io::Result<usize>>
+    where
+        Self: Sized,
+    {
+        Pin::new(&mut self.0).poll_read_buf(cx, buf)
+    }
+}
+
+impl<S> AsyncWrite for TlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+
+    fn poll_write_buf<B: Buf>(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut B,
+    ) -> Poll<io::Result<usize>>
+    where
+        Self: Sized,
+    {
+        Pin::new(&mut self.0).poll_write_buf(cx, buf)
+    }
+}
+
+impl<S> tls::TlsStream for TlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn channel_binding(&self) -> ChannelBinding {
+        let (_, session) = self.0.get_ref();
+        let cert = match session.get_peer_certificates() {
+            Some(certs) if !certs.is_empty() => certs.into_iter().next().unwrap(),
+            _ => return ChannelBinding::none(),
+        };
+
+        match tls_server_end_point(&cert.0) {
+            Some(buf) => ChannelBinding::tls_server_end_point(buf),
+            None => ChannelBinding::none(),
+        }
+    }
+
+    fn alpn_protocol(&self) -> Option<Vec<u8>> {
+        let (_, session) = self.0.get_ref();
+        session.get_alpn_protocol().map(|p| p.to_vec())
+    }
+}
+
+// RFC 5929 hashes the certificate with the hash function of its signature algorithm, upgrading MD5 and SHA-1 to
+// SHA-256.
+fn tls_server_end_point(cert: &[u8]) -> Option<Vec<u8>> {
+    let algorithm = match signature_algorithm(cert)? {
+        OID_MD5_RSA | OID_SHA1_RSA | OID_SHA1_ECDSA | OID_SHA256_RSA | OID_SHA256_ECDSA => {
+            &digest::SHA256
+        }
+        OID_SHA384_RSA | OID_SHA384_ECDSA => &digest::SHA384,
+        OID_SHA512_RSA | OID_SHA512_ECDSA => &digest::SHA512,
+        _ => return None,
+    };
+
+    Some(digest::digest(algorithm, cert).as_ref().to_vec())
+}
+
+// md5WithRSAEncryption, sha1WithRSAEncryption, sha256WithRSAEncryption, ...
+const OID_MD5_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x04];
+const OID_SHA1_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x05];
+const OID_SHA256_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b];
+const OID_SHA384_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0c];
+const OID_SHA512_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0d];
+// ecdsa-with-SHA1, ecdsa-with-SHA256, ...
+const OID_SHA1_ECDSA: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x01];
+const OID_SHA256_ECDSA: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+const OID_SHA384_ECDSA: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03];
+const OID_SHA512_ECDSA: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x04];
+
+// Extracts the signature algorithm OID from a DER-encoded certificate:
+//
+// Certificate ::= SEQUENCE {
+//     tbsCertificate       TBSCertificate,
+//     signatureAlgorithm   AlgorithmIdentifier,
+//     signatureValue       BIT STRING }
+//
+// AlgorithmIdentifier ::= SEQUENCE {
+//     algorithm            OBJECT IDENTIFIER,
+//     parameters           ANY DEFINED BY algorithm OPTIONAL }
+fn signature_algorithm(cert: &[u8]) -> Option<&[u8]> {
+    const SEQUENCE: u8 = 0x30;
+    const OBJECT_IDENTIFIER: u8 = 0x06;
+
+    let (certificate, _) = der_element(cert, SEQUENCE)?;
+    let (_, rest) = der_element(certificate, SEQUENCE)?;
+    let (algorithm_identifier, _) = der_element(rest, SEQUENCE)?;
+    let (algorithm, _) = der_element(algorithm_identifier, OBJECT_IDENTIFIER)?;
+    Some(algorithm)
+}
+
+// Splits a DER element with the given tag off the front of a buffer, returning its contents and the remainder.
+fn der_element(buf: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    if *buf.first()? != tag {
+        return None;
+    }
+
+    let first = *buf.get(1)?;
+    let (len, header) = if first & 0x80 == 0 {
+        (first as usize, 2)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let bytes = buf.get(2..2 + count)?;
+        let len = bytes.iter().fold(0, |len, &b| (len << 8) | b as usize);
+        (len, 2 + count)
+    };
+
+    let contents = buf.get(header..header.checked_add(len)?)?;
+    Some((contents, &buf[header + len..]))
+}
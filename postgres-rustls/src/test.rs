@@ -0,0 +1,198 @@
+use futures::FutureExt;
+use rustls::{
+    Certificate, ClientConfig, RootCertStore, ServerCertVerified, ServerCertVerifier, TLSError,
+};
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_postgres::tls::TlsConnect;
+
+use super::*;
+
+// The test certificate is a self-signed CA certificate without a subjectAltName, which webpki refuses to accept as a
+// server certificate, so pin it instead.
+struct PinnedCert;
+
+impl ServerCertVerifier for PinnedCert {
+    fn verify_server_cert(
+        &self,
+        _: &RootCertStore,
+        presented_certs: &[Certificate],
+        _: DNSNameRef<'_>,
+        _: &[u8],
+    ) -> Result<ServerCertVerified, TLSError> {
+        match presented_certs.first() {
+            Some(cert) if cert.0[..] == include_bytes!("../../test/server.der")[..] => {
+                Ok(ServerCertVerified::assertion())
+            }
+            _ => Err(TLSError::General("untrusted certificate".to_string())),
+        }
+    }
+}
+
+fn config() -> Arc<ClientConfig> {
+    let mut config = ClientConfig::new();
+    config
+        .dangerous()
+        .set_certificate_verifier(Arc::new(PinnedCert));
+    Arc::new(config)
+}
+
+async fn smoke_test<T>(s: &str, tls: T)
+where
+    T: TlsConnect<TcpStream>,
+    T::Stream: 'static + Send,
+{
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+
+    let builder = s.parse::<tokio_postgres::Config>().unwrap();
+    let (client, connection) = builder.connect_raw(stream, tls).await.unwrap();
+
+    let connection = connection.map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    let stmt = client.prepare("SELECT $1::INT4").await.unwrap();
+    let rows = client.query(&stmt, &[&1i32]).await.unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+}
+
+#[tokio::test]
+async fn require() {
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=require",
+        TlsConnector::new(config(), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn prefer() {
+    smoke_test(
+        "user=ssl_user dbname=postgres",
+        TlsConnector::new(config(), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn scram_user() {
+    smoke_test(
+        "user=scram_user password=password dbname=postgres sslmode=require",
+        TlsConnector::new(config(), "localhost"),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn require_channel_binding_err() {
+    let connector = TlsConnector::new(config(), "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder = "user=pass_user password=password dbname=postgres channel_binding=require"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    builder.connect_raw(stream, connector).await.err().unwrap();
+}
+
+#[tokio::test]
+async fn require_channel_binding_ok() {
+    smoke_test(
+        "user=scram_user password=password dbname=postgres channel_binding=require",
+        TlsConnector::new(config(), "localhost"),
+    )
+    .await;
+}
+
+// Accepts a single connection and returns the first TLS record sent by the client, which should be its ClientHello.
+async fn direct_client_hello<T>(tls: T) -> Vec<u8>
+where
+    T: TlsConnect<TcpStream>,
+    T::Stream: 'static + Send,
+{
+    let mut listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0; 5];
+        stream.read_exact(&mut buf).await.unwrap();
+        let len = u16::from_be_bytes([buf[3], buf[4]]) as usize;
+        buf.resize(5 + len, 0);
+        stream.read_exact(&mut buf[5..]).await.unwrap();
+        buf
+    });
+
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let builder = "user=ssl_user dbname=postgres sslmode=require sslnegotiation=direct"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    builder.connect_raw(stream, tls).await.err().unwrap();
+
+    server.await.unwrap()
+}
+
+fn assert_direct_client_hello(record: &[u8]) {
+    // a handshake record rather than an SSLRequest, requesting the `postgresql` ALPN protocol
+    assert_eq!(record[0], 0x16);
+    assert!(record.windows(11).any(|w| w == b"\x0apostgresql"));
+}
+
+#[tokio::test]
+async fn direct_ssl_negotiation() {
+    let tls = TlsConnector::new(config(), "localhost");
+
+    assert_direct_client_hello(&direct_client_hello(tls).await);
+}
+
+#[tokio::test]
+async fn untrusted_cert() {
+    let connector = TlsConnector::new(Arc::new(ClientConfig::new()), "localhost");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder = "user=ssl_user dbname=postgres sslmode=require"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    builder.connect_raw(stream, connector).await.err().unwrap();
+}
+
+#[tokio::test]
+async fn ip_address_domain() {
+    let connector = TlsConnector::new(config(), "127.0.0.1");
+
+    let stream = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let builder = "user=ssl_user dbname=postgres sslmode=require"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    builder.connect_raw(stream, connector).await.err().unwrap();
+}
+
+#[cfg(feature = "runtime")]
+async fn runtime_smoke_test(s: &str) {
+    let mut connector = MakeTlsConnector::new(ClientConfig::new());
+    connector.config = config();
+
+    let (client, connection) = tokio_postgres::connect(s, connector).await.unwrap();
+    let connection = connection.map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    let stmt = client.prepare("SELECT $1::INT4").await.unwrap();
+    let rows = client.query(&stmt, &[&1i32]).await.unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+}
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime() {
+    runtime_smoke_test("host=localhost port=5433 user=postgres sslmode=require").await;
+}
+
+// The domain only has to be valid if TLS is actually used.
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn runtime_without_tls() {
+    runtime_smoke_test("host=127.0.0.1 port=5433 user=postgres sslmode=disable").await;
+    // postgres doesn't support TLS over Unix sockets
+    runtime_smoke_test("host=/var/run/postgresql port=5433 user=postgres sslmode=prefer").await;
+}
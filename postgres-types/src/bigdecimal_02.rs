@@ -0,0 +1,38 @@
+use bigdecimal_02::BigDecimal;
+use bytes::BytesMut;
+use std::error::Error;
+
+use crate::{FromSql, IsNull, PgNumeric, ToSql, Type};
+
+impl<'a> FromSql<'a> for BigDecimal {
+    fn from_sql(ty: &Type, raw: &[u8]) -> Result<BigDecimal, Box<dyn Error + Sync + Send>> {
+        let numeric = PgNumeric::from_sql(ty, raw)?;
+        if !numeric.is_finite() {
+            return Err("NaN and infinite values cannot be decoded as a BigDecimal".into());
+        }
+        Ok(numeric.to_string().parse()?)
+    }
+
+    accepts!(NUMERIC);
+}
+
+impl ToSql for BigDecimal {
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let (int, scale) = self.as_bigint_and_exponent();
+        let int = int.to_string();
+        let (negative, digits) = if int.starts_with('-') {
+            (true, &int[1..])
+        } else {
+            (false, &int[..])
+        };
+        let point = (digits.len() as i64)
+            .checked_sub(scale)
+            .ok_or("value too large to transmit")?;
+        let numeric = PgNumeric::from_decimal(negative, digits.as_bytes(), point)
+            .ok_or("value too large to transmit")?;
+        numeric.to_sql(ty, w)
+    }
+
+    accepts!(NUMERIC);
+    to_sql_checked!();
+}
@@ -1,9 +1,10 @@
 use bytes::BytesMut;
 use chrono_04::{DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use postgres_protocol::types;
+use std::convert::TryFrom;
 use std::error::Error;
 
-use crate::{FromSql, IsNull, ToSql, Type};
+use crate::{FromSql, IsNull, PgInterval, ToSql, TryFromIntervalError, Type};
 
 fn base() -> NaiveDateTime {
     NaiveDate::from_ymd(2000, 1, 1).and_hms(0, 0, 0)
@@ -153,3 +154,22 @@ impl ToSql for NaiveTime {
     accepts!(TIME);
     to_sql_checked!();
 }
+
+impl TryFrom<PgInterval> for Duration {
+    type Error = TryFromIntervalError;
+
+    fn try_from(interval: PgInterval) -> Result<Duration, TryFromIntervalError> {
+        interval.fixed_microseconds().map(Duration::microseconds)
+    }
+}
+
+impl TryFrom<Duration> for PgInterval {
+    type Error = TryFromIntervalError;
+
+    fn try_from(duration: Duration) -> Result<PgInterval, TryFromIntervalError> {
+        let microseconds = duration
+            .num_microseconds()
+            .ok_or_else(TryFromIntervalError::out_of_range)?;
+        Ok(PgInterval::new(0, 0, microseconds))
+    }
+}
@@ -0,0 +1,123 @@
+//! Postgres `INTERVAL` type.
+
+use bytes::BytesMut;
+use postgres_protocol::types;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+/// Postgres `INTERVAL` type.
+///
+/// Months and days are stored separately from the time component since their lengths vary, so an interval only has
+/// a fixed length if both are zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PgInterval {
+    /// The number of months.
+    pub months: i32,
+    /// The number of days.
+    pub days: i32,
+    /// The time component in microseconds.
+    pub microseconds: i64,
+}
+
+impl PgInterval {
+    /// Creates a new interval.
+    pub fn new(months: i32, days: i32, microseconds: i64) -> PgInterval {
+        PgInterval {
+            months,
+            days,
+            microseconds,
+        }
+    }
+
+    // Returns the time component if the interval has a fixed length.
+    pub(crate) fn fixed_microseconds(&self) -> Result<i64, TryFromIntervalError> {
+        if self.months != 0 || self.days != 0 {
+            return Err(TryFromIntervalError(ErrorKind::Ambiguous));
+        }
+        Ok(self.microseconds)
+    }
+}
+
+/// An error converting between a `PgInterval` and a duration type.
+#[derive(Debug)]
+pub struct TryFromIntervalError(ErrorKind);
+
+#[derive(Debug)]
+enum ErrorKind {
+    Ambiguous,
+    Negative,
+    OutOfRange,
+}
+
+impl TryFromIntervalError {
+    pub(crate) fn out_of_range() -> TryFromIntervalError {
+        TryFromIntervalError(ErrorKind::OutOfRange)
+    }
+}
+
+impl fmt::Display for TryFromIntervalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            ErrorKind::Ambiguous => {
+                f.write_str("interval has a month or day component, so its length is ambiguous")
+            }
+            ErrorKind::Negative => f.write_str("interval is negative"),
+            ErrorKind::OutOfRange => f.write_str("value out of range"),
+        }
+    }
+}
+
+impl Error for TryFromIntervalError {}
+
+impl TryFrom<PgInterval> for Duration {
+    type Error = TryFromIntervalError;
+
+    fn try_from(interval: PgInterval) -> Result<Duration, TryFromIntervalError> {
+        let microseconds = interval.fixed_microseconds()?;
+        if microseconds < 0 {
+            return Err(TryFromIntervalError(ErrorKind::Negative));
+        }
+        Ok(Duration::from_micros(microseconds as u64))
+    }
+}
+
+/// Converts a duration to an interval with no month or day component.
+///
+/// Precision beyond microseconds is truncated.
+impl TryFrom<Duration> for PgInterval {
+    type Error = TryFromIntervalError;
+
+    fn try_from(duration: Duration) -> Result<PgInterval, TryFromIntervalError> {
+        let microseconds = i64::try_from(duration.as_micros())
+            .map_err(|_| TryFromIntervalError::out_of_range())?;
+        Ok(PgInterval::new(0, 0, microseconds))
+    }
+}
+
+impl<'a> FromSql<'a> for PgInterval {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let interval = types::interval_from_sql(raw)?;
+        Ok(PgInterval::new(
+            interval.months(),
+            interval.days(),
+            interval.microseconds(),
+        ))
+    }
+
+    accepts!(INTERVAL);
+}
+
+impl ToSql for PgInterval {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::interval_to_sql(self.months, self.days, self.microseconds, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(INTERVAL);
+
+    to_sql_checked!();
+}
@@ -130,6 +130,11 @@ use crate::type_gen::{Inner, Other};
 #[doc(inline)]
 pub use postgres_protocol::Oid;
 
+pub use crate::interval::{PgInterval, TryFromIntervalError};
+pub use crate::multirange::Multirange;
+pub use crate::numeric::{ParseNumericError, PgNumeric};
+pub use crate::pg_lsn::{ParseLsnError, PgLsn};
+pub use crate::range::{Range, RangeBound};
 pub use crate::special::{Date, Timestamp};
 use bytes::BytesMut;
 
@@ -188,6 +193,8 @@ where
     v.to_sql(ty, out)
 }
 
+#[cfg(feature = "with-bigdecimal-0_2")]
+mod bigdecimal_02;
 #[cfg(feature = "with-bit-vec-0_6")]
 mod bit_vec_06;
 #[cfg(feature = "with-chrono-0_4")]
@@ -198,6 +205,8 @@ mod eui48_04;
 mod geo_types_04;
 #[cfg(feature = "with-geo-types-0_6")]
 mod geo_types_06;
+#[cfg(feature = "with-rust_decimal-1")]
+mod rust_decimal_1;
 #[cfg(feature = "with-serde_json-1")]
 mod serde_json_1;
 #[cfg(feature = "with-time-0_2")]
@@ -209,8 +218,13 @@ mod uuid_08;
 #[cfg(feature = "with-time-0_2")]
 extern crate time_02 as time;
 
+mod interval;
+mod multirange;
+mod numeric;
+mod pg_lsn;
 #[doc(hidden)]
 pub mod private;
+mod range;
 mod special;
 mod type_gen;
 
@@ -289,6 +303,8 @@ pub enum Kind {
     Array(Type),
     /// A range type along with the type of its elements.
     Range(Type),
+    /// A multirange type along with the type of its ranges.
+    Multirange(Type),
     /// A domain type along with its underlying type.
     Domain(Type),
     /// A composite type along with information about its fields.
@@ -384,6 +400,11 @@ impl WrongType {
 /// | `HashMap<String, Option<String>>` | HSTORE                                        |
 /// | `SystemTime`                      | TIMESTAMP, TIMESTAMP WITH TIME ZONE           |
 /// | `IpAddr`                          | INET                                          |
+/// | `PgLsn`                           | PG_LSN                                        |
+/// | `PgNumeric`                       | NUMERIC                                       |
+/// | `PgInterval`                      | INTERVAL                                      |
+/// | `Range<T>`                        | Range types, such as INT4RANGE and DATERANGE  |
+/// | `Multirange<T>`                   | Multirange types, such as INT4MULTIRANGE      |
 ///
 /// In addition, some implementations are provided for types in third party
 /// crates. These are disabled by default; to opt into one of these
@@ -410,6 +431,8 @@ impl WrongType {
 /// | `serde_json::Value`             | JSON, JSONB                         |
 /// | `uuid::Uuid`                    | UUID                                |
 /// | `bit_vec::BitVec`               | BIT, VARBIT                         |
+/// | `rust_decimal::Decimal`         | NUMERIC                             |
+/// | `bigdecimal::BigDecimal`        | NUMERIC                             |
 /// | `eui48::MacAddress`             | MACADDR                             |
 ///
 /// # Nullability
@@ -617,6 +640,25 @@ impl<'a> FromSql<'a> for IpAddr {
     accepts!(INET);
 }
 
+/// The format of a value sent to or received from Postgres.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// The text format.
+    Text,
+    /// The binary format.
+    Binary,
+}
+
+impl Format {
+    /// Returns the format code used in protocol messages.
+    pub fn code(self) -> i16 {
+        match self {
+            Format::Text => 0,
+            Format::Binary => 1,
+        }
+    }
+}
+
 /// An enum representing the nullability of a Postgres value.
 pub enum IsNull {
     /// The value is NULL.
@@ -647,6 +689,11 @@ pub enum IsNull {
 /// | `HashMap<String, Option<String>>` | HSTORE                               |
 /// | `SystemTime`                      | TIMESTAMP, TIMESTAMP WITH TIME ZONE  |
 /// | `IpAddr`                          | INET                                 |
+/// | `PgLsn`                           | PG_LSN                               |
+/// | `PgNumeric`                       | NUMERIC                              |
+/// | `PgInterval`                      | INTERVAL                             |
+/// | `Range<T>`                        | Range types, such as INT4RANGE       |
+/// | `Multirange<T>`                   | Multirange types                     |
 ///
 /// In addition, some implementations are provided for types in third party
 /// crates. These are disabled by default; to opt into one of these
@@ -673,6 +720,8 @@ pub enum IsNull {
 /// | `serde_json::Value`             | JSON, JSONB                         |
 /// | `uuid::Uuid`                    | UUID                                |
 /// | `bit_vec::BitVec`               | BIT, VARBIT                         |
+/// | `rust_decimal::Decimal`         | NUMERIC                             |
+/// | `bigdecimal::BigDecimal`        | NUMERIC                             |
 /// | `eui48::MacAddress`             | MACADDR                             |
 ///
 /// # Nullability
@@ -0,0 +1,123 @@
+//! Postgres multirange types.
+
+use bytes::BytesMut;
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::types;
+use std::error::Error;
+use std::iter::FromIterator;
+
+use crate::{FromSql, IsNull, Kind, Range, ToSql, Type};
+
+/// A Postgres multirange, such as `INT4MULTIRANGE`, available in Postgres 14 and newer.
+///
+/// A multirange is an ordered list of ranges. The server normalizes multiranges it returns by removing empty ranges
+/// and merging ranges which overlap or are adjacent, but values sent to the server need not be normalized.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Multirange<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Multirange<T> {
+    /// Creates a new multirange from its ranges.
+    pub fn new(ranges: Vec<Range<T>>) -> Multirange<T> {
+        Multirange { ranges }
+    }
+
+    /// Returns the empty multirange.
+    pub fn empty() -> Multirange<T> {
+        Multirange { ranges: vec![] }
+    }
+
+    /// Determines if the multirange has no values.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().all(Range::is_empty)
+    }
+
+    /// Returns the ranges of the multirange.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    /// Consumes the multirange, returning its ranges.
+    pub fn into_ranges(self) -> Vec<Range<T>> {
+        self.ranges
+    }
+}
+
+impl<T: PartialOrd> Multirange<T> {
+    /// Determines if any range of the multirange contains a value, like the Postgres `@>` operator.
+    pub fn contains(&self, value: &T) -> bool {
+        self.ranges.iter().any(|r| r.contains(value))
+    }
+
+    /// Determines if any range of the multirange has values in common with a range, like the Postgres `&&` operator.
+    pub fn overlaps(&self, other: &Range<T>) -> bool {
+        self.ranges.iter().any(|r| r.overlaps(other))
+    }
+}
+
+impl<T> From<Vec<Range<T>>> for Multirange<T> {
+    fn from(ranges: Vec<Range<T>>) -> Multirange<T> {
+        Multirange::new(ranges)
+    }
+}
+
+impl<T> FromIterator<Range<T>> for Multirange<T> {
+    fn from_iter<I>(iter: I) -> Multirange<T>
+    where
+        I: IntoIterator<Item = Range<T>>,
+    {
+        Multirange::new(iter.into_iter().collect())
+    }
+}
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for Multirange<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Multirange<T>, Box<dyn Error + Sync + Send>> {
+        let range_type = match *ty.kind() {
+            Kind::Multirange(ref range_type) => range_type,
+            _ => panic!("expected multirange type"),
+        };
+        let member_type = match *range_type.kind() {
+            Kind::Range(ref member) => member,
+            _ => panic!("expected range type"),
+        };
+
+        let ranges = types::multirange_from_sql(raw)?
+            .ranges()
+            .map(|range| Range::from_raw(range, member_type))
+            .collect()?;
+        Ok(Multirange { ranges })
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Multirange(ref inner) => Range::<T>::accepts(inner),
+            _ => false,
+        }
+    }
+}
+
+impl<T: ToSql> ToSql for Multirange<T> {
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let range_type = match *ty.kind() {
+            Kind::Multirange(ref range_type) => range_type,
+            _ => panic!("expected multirange type"),
+        };
+
+        types::multirange_to_sql(
+            &self.ranges,
+            |range, buf| range.to_sql(range_type, buf).map(|_| ()),
+            w,
+        )?;
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Multirange(ref inner) => Range::<T>::accepts(inner),
+            _ => false,
+        }
+    }
+
+    to_sql_checked!();
+}
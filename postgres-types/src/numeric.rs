@@ -0,0 +1,301 @@
+//! Arbitrary precision `NUMERIC` type.
+
+use bytes::BytesMut;
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::types::{self, NumericSign};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+// the largest display scale which can be sent to the server
+const MAX_SCALE: i64 = 0x3FFF;
+
+/// Postgres `NUMERIC` type.
+///
+/// Every value the server can store is represented exactly, including `NaN` and the infinities. The display scale
+/// is preserved, so `1.5` and `1.50` are different values, just as they are displayed differently by Postgres.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PgNumeric {
+    sign: NumericSign,
+    weight: i16,
+    scale: u16,
+    // base 10000, with no leading or trailing zeros
+    digits: Vec<i16>,
+}
+
+/// Error parsing a `PgNumeric`.
+#[derive(Debug)]
+pub struct ParseNumericError(());
+
+impl PgNumeric {
+    /// Returns the `NaN` value.
+    pub fn nan() -> PgNumeric {
+        PgNumeric::special(NumericSign::NaN)
+    }
+
+    /// Returns positive infinity.
+    pub fn infinity() -> PgNumeric {
+        PgNumeric::special(NumericSign::PositiveInfinity)
+    }
+
+    /// Returns negative infinity.
+    pub fn neg_infinity() -> PgNumeric {
+        PgNumeric::special(NumericSign::NegativeInfinity)
+    }
+
+    fn special(sign: NumericSign) -> PgNumeric {
+        PgNumeric {
+            sign,
+            weight: 0,
+            scale: 0,
+            digits: vec![],
+        }
+    }
+
+    /// Determines if the value is `NaN`.
+    pub fn is_nan(&self) -> bool {
+        self.sign == NumericSign::NaN
+    }
+
+    /// Determines if the value is positive or negative infinity.
+    pub fn is_infinite(&self) -> bool {
+        self.sign == NumericSign::PositiveInfinity || self.sign == NumericSign::NegativeInfinity
+    }
+
+    /// Determines if the value is neither `NaN` nor infinite.
+    pub fn is_finite(&self) -> bool {
+        self.sign == NumericSign::Positive || self.sign == NumericSign::Negative
+    }
+
+    /// Determines if the value is negative, including negative infinity.
+    pub fn is_sign_negative(&self) -> bool {
+        self.sign == NumericSign::Negative || self.sign == NumericSign::NegativeInfinity
+    }
+
+    /// Returns the number of decimal digits after the decimal point.
+    pub fn scale(&self) -> u16 {
+        self.scale
+    }
+
+    /// Creates a finite value from its decimal digits, `point` of which are before the decimal point.
+    ///
+    /// `point` may be negative or larger than the number of digits. Returns `None` if the value is out of range.
+    pub(crate) fn from_decimal(
+        negative: bool,
+        mut digits: &[u8],
+        mut point: i64,
+    ) -> Option<PgNumeric> {
+        let scale = (digits.len() as i64 - point).max(0);
+        if scale > MAX_SCALE {
+            return None;
+        }
+
+        while let Some((&b'0', rest)) = digits.split_first() {
+            digits = rest;
+            point -= 1;
+        }
+        while let Some((&b'0', rest)) = digits.split_last() {
+            digits = rest;
+        }
+        if digits.is_empty() {
+            return Some(PgNumeric {
+                sign: NumericSign::Positive,
+                weight: 0,
+                scale: scale as u16,
+                digits: vec![],
+            });
+        }
+
+        // align the digits to base 10000 groups
+        let pad = (4 - point.rem_euclid(4)) % 4;
+        let weight = (point + pad) / 4 - 1;
+        if weight < i64::from(i16::min_value()) || weight > i64::from(i16::max_value()) {
+            return None;
+        }
+        let decimal = (0..pad)
+            .map(|_| 0)
+            .chain(digits.iter().map(|d| i16::from(d - b'0')))
+            .collect::<Vec<_>>();
+        let digits = decimal
+            .chunks(4)
+            .map(|c| (0..4).fold(0, |acc, i| acc * 10 + c.get(i).cloned().unwrap_or(0)))
+            .collect();
+
+        Some(PgNumeric {
+            sign: if negative {
+                NumericSign::Negative
+            } else {
+                NumericSign::Positive
+            },
+            weight: weight as i16,
+            scale: scale as u16,
+            digits,
+        })
+    }
+
+    /// Returns the decimal digits of a finite value multiplied by `10^scale`, without a sign.
+    pub(crate) fn unscaled_digits(&self) -> Option<String> {
+        if !self.is_finite() {
+            return None;
+        }
+
+        let frac_groups = (i32::from(self.scale) + 3) / 4;
+        let mut out = String::new();
+        for power in (-frac_groups..=i32::from(self.weight).max(0)).rev() {
+            let idx = i32::from(self.weight) - power;
+            let digit = if idx >= 0 {
+                self.digits.get(idx as usize).cloned().unwrap_or(0)
+            } else {
+                0
+            };
+            out.push_str(&format!("{:04}", digit));
+        }
+        out.truncate(out.len() - (frac_groups * 4 - i32::from(self.scale)) as usize);
+
+        let zeros = out.bytes().take_while(|b| *b == b'0').count();
+        out.drain(..zeros);
+        if out.is_empty() {
+            out.push('0');
+        }
+        Some(out)
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for PgNumeric {
+                fn from(v: $t) -> PgNumeric {
+                    let s = v.to_string();
+                    let (negative, digits) = if s.starts_with('-') {
+                        (true, &s[1..])
+                    } else {
+                        (false, &s[..])
+                    };
+                    PgNumeric::from_decimal(negative, digits.as_bytes(), digits.len() as i64)
+                        .expect("integers are in range")
+                }
+            }
+        )*
+    };
+}
+
+from_int!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl FromStr for PgNumeric {
+    type Err = ParseNumericError;
+
+    fn from_str(s: &str) -> Result<PgNumeric, ParseNumericError> {
+        match &*s.to_ascii_lowercase() {
+            "nan" => return Ok(PgNumeric::nan()),
+            "infinity" | "+infinity" | "inf" | "+inf" => return Ok(PgNumeric::infinity()),
+            "-infinity" | "-inf" => return Ok(PgNumeric::neg_infinity()),
+            _ => {}
+        }
+
+        let (negative, s) = if s.starts_with('-') {
+            (true, &s[1..])
+        } else if s.starts_with('+') {
+            (false, &s[1..])
+        } else {
+            (false, s)
+        };
+
+        let mut parts = s.splitn(2, &['e', 'E'][..]);
+        let mantissa = parts.next().unwrap_or("");
+        let exponent = match parts.next() {
+            Some(exponent) => exponent.parse::<i32>().map_err(|_| ParseNumericError(()))?,
+            None => 0,
+        };
+
+        let mut parts = mantissa.splitn(2, '.');
+        let int = parts.next().unwrap_or("");
+        let frac = parts.next().unwrap_or("");
+        if int.len() + frac.len() == 0
+            || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return Err(ParseNumericError(()));
+        }
+
+        let digits = format!("{}{}", int, frac);
+        let point = int.len() as i64 + i64::from(exponent);
+        PgNumeric::from_decimal(negative, digits.as_bytes(), point).ok_or(ParseNumericError(()))
+    }
+}
+
+impl fmt::Display for PgNumeric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = match self.sign {
+            NumericSign::NaN => return f.write_str("NaN"),
+            NumericSign::PositiveInfinity => return f.write_str("Infinity"),
+            NumericSign::NegativeInfinity => return f.write_str("-Infinity"),
+            NumericSign::Negative => {
+                f.write_str("-")?;
+                self.unscaled_digits().unwrap()
+            }
+            NumericSign::Positive => self.unscaled_digits().unwrap(),
+        };
+
+        let scale = usize::from(self.scale);
+        if scale > 0 {
+            if digits.len() <= scale {
+                let zeros = "0".repeat(scale + 1 - digits.len());
+                digits.insert_str(0, &zeros);
+            }
+            digits.insert(digits.len() - scale, '.');
+        }
+        f.write_str(&digits)
+    }
+}
+
+impl fmt::Debug for PgNumeric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for ParseNumericError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid numeric")
+    }
+}
+
+impl Error for ParseNumericError {}
+
+impl<'a> FromSql<'a> for PgNumeric {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let numeric = types::numeric_from_sql(raw)?;
+        match numeric.sign() {
+            NumericSign::Positive | NumericSign::Negative => {}
+            // the server doesn't zero the other fields of special values
+            sign => return Ok(PgNumeric::special(sign)),
+        }
+        Ok(PgNumeric {
+            sign: numeric.sign(),
+            weight: numeric.weight(),
+            scale: numeric.scale(),
+            digits: numeric.digits().collect()?,
+        })
+    }
+
+    accepts!(NUMERIC);
+}
+
+impl ToSql for PgNumeric {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::numeric_to_sql(
+            self.sign,
+            self.weight,
+            self.scale,
+            self.digits.iter().cloned(),
+            out,
+        )?;
+        Ok(IsNull::No)
+    }
+
+    accepts!(NUMERIC);
+
+    to_sql_checked!();
+}
@@ -0,0 +1,86 @@
+//! Log Sequence Number (LSN) type for PostgreSQL Write-Ahead Log
+//! (WAL), also known as the transaction log.
+
+use bytes::BytesMut;
+use postgres_protocol::types;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+/// Postgres `PG_LSN` type.
+#[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct PgLsn(u64);
+
+/// Error parsing LSN.
+#[derive(Debug)]
+pub struct ParseLsnError(());
+
+impl From<u64> for PgLsn {
+    fn from(lsn_u64: u64) -> Self {
+        PgLsn(lsn_u64)
+    }
+}
+
+impl From<PgLsn> for u64 {
+    fn from(lsn: PgLsn) -> u64 {
+        lsn.0
+    }
+}
+
+impl FromStr for PgLsn {
+    type Err = ParseLsnError;
+
+    fn from_str(lsn_str: &str) -> Result<Self, Self::Err> {
+        let mut split = lsn_str.split('/');
+        match (split.next(), split.next(), split.next()) {
+            (Some(upper), Some(lower), None) if !upper.is_empty() && !lower.is_empty() => {
+                let upper = u32::from_str_radix(upper, 16).map_err(|_| ParseLsnError(()))?;
+                let lower = u32::from_str_radix(lower, 16).map_err(|_| ParseLsnError(()))?;
+                Ok(PgLsn((u64::from(upper) << 32) | u64::from(lower)))
+            }
+            _ => Err(ParseLsnError(())),
+        }
+    }
+}
+
+impl fmt::Display for PgLsn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 & 0x0000_0000_ffff_ffff)
+    }
+}
+
+impl fmt::Debug for PgLsn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for ParseLsnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid LSN")
+    }
+}
+
+impl Error for ParseLsnError {}
+
+impl<'a> FromSql<'a> for PgLsn {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let v = types::lsn_from_sql(raw)?;
+        Ok(v.into())
+    }
+
+    accepts!(PG_LSN);
+}
+
+impl ToSql for PgLsn {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::lsn_to_sql((*self).into(), out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(PG_LSN);
+
+    to_sql_checked!();
+}
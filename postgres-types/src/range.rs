@@ -0,0 +1,257 @@
+//! Postgres range types.
+
+use bytes::BytesMut;
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::{FromSql, IsNull, Kind, ToSql, Type};
+
+/// One side of a range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RangeBound<T> {
+    /// An inclusive bound.
+    Inclusive(T),
+    /// An exclusive bound.
+    Exclusive(T),
+    /// No bound.
+    Unbounded,
+}
+
+impl<T> RangeBound<T> {
+    /// Returns the value of the bound, or `None` if it is unbounded.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            RangeBound::Inclusive(v) | RangeBound::Exclusive(v) => Some(v),
+            RangeBound::Unbounded => None,
+        }
+    }
+}
+
+/// A Postgres range, such as `INT4RANGE`, `TSTZRANGE` or `DATERANGE`.
+///
+/// `T` is the Rust type of the range's subtype. Note that the server canonicalizes ranges of discrete types, so the
+/// `INT4RANGE` value `[1,3]` is returned as `[1,4)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Range<T> {
+    /// An empty range.
+    Empty,
+    /// A nonempty range.
+    Nonempty(RangeBound<T>, RangeBound<T>),
+}
+
+impl<T> Range<T> {
+    /// Creates a new nonempty range.
+    pub fn new(lower: RangeBound<T>, upper: RangeBound<T>) -> Range<T> {
+        Range::Nonempty(lower, upper)
+    }
+
+    /// Returns the empty range.
+    pub fn empty() -> Range<T> {
+        Range::Empty
+    }
+
+    /// Determines if the range is the empty range.
+    ///
+    /// Nonempty ranges whose bounds exclude every value, such as `[2,1]`, are not considered empty.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Range::Empty => true,
+            Range::Nonempty(..) => false,
+        }
+    }
+
+    /// Returns the lower bound of the range, or `None` if it is empty.
+    pub fn lower(&self) -> Option<&RangeBound<T>> {
+        match self {
+            Range::Empty => None,
+            Range::Nonempty(lower, _) => Some(lower),
+        }
+    }
+
+    /// Returns the upper bound of the range, or `None` if it is empty.
+    pub fn upper(&self) -> Option<&RangeBound<T>> {
+        match self {
+            Range::Empty => None,
+            Range::Nonempty(_, upper) => Some(upper),
+        }
+    }
+}
+
+// The helpers below treat bounds as continuous, which matches the server for the canonical form of discrete ranges.
+impl<T: PartialOrd> Range<T> {
+    /// Determines if the range contains a value, like the Postgres `@>` operator.
+    pub fn contains(&self, value: &T) -> bool {
+        match self {
+            Range::Empty => false,
+            Range::Nonempty(lower, upper) => {
+                let above_lower = match lower {
+                    RangeBound::Inclusive(l) => l <= value,
+                    RangeBound::Exclusive(l) => l < value,
+                    RangeBound::Unbounded => true,
+                };
+                let below_upper = match upper {
+                    RangeBound::Inclusive(u) => value <= u,
+                    RangeBound::Exclusive(u) => value < u,
+                    RangeBound::Unbounded => true,
+                };
+                above_lower && below_upper
+            }
+        }
+    }
+
+    /// Determines if the range contains every value of another range, like the Postgres `@>` operator.
+    ///
+    /// Every range contains the empty range.
+    pub fn contains_range(&self, other: &Range<T>) -> bool {
+        match (self, other) {
+            (_, Range::Empty) => true,
+            (Range::Empty, _) => false,
+            (Range::Nonempty(lower, upper), Range::Nonempty(other_lower, other_upper)) => {
+                lower_le(lower, other_lower) && upper_ge(upper, other_upper)
+            }
+        }
+    }
+
+    /// Determines if the range has any values in common with another range, like the Postgres `&&` operator.
+    pub fn overlaps(&self, other: &Range<T>) -> bool {
+        match (self, other) {
+            (Range::Nonempty(lower, upper), Range::Nonempty(other_lower, other_upper)) => {
+                intersects(lower, upper)
+                    && intersects(other_lower, other_upper)
+                    && intersects(lower, other_upper)
+                    && intersects(other_lower, upper)
+            }
+            _ => false,
+        }
+    }
+}
+
+// determines if a lower bound starts at or before another
+fn lower_le<T: PartialOrd>(a: &RangeBound<T>, b: &RangeBound<T>) -> bool {
+    match (a, b) {
+        (RangeBound::Unbounded, _) => true,
+        (_, RangeBound::Unbounded) => false,
+        (RangeBound::Exclusive(a), RangeBound::Inclusive(b)) => a < b,
+        (RangeBound::Inclusive(a), RangeBound::Inclusive(b))
+        | (RangeBound::Inclusive(a), RangeBound::Exclusive(b))
+        | (RangeBound::Exclusive(a), RangeBound::Exclusive(b)) => a <= b,
+    }
+}
+
+// determines if an upper bound ends at or after another
+fn upper_ge<T: PartialOrd>(a: &RangeBound<T>, b: &RangeBound<T>) -> bool {
+    match (a, b) {
+        (RangeBound::Unbounded, _) => true,
+        (_, RangeBound::Unbounded) => false,
+        (RangeBound::Exclusive(a), RangeBound::Inclusive(b)) => a > b,
+        (RangeBound::Inclusive(a), RangeBound::Inclusive(b))
+        | (RangeBound::Inclusive(a), RangeBound::Exclusive(b))
+        | (RangeBound::Exclusive(a), RangeBound::Exclusive(b)) => a >= b,
+    }
+}
+
+// determines if any value lies between a lower and an upper bound
+fn intersects<T: PartialOrd>(lower: &RangeBound<T>, upper: &RangeBound<T>) -> bool {
+    match (lower, upper) {
+        (RangeBound::Unbounded, _) | (_, RangeBound::Unbounded) => true,
+        (RangeBound::Inclusive(l), RangeBound::Inclusive(u)) => l <= u,
+        (RangeBound::Inclusive(l), RangeBound::Exclusive(u))
+        | (RangeBound::Exclusive(l), RangeBound::Inclusive(u))
+        | (RangeBound::Exclusive(l), RangeBound::Exclusive(u)) => l < u,
+    }
+}
+
+impl<'a, T: FromSql<'a>> Range<T> {
+    pub(crate) fn from_raw(
+        range: types::Range<'a>,
+        member_type: &Type,
+    ) -> Result<Range<T>, Box<dyn Error + Sync + Send>> {
+        match range {
+            types::Range::Empty => Ok(Range::Empty),
+            types::Range::Nonempty(lower, upper) => Ok(Range::Nonempty(
+                bound_from_sql(lower, member_type)?,
+                bound_from_sql(upper, member_type)?,
+            )),
+        }
+    }
+}
+
+fn bound_from_sql<'a, T>(
+    bound: types::RangeBound<Option<&'a [u8]>>,
+    ty: &Type,
+) -> Result<RangeBound<T>, Box<dyn Error + Sync + Send>>
+where
+    T: FromSql<'a>,
+{
+    match bound {
+        types::RangeBound::Inclusive(v) => Ok(RangeBound::Inclusive(T::from_sql_nullable(ty, v)?)),
+        types::RangeBound::Exclusive(v) => Ok(RangeBound::Exclusive(T::from_sql_nullable(ty, v)?)),
+        types::RangeBound::Unbounded => Ok(RangeBound::Unbounded),
+    }
+}
+
+fn bound_to_sql<T>(
+    bound: &RangeBound<T>,
+    ty: &Type,
+    buf: &mut BytesMut,
+) -> Result<types::RangeBound<postgres_protocol::IsNull>, Box<dyn Error + Sync + Send>>
+where
+    T: ToSql,
+{
+    let is_null = |v: &T, buf: &mut BytesMut| match v.to_sql(ty, buf)? {
+        IsNull::No => Ok::<_, Box<dyn Error + Sync + Send>>(postgres_protocol::IsNull::No),
+        IsNull::Yes => Ok(postgres_protocol::IsNull::Yes),
+    };
+
+    match bound {
+        RangeBound::Inclusive(v) => Ok(types::RangeBound::Inclusive(is_null(v, buf)?)),
+        RangeBound::Exclusive(v) => Ok(types::RangeBound::Exclusive(is_null(v, buf)?)),
+        RangeBound::Unbounded => Ok(types::RangeBound::Unbounded),
+    }
+}
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for Range<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Range<T>, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Range(ref member) => member,
+            _ => panic!("expected range type"),
+        };
+
+        Range::from_raw(types::range_from_sql(raw)?, member_type)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Range(ref inner) => T::accepts(inner),
+            _ => false,
+        }
+    }
+}
+
+impl<T: ToSql> ToSql for Range<T> {
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Range(ref member) => member,
+            _ => panic!("expected range type"),
+        };
+
+        match self {
+            Range::Empty => types::empty_range_to_sql(w),
+            Range::Nonempty(lower, upper) => types::range_to_sql(
+                |buf| bound_to_sql(lower, member_type, buf),
+                |buf| bound_to_sql(upper, member_type, buf),
+                w,
+            )?,
+        }
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Range(ref inner) => T::accepts(inner),
+            _ => false,
+        }
+    }
+
+    to_sql_checked!();
+}
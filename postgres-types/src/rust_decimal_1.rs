@@ -0,0 +1,55 @@
+use bytes::BytesMut;
+use rust_decimal_1::Decimal;
+use std::error::Error;
+
+use crate::{FromSql, IsNull, PgNumeric, ToSql, Type};
+
+// the largest scale a Decimal can represent
+const MAX_SCALE: u32 = 28;
+
+impl<'a> FromSql<'a> for Decimal {
+    fn from_sql(ty: &Type, raw: &[u8]) -> Result<Decimal, Box<dyn Error + Sync + Send>> {
+        let numeric = PgNumeric::from_sql(ty, raw)?;
+        let mut digits = match numeric.unscaled_digits() {
+            Some(digits) => digits,
+            None => return Err("NaN and infinite values cannot be decoded as a Decimal".into()),
+        };
+
+        // drop trailing zeros which would take the scale past what Decimal supports
+        let mut scale = u32::from(numeric.scale());
+        while scale > MAX_SCALE && digits.ends_with('0') {
+            digits.pop();
+            scale -= 1;
+        }
+        if scale > MAX_SCALE {
+            return Err("value has too many decimal places to decode as a Decimal".into());
+        }
+
+        let mantissa = digits
+            .parse::<i128>()
+            .map_err(|_| "value too large to decode as a Decimal")?;
+        let mantissa = if numeric.is_sign_negative() {
+            -mantissa
+        } else {
+            mantissa
+        };
+        Decimal::try_from_i128_with_scale(mantissa, scale)
+            .map_err(|_| "value too large to decode as a Decimal".into())
+    }
+
+    accepts!(NUMERIC);
+}
+
+impl ToSql for Decimal {
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let mantissa = self.mantissa();
+        let digits = mantissa.abs().to_string();
+        let point = digits.len() as i64 - i64::from(self.scale());
+        let numeric = PgNumeric::from_decimal(mantissa < 0, digits.as_bytes(), point)
+            .ok_or("value too large to transmit")?;
+        numeric.to_sql(ty, w)
+    }
+
+    accepts!(NUMERIC);
+    to_sql_checked!();
+}
@@ -4,7 +4,7 @@ use std::convert::TryFrom;
 use std::error::Error;
 use time_02::{date, time, Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
 
-use crate::{FromSql, IsNull, ToSql, Type};
+use crate::{FromSql, IsNull, PgInterval, ToSql, TryFromIntervalError, Type};
 
 #[rustfmt::skip]
 const fn base() -> PrimitiveDateTime {
@@ -107,3 +107,21 @@ impl ToSql for Time {
     accepts!(TIME);
     to_sql_checked!();
 }
+
+impl TryFrom<PgInterval> for Duration {
+    type Error = TryFromIntervalError;
+
+    fn try_from(interval: PgInterval) -> Result<Duration, TryFromIntervalError> {
+        interval.fixed_microseconds().map(Duration::microseconds)
+    }
+}
+
+impl TryFrom<Duration> for PgInterval {
+    type Error = TryFromIntervalError;
+
+    fn try_from(duration: Duration) -> Result<PgInterval, TryFromIntervalError> {
+        let microseconds = i64::try_from(duration.whole_microseconds())
+            .map_err(|_| TryFromIntervalError::out_of_range())?;
+        Ok(PgInterval::new(0, 0, microseconds))
+    }
+}
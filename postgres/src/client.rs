@@ -3,9 +3,10 @@ use crate::{
     CancelToken, Config, CopyInWriter, CopyOutReader, Notifications, RowIter, Statement,
     ToStatement, Transaction, TransactionBuilder,
 };
+use bytes::Bytes;
 use std::task::Poll;
 use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
-use tokio_postgres::types::{ToSql, Type};
+use tokio_postgres::types::{Format, Oid, ToSql, Type};
 use tokio_postgres::{Error, Row, SimpleQueryMessage, Socket};
 
 /// A synchronous PostgreSQL client.
@@ -275,6 +276,52 @@ impl Client {
         Ok(RowIter::new(self.connection.as_ref(), stream))
     }
 
+    /// Executes a statement with explicitly typed parameters, returning the resulting rows.
+    ///
+    /// Unlike `query`, the query string is not prepared separately: it is parsed into the unnamed statement, bound, and
+    /// executed in a single round trip. The types of the parameters are taken from `params` rather than inferred by the
+    /// server.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    /// use postgres::types::Type;
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let baz = true;
+    /// for row in client.query_typed("SELECT foo FROM bar WHERE baz = $1", &[(&baz, Type::BOOL)])? {
+    ///     let foo: i32 = row.get("foo");
+    ///     println!("foo: {}", foo);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_typed(
+        &mut self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error> {
+        self.connection
+            .block_on(self.client.query_typed(query, params))
+    }
+
+    /// A maximally-flexible version of `query_typed`.
+    ///
+    /// It takes an iterator of parameters and their types rather than a slice, and returns an iterator of rows rather
+    /// than collecting them into an array.
+    pub fn query_typed_raw<'a, I>(&mut self, query: &str, params: I) -> Result<RowIter<'_>, Error>
+    where
+        I: IntoIterator<Item = (&'a dyn ToSql, Type)>,
+    {
+        let stream = self
+            .connection
+            .block_on(self.client.query_typed_raw(query, params))?;
+        Ok(RowIter::new(self.connection.as_ref(), stream))
+    }
+
     /// Creates a new prepared statement.
     ///
     /// Prepared statements can be executed repeatedly, and may contain query parameters (indicated by `$1`, `$2`, etc),
@@ -426,6 +473,20 @@ impl Client {
         self.connection.block_on(self.client.batch_execute(query))
     }
 
+    /// Calls a function through the "fast-path" interface, bypassing the SQL parser.
+    ///
+    /// The function is identified by its OID, and its arguments must already be encoded in the binary format, with
+    /// `None` representing `NULL`. The result is returned in the requested format, or `None` if it is `NULL`.
+    pub fn call_function(
+        &mut self,
+        oid: Oid,
+        args: &[Option<&[u8]>],
+        result_format: Format,
+    ) -> Result<Option<Bytes>, Error> {
+        self.connection
+            .block_on(self.client.call_function(oid, args, result_format))
+    }
+
     /// Begins a new database transaction.
     ///
     /// The transaction will roll back by default - use the `commit` method to commit it.
@@ -525,6 +586,20 @@ impl Client {
         CancelToken::new(self.client.cancel_token())
     }
 
+    /// Clears the client's cache of prepared statements.
+    ///
+    /// See `Config::statement_cache_capacity`.
+    pub fn clear_statement_cache(&self) {
+        self.client.clear_statement_cache()
+    }
+
+    /// Clears the client's cache of type information.
+    ///
+    /// See `tokio_postgres::Client::clear_type_cache`.
+    pub fn clear_type_cache(&self) {
+        self.client.clear_type_cache()
+    }
+
     /// Determines if the client's connection has already closed.
     ///
     /// If this returns `true`, the client is no longer usable.
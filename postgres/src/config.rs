@@ -10,7 +10,10 @@ use std::str::FromStr;
 use std::time::Duration;
 use tokio::runtime;
 #[doc(inline)]
-pub use tokio_postgres::config::{ChannelBinding, Host, SslMode, TargetSessionAttrs};
+pub use tokio_postgres::config::{
+    AuthMethod, ChannelBinding, Host, LoadBalanceHosts, PasswordProvider, SslMode, SslNegotiation,
+    TargetSessionAttrs,
+};
 use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
 use tokio_postgres::{Error, Socket};
 
@@ -31,7 +34,20 @@ use tokio_postgres::{Error, Socket};
 /// * `options` - Command line options used to configure the server.
 /// * `application_name` - Sets the `application_name` parameter on the server.
 /// * `sslmode` - Controls usage of TLS. If set to `disable`, TLS will not be used. If set to `prefer`, TLS will be used
-///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. Defaults to `prefer`.
+///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. If set to `verify-ca`
+///     or `verify-full`, TLS will be forced to be used and the TLS connector is expected to verify the server's
+///     certificate chain, and for `verify-full` the hostname as well. Defaults to `prefer`.
+/// * `sslnegotiation` - Controls how TLS is negotiated with the server. If set to `postgres`, the client asks the server
+///     whether it supports TLS before starting the handshake. If set to `direct`, the handshake is started immediately
+///     using the `postgresql` ALPN protocol, which requires PostgreSQL 17 or newer and an `sslmode` of `require`,
+///     `verify-ca` or `verify-full`. Defaults to `postgres`.
+/// * `sslrootcert` - The path to a file containing the certificate authorities trusted to sign the server's
+///     certificate.
+/// * `sslcert` - The path to a file containing the client's certificate.
+/// * `sslkey` - The path to a file containing the private key for the client's certificate.
+/// * `sslpassword` - The password used to decrypt the client's private key.
+/// * `sslcrl` - The path to a file containing a certificate revocation list checked when verifying the server's
+///     certificate.
 /// * `host` - The host to connect to. On Unix platforms, if the host starts with a `/` character it is treated as the
 ///     path to the directory containing Unix domain sockets. Otherwise, it is treated as a hostname. Multiple hosts
 ///     can be specified, separated by commas. Each host will be tried in turn when connecting. Required if connecting
@@ -46,8 +62,24 @@ use tokio_postgres::{Error, Socket};
 /// * `keepalives_idle` - The number of seconds of inactivity after which a keepalive message is sent to the server.
 ///     This option is ignored when connecting with Unix sockets. Defaults to 2 hours.
 /// * `target_session_attrs` - Specifies requirements of the session. If set to `read-write`, the client will check that
-///     the `transaction_read_write` session parameter is set to `on`. This can be used to connect to the primary server
-///     in a database cluster as opposed to the secondary read-only mirrors. Defaults to `all`.
+///     the session allows writes, and if set to `read-only` that it does not. If set to `primary` or `standby`, the
+///     client will check whether the server is in hot standby mode. If set to `prefer-standby`, standby servers are
+///     tried first, and then all hosts are tried again without any requirements. This can be used to connect to the
+///     primary server in a database cluster as opposed to the secondary read-only mirrors. Defaults to `any`.
+/// * `load_balance_hosts` - Controls the order in which the client tries to connect to the available hosts. If set to
+///     `disable`, hosts are tried in the order they were specified. If set to `random`, hosts are tried in a random
+///     order. Defaults to `disable`.
+/// * `require_auth` - A comma-separated list of the authentication methods the server is allowed to request: `password`,
+///     `md5`, `gss`, `sspi`, `scram-sha-256` or `none`, where `none` allows the server to skip authentication. If each
+///     method is instead prefixed with `!`, the server is allowed to request any method except for those listed. The
+///     connection fails if the server requests any other method. Defaults to allowing all methods.
+/// * `statement_cache_capacity` - The maximum number of prepared statements the client caches for reuse, keyed by
+///     query text and parameter types. A value of 0 disables the cache. Defaults to 0.
+/// * `named_statements` - Controls the use of named prepared statements. If set to `false`, `off`, `no` or `0`, queries
+///     are only ever prepared as the unnamed statement, which is required when connecting through a pooler such as
+///     PgBouncer in transaction pooling mode. Defaults to `true`.
+/// * `service` - The name of an entry in the connection service file to take defaults from. This is only used by the
+///     `apply_env` method.
 ///
 /// ## Examples
 ///
@@ -114,6 +146,13 @@ impl Config {
         }
     }
 
+    /// Creates a new configuration from the environment.
+    ///
+    /// This is equivalent to calling `apply_env` on an empty configuration.
+    pub fn from_env() -> Result<Config, Error> {
+        tokio_postgres::Config::from_env().map(Config::from)
+    }
+
     /// Sets the user to authenticate with.
     ///
     /// Required.
@@ -143,6 +182,22 @@ impl Config {
         self.config.get_password()
     }
 
+    /// Sets a provider which is asked for a password each time a connection authenticates.
+    ///
+    /// The provider takes precedence over a password set with the `password` method.
+    pub fn password_provider<P>(&mut self, password_provider: P) -> &mut Config
+    where
+        P: PasswordProvider + 'static,
+    {
+        self.config.password_provider(password_provider);
+        self
+    }
+
+    /// Gets the password provider, if one has been set with the `password_provider` method.
+    pub fn get_password_provider(&self) -> Option<&dyn PasswordProvider> {
+        self.config.get_password_provider()
+    }
+
     /// Sets the name of the database to connect to.
     ///
     /// Defaults to the user.
@@ -194,6 +249,100 @@ impl Config {
         self.config.get_ssl_mode()
     }
 
+    /// Sets how TLS is negotiated with the server.
+    ///
+    /// Defaults to `postgres`.
+    pub fn ssl_negotiation(&mut self, ssl_negotiation: SslNegotiation) -> &mut Config {
+        self.config.ssl_negotiation(ssl_negotiation);
+        self
+    }
+
+    /// Gets how TLS is negotiated with the server.
+    pub fn get_ssl_negotiation(&self) -> SslNegotiation {
+        self.config.get_ssl_negotiation()
+    }
+
+    /// Sets the path to a file containing the certificate authorities trusted to sign the server's certificate.
+    ///
+    /// This is not used by `postgres` itself, but by TLS connectors built from the configuration.
+    pub fn ssl_root_cert<T>(&mut self, ssl_root_cert: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.config.ssl_root_cert(ssl_root_cert);
+        self
+    }
+
+    /// Gets the path to the trusted certificate authorities, if one has been set with the `ssl_root_cert` method.
+    pub fn get_ssl_root_cert(&self) -> Option<&Path> {
+        self.config.get_ssl_root_cert()
+    }
+
+    /// Sets the path to a file containing the client's certificate.
+    ///
+    /// This is not used by `postgres` itself, but by TLS connectors built from the configuration.
+    pub fn ssl_cert<T>(&mut self, ssl_cert: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.config.ssl_cert(ssl_cert);
+        self
+    }
+
+    /// Gets the path to the client's certificate, if one has been set with the `ssl_cert` method.
+    pub fn get_ssl_cert(&self) -> Option<&Path> {
+        self.config.get_ssl_cert()
+    }
+
+    /// Sets the path to a file containing the private key for the client's certificate.
+    ///
+    /// This is not used by `postgres` itself, but by TLS connectors built from the configuration.
+    pub fn ssl_key<T>(&mut self, ssl_key: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.config.ssl_key(ssl_key);
+        self
+    }
+
+    /// Gets the path to the client's private key, if one has been set with the `ssl_key` method.
+    pub fn get_ssl_key(&self) -> Option<&Path> {
+        self.config.get_ssl_key()
+    }
+
+    /// Sets the password used to decrypt the client's private key.
+    ///
+    /// This is not used by `postgres` itself, but by TLS connectors built from the configuration.
+    pub fn ssl_password<T>(&mut self, ssl_password: T) -> &mut Config
+    where
+        T: AsRef<[u8]>,
+    {
+        self.config.ssl_password(ssl_password);
+        self
+    }
+
+    /// Gets the password used to decrypt the client's private key, if one has been set with the `ssl_password`
+    /// method.
+    pub fn get_ssl_password(&self) -> Option<&[u8]> {
+        self.config.get_ssl_password()
+    }
+
+    /// Sets the path to a file containing a certificate revocation list checked when verifying the server's certificate.
+    ///
+    /// This is not used by `postgres` itself, but by TLS connectors built from the configuration.
+    pub fn ssl_crl<T>(&mut self, ssl_crl: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.config.ssl_crl(ssl_crl);
+        self
+    }
+
+    /// Gets the path to the certificate revocation list, if one has been set with the `ssl_crl` method.
+    pub fn get_ssl_crl(&self) -> Option<&Path> {
+        self.config.get_ssl_crl()
+    }
+
     /// Adds a host to the configuration.
     ///
     /// Multiple hosts can be specified by calling this method multiple times, and each will be tried in order. On Unix
@@ -294,6 +443,36 @@ impl Config {
         self.config.get_target_session_attrs()
     }
 
+    /// Sets the host load balancing behavior.
+    ///
+    /// If set to `Random`, the hosts are shuffled before each connection attempt, which spreads clients across a set of
+    /// equivalent servers such as read replicas. Defaults to `Disable`.
+    pub fn load_balance_hosts(&mut self, load_balance_hosts: LoadBalanceHosts) -> &mut Config {
+        self.config.load_balance_hosts(load_balance_hosts);
+        self
+    }
+
+    /// Gets the host load balancing behavior.
+    pub fn get_load_balance_hosts(&self) -> LoadBalanceHosts {
+        self.config.get_load_balance_hosts()
+    }
+
+    /// Sets the authentication methods the server is allowed to request.
+    ///
+    /// The connection fails with an authentication error if the server requests a method which is not in the list. Use
+    /// `AuthMethod::None` to allow servers which don't require authentication at all. Defaults to allowing all
+    /// methods.
+    pub fn require_auth(&mut self, require_auth: &[AuthMethod]) -> &mut Config {
+        self.config.require_auth(require_auth);
+        self
+    }
+
+    /// Gets the authentication methods the server is allowed to request, if they have been restricted with the
+    /// `require_auth` method.
+    pub fn get_require_auth(&self) -> Option<&[AuthMethod]> {
+        self.config.get_require_auth()
+    }
+
     /// Sets the channel binding behavior.
     ///
     /// Defaults to `prefer`.
@@ -307,6 +486,53 @@ impl Config {
         self.config.get_channel_binding()
     }
 
+    /// Sets the maximum number of prepared statements the client caches for reuse.
+    ///
+    /// A capacity of 0 disables the cache. Defaults to 0.
+    pub fn statement_cache_capacity(&mut self, statement_cache_capacity: usize) -> &mut Config {
+        self.config
+            .statement_cache_capacity(statement_cache_capacity);
+        self
+    }
+
+    /// Gets the maximum number of prepared statements the client caches for reuse.
+    pub fn get_statement_cache_capacity(&self) -> usize {
+        self.config.get_statement_cache_capacity()
+    }
+
+    /// Controls the use of named prepared statements.
+    ///
+    /// If disabled, queries are only ever prepared as the unnamed statement, and `Client::prepare` returns an error.
+    /// Defaults to `true`.
+    pub fn named_statements(&mut self, named_statements: bool) -> &mut Config {
+        self.config.named_statements(named_statements);
+        self
+    }
+
+    /// Reports whether named prepared statements will be used.
+    pub fn get_named_statements(&self) -> bool {
+        self.config.get_named_statements()
+    }
+
+    /// Sets the name of the connection service file entry that `apply_env` takes defaults from.
+    pub fn service(&mut self, service: &str) -> &mut Config {
+        self.config.service(service);
+        self
+    }
+
+    /// Gets the name of the connection service file entry, if one has been set with the `service` method.
+    pub fn get_service(&self) -> Option<&str> {
+        self.config.get_service()
+    }
+
+    /// Fills in settings which have not been set from the environment, in the same way as libpq.
+    ///
+    /// See `tokio_postgres::Config::apply_env` for details on the sources used and their precedence.
+    pub fn apply_env(&mut self) -> Result<&mut Config, Error> {
+        self.config.apply_env()?;
+        Ok(self)
+    }
+
     /// Opens a connection to a PostgreSQL database.
     pub fn connect<T>(&self, tls: T) -> Result<Client, Error>
     where
@@ -43,6 +43,18 @@ pub trait GenericClient: private::Sealed {
         I: IntoIterator<Item = &'a dyn ToSql>,
         I::IntoIter: ExactSizeIterator;
 
+    /// Like `Client::query_typed`.
+    fn query_typed(
+        &mut self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error>;
+
+    /// Like `Client::query_typed_raw`.
+    fn query_typed_raw<'a, I>(&mut self, query: &str, params: I) -> Result<RowIter<'_>, Error>
+    where
+        I: IntoIterator<Item = (&'a dyn ToSql, Type)>;
+
     /// Like `Client::prepare`.
     fn prepare(&mut self, query: &str) -> Result<Statement, Error>;
 
@@ -113,6 +125,21 @@ impl GenericClient for Client {
         self.query_raw(query, params)
     }
 
+    fn query_typed(
+        &mut self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error> {
+        self.query_typed(query, params)
+    }
+
+    fn query_typed_raw<'a, I>(&mut self, query: &str, params: I) -> Result<RowIter<'_>, Error>
+    where
+        I: IntoIterator<Item = (&'a dyn ToSql, Type)>,
+    {
+        self.query_typed_raw(query, params)
+    }
+
     fn prepare(&mut self, query: &str) -> Result<Statement, Error> {
         self.prepare(query)
     }
@@ -192,6 +219,21 @@ impl GenericClient for Transaction<'_> {
         self.query_raw(query, params)
     }
 
+    fn query_typed(
+        &mut self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error> {
+        self.query_typed(query, params)
+    }
+
+    fn query_typed_raw<'a, I>(&mut self, query: &str, params: I) -> Result<RowIter<'_>, Error>
+    where
+        I: IntoIterator<Item = (&'a dyn ToSql, Type)>,
+    {
+        self.query_typed_raw(query, params)
+    }
+
     fn prepare(&mut self, query: &str) -> Result<Statement, Error> {
         self.prepare(query)
     }
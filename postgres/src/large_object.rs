@@ -0,0 +1,136 @@
+//! Support for PostgreSQL large objects.
+//!
+//! Large objects are created, opened and removed through a `Transaction`. An open object implements `Read`, `Write`
+//! and `Seek`.
+
+use crate::connection::ConnectionRef;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite};
+use tokio_postgres::types::Oid;
+use tokio_postgres::Error;
+
+#[doc(inline)]
+pub use tokio_postgres::large_object::Mode;
+
+/// An open large object.
+///
+/// Reads and writes are sent to the server in chunks of at most 64 KiB, one request at a time.
+pub struct LargeObject<'a> {
+    connection: ConnectionRef<'a>,
+    object: tokio_postgres::large_object::LargeObject<'a>,
+}
+
+impl<'a> LargeObject<'a> {
+    pub(crate) fn new(
+        connection: ConnectionRef<'a>,
+        object: tokio_postgres::large_object::LargeObject<'a>,
+    ) -> LargeObject<'a> {
+        LargeObject { connection, object }
+    }
+
+    /// Returns the OID of the object.
+    pub fn oid(&self) -> Oid {
+        self.object.oid()
+    }
+
+    /// Truncates or extends the object to the given length.
+    ///
+    /// The object's position is unchanged.
+    pub fn truncate(&mut self, len: u64) -> Result<(), Error> {
+        self.connection.block_on(self.object.truncate(len))
+    }
+
+    /// Closes the object.
+    ///
+    /// Objects are closed automatically when their transaction ends, but this can be used to release them earlier.
+    pub fn close(mut self) -> Result<(), Error> {
+        self.connection.block_on(self.object.close())
+    }
+
+    fn poll_io<F, T>(&mut self, mut f: F) -> io::Result<T>
+    where
+        F: FnMut(
+            Pin<&mut tokio_postgres::large_object::LargeObject<'a>>,
+            &mut Context<'_>,
+        ) -> Poll<io::Result<T>>,
+    {
+        let object = &mut self.object;
+        match self
+            .connection
+            .poll_block_on(|cx, _, _| f(Pin::new(object), cx).map(Ok))
+        {
+            Ok(r) => r,
+            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+        }
+    }
+}
+
+impl Read for LargeObject<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.poll_io(|object, cx| object.poll_read(cx, buf))
+    }
+}
+
+impl Write for LargeObject<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.poll_io(|object, cx| object.poll_write(cx, buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.poll_io(|object, cx| object.poll_flush(cx))
+    }
+}
+
+impl Seek for LargeObject<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.poll_io(|object, cx| object.start_seek(cx, pos))?;
+        self.poll_io(|object, cx| object.poll_complete(cx))
+    }
+}
+
+// Adapts a blocking reader for `import_large_object`.
+pub(crate) struct SyncReader<R>(pub R);
+
+// the reader is never pinned
+impl<R> Unpin for SyncReader<R> {}
+
+impl<R> AsyncRead for SyncReader<R>
+where
+    R: Read,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.0.read(buf))
+    }
+}
+
+// Adapts a blocking writer for `export_large_object`.
+pub(crate) struct SyncWriter<W>(pub W);
+
+impl<W> Unpin for SyncWriter<W> {}
+
+impl<W> AsyncWrite for SyncWriter<W>
+where
+    W: Write,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.0.write(buf))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.0.flush())
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
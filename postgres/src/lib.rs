@@ -44,8 +44,8 @@
 //!
 //! TLS support is implemented via external libraries. `Client::connect` and `Config::connect` take a TLS implementation
 //! as an argument. The `NoTls` type in this crate can be used when TLS is not required. Otherwise, the
-//! `postgres-openssl` and `postgres-native-tls` crates provide implementations backed by the `openssl` and `native-tls`
-//! crates, respectively.
+//! `postgres-openssl`, `postgres-native-tls` and `postgres-rustls` crates provide implementations backed by the
+//! `openssl`, `native-tls` and `rustls` crates, respectively.
 //!
 //! # Features
 //!
@@ -53,11 +53,13 @@
 //!
 //! | Feature | Description | Extra dependencies | Default |
 //! | ------- | ----------- | ------------------ | ------- |
+//! | `with-bigdecimal-0_2` | Enable support for the `bigdecimal` crate. | [bigdecimal](https://crates.io/crates/bigdecimal) 0.2 | no |
 //! | `with-bit-vec-0_6` | Enable support for the `bit-vec` crate. | [bit-vec](https://crates.io/crates/bit-vec) 0.6 | no |
 //! | `with-chrono-0_4` | Enable support for the `chrono` crate. | [chrono](https://crates.io/crates/chrono) 0.4 | no |
 //! | `with-eui48-0_4` | Enable support for the `eui48` crate. | [eui48](https://crates.io/crates/eui48) 0.4 | no |
 //! | `with-geo-types-0_4` | Enable support for the 0.4 version of the `geo-types` crate. | [geo-types](https://crates.io/crates/geo-types/0.4.0) 0.4 | no |
 //! | `with-geo-types-0_5` | Enable support for the 0.5 version of the `geo-types` crate. | [geo-types](https://crates.io/crates/geo-types/0.5.0) 0.5 | no |
+//! | `with-rust_decimal-1` | Enable support for the `rust_decimal` crate. | [rust_decimal](https://crates.io/crates/rust_decimal) 1.13 | no |
 //! | `with-serde_json-1` | Enable support for the `serde_json` crate. | [serde_json](https://crates.io/crates/serde_json) 1.0 | no |
 //! | `with-uuid-0_8` | Enable support for the `uuid` crate. | [uuid](https://crates.io/crates/uuid) 0.8 | no |
 //! | `with-time-0_2` | Enable support for the `time` crate. | [time](https://crates.io/crates/time) 0.2 | no |
@@ -96,6 +98,7 @@ mod connection;
 mod copy_in_writer;
 mod copy_out_reader;
 mod generic_client;
+pub mod large_object;
 mod lazy_pin;
 pub mod notifications;
 mod row_iter;
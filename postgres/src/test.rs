@@ -1,12 +1,13 @@
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::thread;
 use std::time::Duration;
 use tokio_postgres::error::SqlState;
-use tokio_postgres::types::Type;
+use tokio_postgres::types::{Format, Type};
 use tokio_postgres::NoTls;
 
 use super::*;
 use crate::binary_copy::{BinaryCopyInWriter, BinaryCopyOutIter};
+use crate::large_object::Mode;
 use fallible_iterator::FallibleIterator;
 
 #[test]
@@ -39,6 +40,55 @@ fn query_unprepared() {
     assert_eq!(rows[0].get::<_, &str>(0), "hello");
 }
 
+#[test]
+fn call_function() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    let oid = client
+        .query_one("SELECT 'md5(text)'::regprocedure::oid", &[])
+        .unwrap()
+        .get::<_, u32>(0);
+
+    let mut transaction = client.transaction().unwrap();
+    let value = transaction
+        .call_function(oid, &[Some(b"hello")], Format::Text)
+        .unwrap();
+    assert_eq!(
+        value.as_deref(),
+        Some(&b"5d41402abc4b2a76b9719d911017c592"[..])
+    );
+}
+
+#[test]
+fn large_object() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+    let mut transaction = client.transaction().unwrap();
+
+    let oid = transaction.create_large_object().unwrap();
+    let mut object = transaction.open_large_object(oid, Mode::ReadWrite).unwrap();
+    object.write_all(b"hello world").unwrap();
+    object.seek(SeekFrom::Start(6)).unwrap();
+    let mut buf = String::new();
+    object.read_to_string(&mut buf).unwrap();
+    assert_eq!(buf, "world");
+    object.truncate(5).unwrap();
+    object.close().unwrap();
+
+    let mut exported = vec![];
+    transaction.export_large_object(oid, &mut exported).unwrap();
+    assert_eq!(exported, b"hello");
+
+    let oid2 = transaction.import_large_object(&b"imported"[..]).unwrap();
+    let mut object = transaction.open_large_object(oid2, Mode::Read).unwrap();
+    let mut buf = vec![];
+    object.read_to_end(&mut buf).unwrap();
+    assert_eq!(buf, b"imported");
+    drop(object);
+
+    transaction.unlink_large_object(oid).unwrap();
+    transaction.unlink_large_object(oid2).unwrap();
+}
+
 #[test]
 fn transaction_commit() {
     let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
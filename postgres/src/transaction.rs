@@ -1,6 +1,9 @@
 use crate::connection::ConnectionRef;
+use crate::large_object::{LargeObject, Mode, SyncReader, SyncWriter};
 use crate::{CancelToken, CopyInWriter, CopyOutReader, Portal, RowIter, Statement, ToStatement};
-use tokio_postgres::types::{ToSql, Type};
+use bytes::Bytes;
+use std::io::{Read, Write};
+use tokio_postgres::types::{Format, Oid, ToSql, Type};
 use tokio_postgres::{Error, Row, SimpleQueryMessage};
 
 /// A representation of a PostgreSQL database transaction.
@@ -114,6 +117,34 @@ impl<'a> Transaction<'a> {
         Ok(RowIter::new(self.connection.as_ref(), stream))
     }
 
+    /// Like `Client::query_typed`.
+    pub fn query_typed(
+        &mut self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error> {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .query_typed(query, params),
+        )
+    }
+
+    /// Like `Client::query_typed_raw`.
+    pub fn query_typed_raw<'b, I>(&mut self, query: &str, params: I) -> Result<RowIter<'_>, Error>
+    where
+        I: IntoIterator<Item = (&'b dyn ToSql, Type)>,
+    {
+        let stream = self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .query_typed_raw(query, params),
+        )?;
+        Ok(RowIter::new(self.connection.as_ref(), stream))
+    }
+
     /// Binds parameters to a statement, creating a "portal".
     ///
     /// Portals can be used with the `query_portal` method to page through the results of a query without being forced
@@ -194,6 +225,71 @@ impl<'a> Transaction<'a> {
             .block_on(self.transaction.as_ref().unwrap().batch_execute(query))
     }
 
+    /// Like `Client::call_function`.
+    pub fn call_function(
+        &mut self,
+        oid: Oid,
+        args: &[Option<&[u8]>],
+        result_format: Format,
+    ) -> Result<Option<Bytes>, Error> {
+        self.connection
+            .block_on(
+                self.transaction
+                    .as_ref()
+                    .unwrap()
+                    .call_function(oid, args, result_format),
+            )
+    }
+
+    /// Creates a new, empty large object, returning its OID.
+    pub fn create_large_object(&mut self) -> Result<Oid, Error> {
+        self.connection
+            .block_on(self.transaction.as_ref().unwrap().create_large_object())
+    }
+
+    /// Opens a large object.
+    pub fn open_large_object(&mut self, oid: Oid, mode: Mode) -> Result<LargeObject<'_>, Error> {
+        let object = self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .open_large_object(oid, mode),
+        )?;
+        Ok(LargeObject::new(self.connection.as_ref(), object))
+    }
+
+    /// Deletes a large object.
+    pub fn unlink_large_object(&mut self, oid: Oid) -> Result<(), Error> {
+        self.connection
+            .block_on(self.transaction.as_ref().unwrap().unlink_large_object(oid))
+    }
+
+    /// Creates a new large object with the contents of a reader, returning its OID.
+    pub fn import_large_object<R>(&mut self, reader: R) -> Result<Oid, Error>
+    where
+        R: Read,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .import_large_object(SyncReader(reader)),
+        )
+    }
+
+    /// Writes the contents of a large object to a writer.
+    pub fn export_large_object<W>(&mut self, oid: Oid, writer: W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .export_large_object(oid, SyncWriter(writer)),
+        )
+    }
+
     /// Like `Client::cancel_token`.
     pub fn cancel_token(&self) -> CancelToken {
         CancelToken::new(self.transaction.as_ref().unwrap().cancel_token())
@@ -28,9 +28,13 @@ where
 
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
 
-    match responses.next().await? {
-        Message::BindComplete => {}
-        _ => return Err(Error::unexpected_message()),
+    match query::bind_response(&mut responses, &statement).await {
+        Ok(Message::BindComplete) => {}
+        Ok(_) => return Err(Error::unexpected_message()),
+        Err(e) => {
+            query::invalidate_on_plan_change(client, &statement, &e);
+            return Err(e);
+        }
     }
 
     Ok(Portal::new(client, name, statement))
@@ -1,20 +1,26 @@
 use crate::codec::BackendMessages;
-use crate::config::{Host, SslMode};
+use crate::config::{Host, SslMode, SslNegotiation};
 use crate::connection::{Request, RequestMessages};
 use crate::copy_out::CopyOutStream;
 use crate::query::RowStream;
+use crate::replication::{
+    self, BaseBackupOptions, BaseBackupStream, CreateReplicationSlotResponse, IdentifySystem,
+    LogicalReplicationStream, PhysicalReplicationStream, SnapshotAction, TimelineHistory,
+};
 use crate::simple_query::SimpleQueryStream;
+use crate::statement_cache::StatementCache;
 #[cfg(feature = "runtime")]
 use crate::tls::MakeTlsConnect;
 use crate::tls::TlsConnect;
-use crate::types::{Oid, ToSql, Type};
+use crate::types::{Format, Oid, PgLsn, ToSql, Type};
 #[cfg(feature = "runtime")]
 use crate::Socket;
 use crate::{
-    copy_in, copy_out, prepare, query, simple_query, slice_iter, CancelToken, CopyInSink, Error,
-    Row, SimpleQueryMessage, Statement, ToStatement, Transaction, TransactionBuilder,
+    copy_both, copy_in, copy_out, function_call, prepare, query, simple_query, slice_iter,
+    CancelToken, CopyBothDuplex, CopyInSink, Error, Pipeline, Row, SimpleQueryMessage, Statement,
+    ToStatement, Transaction, TransactionBuilder,
 };
-use bytes::{Buf, BytesMut};
+use bytes::{Buf, Bytes, BytesMut};
 use fallible_iterator::FallibleIterator;
 use futures::channel::mpsc;
 use futures::{future, pin_mut, ready, StreamExt, TryStreamExt};
@@ -57,12 +63,15 @@ struct State {
     typeinfo: Option<Statement>,
     typeinfo_composite: Option<Statement>,
     typeinfo_enum: Option<Statement>,
+    typeinfo_multirange: Option<Statement>,
     types: HashMap<Oid, Type>,
+    statement_cache: StatementCache,
     buf: BytesMut,
 }
 
 pub struct InnerClient {
     sender: mpsc::UnboundedSender<Request>,
+    named_statements: bool,
     state: Mutex<State>,
 }
 
@@ -80,6 +89,10 @@ impl InnerClient {
         })
     }
 
+    pub fn named_statements(&self) -> bool {
+        self.named_statements
+    }
+
     pub fn typeinfo(&self) -> Option<Statement> {
         self.state.lock().typeinfo.clone()
     }
@@ -104,6 +117,14 @@ impl InnerClient {
         self.state.lock().typeinfo_enum = Some(statement.clone());
     }
 
+    pub fn typeinfo_multirange(&self) -> Option<Statement> {
+        self.state.lock().typeinfo_multirange.clone()
+    }
+
+    pub fn set_typeinfo_multirange(&self, statement: &Statement) {
+        self.state.lock().typeinfo_multirange = Some(statement.clone());
+    }
+
     pub fn type_(&self, oid: Oid) -> Option<Type> {
         self.state.lock().types.get(&oid).cloned()
     }
@@ -112,6 +133,48 @@ impl InnerClient {
         self.state.lock().types.insert(oid, type_.clone());
     }
 
+    pub fn cached_statement(&self, query: &str, types: &[Type]) -> Option<Statement> {
+        self.state.lock().statement_cache.get(query, types)
+    }
+
+    // The statements removed from the cache by these methods are dropped after the state lock is released, since
+    // closing a statement requires the lock.
+    pub fn set_cached_statement(&self, query: &str, types: &[Type], statement: &Statement) {
+        let evicted = self
+            .state
+            .lock()
+            .statement_cache
+            .insert(query, types, statement);
+        drop(evicted);
+    }
+
+    pub fn invalidate_cached_statement(&self, statement: &Statement) {
+        let removed = self.state.lock().statement_cache.remove(statement);
+        drop(removed);
+    }
+
+    pub fn cached_queries(&self) -> Vec<(String, Vec<Type>)> {
+        self.state.lock().statement_cache.queries()
+    }
+
+    pub fn clear_statement_cache(&self) {
+        let removed = self.state.lock().statement_cache.clear();
+        drop(removed);
+    }
+
+    pub fn clear_type_cache(&self) {
+        let mut state = self.state.lock();
+        let removed = (
+            state.typeinfo.take(),
+            state.typeinfo_composite.take(),
+            state.typeinfo_enum.take(),
+            state.typeinfo_multirange.take(),
+        );
+        state.types.clear();
+        drop(state);
+        drop(removed);
+    }
+
     pub fn with_buf<F, R>(&self, f: F) -> R
     where
         F: FnOnce(&mut BytesMut) -> R,
@@ -141,6 +204,7 @@ pub struct Client {
     #[cfg(feature = "runtime")]
     socket_config: Option<SocketConfig>,
     ssl_mode: SslMode,
+    ssl_negotiation: SslNegotiation,
     process_id: i32,
     secret_key: i32,
 }
@@ -149,23 +213,30 @@ impl Client {
     pub(crate) fn new(
         sender: mpsc::UnboundedSender<Request>,
         ssl_mode: SslMode,
+        ssl_negotiation: SslNegotiation,
         process_id: i32,
         secret_key: i32,
+        statement_cache_capacity: usize,
+        named_statements: bool,
     ) -> Client {
         Client {
             inner: Arc::new(InnerClient {
                 sender,
+                named_statements,
                 state: Mutex::new(State {
                     typeinfo: None,
                     typeinfo_composite: None,
                     typeinfo_enum: None,
+                    typeinfo_multirange: None,
                     types: HashMap::new(),
+                    statement_cache: StatementCache::new(statement_cache_capacity),
                     buf: BytesMut::new(),
                 }),
             }),
             #[cfg(feature = "runtime")]
             socket_config: None,
             ssl_mode,
+            ssl_negotiation,
             process_id,
             secret_key,
         }
@@ -184,6 +255,8 @@ impl Client {
     ///
     /// Prepared statements can be executed repeatedly, and may contain query parameters (indicated by `$1`, `$2`, etc),
     /// which are set when executed. Prepared statements can only be used with the connection that created them.
+    ///
+    /// Returns an error if named statements have been disabled with `Config::named_statements`.
     pub async fn prepare(&self, query: &str) -> Result<Statement, Error> {
         self.prepare_typed(query, &[]).await
     }
@@ -197,7 +270,11 @@ impl Client {
         query: &str,
         parameter_types: &[Type],
     ) -> Result<Statement, Error> {
-        prepare::prepare(&self.inner, query, parameter_types).await
+        if !self.inner.named_statements() {
+            return Err(Error::config("named statements are disabled".into()));
+        }
+
+        prepare::prepare_cached(&self.inner, query, parameter_types).await
     }
 
     /// Executes a statement, returning a vector of the resulting rows.
@@ -352,6 +429,38 @@ impl Client {
         query::query(&self.inner, statement, params).await
     }
 
+    /// Executes a statement with explicitly typed parameters, returning a vector of the resulting rows.
+    ///
+    /// Unlike `query`, the query string is not prepared separately: it is parsed into the unnamed statement, bound, and
+    /// executed in a single round trip. The types of the parameters are taken from `params` rather than inferred by the
+    /// server, so this is useful for queries which are executed once, or which are generated dynamically.
+    ///
+    /// A statement may contain parameters, specified by `$n`, where `n` is the index of the parameter of the list
+    /// provided, 1-indexed.
+    pub async fn query_typed(
+        &self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error> {
+        self.query_typed_raw(query, params.iter().map(|(v, t)| (*v as _, t.clone())))
+            .await?
+            .try_collect()
+            .await
+    }
+
+    /// The maximally flexible version of [`query_typed`].
+    ///
+    /// It takes an iterator of parameters and their types rather than a slice, and returns a stream of rows rather than
+    /// collecting them into an array.
+    ///
+    /// [`query_typed`]: #method.query_typed
+    pub async fn query_typed_raw<'a, I>(&self, query: &str, params: I) -> Result<RowStream, Error>
+    where
+        I: IntoIterator<Item = (&'a dyn ToSql, Type)>,
+    {
+        query::query_typed(&self.inner, query, params).await
+    }
+
     /// Executes a statement, returning the number of rows modified.
     ///
     /// A statement may contain parameters, specified by `$n`, where `n` is the index of the parameter of the list
@@ -401,6 +510,15 @@ impl Client {
         query::execute(self.inner(), statement, params).await
     }
 
+    /// Returns a builder for a pipeline of statements.
+    ///
+    /// The statements queued in the pipeline are sent to the server in a single write, and are synchronized with either
+    /// a single `Sync` or one per statement. This avoids a round trip per statement when executing many independent
+    /// statements.
+    pub fn pipeline(&self) -> Pipeline<'_> {
+        Pipeline::new(self)
+    }
+
     /// Executes a `COPY FROM STDIN` statement, returning a sink used to write the copy data.
     ///
     /// PostgreSQL does not support parameters in `COPY` statements, so this method does not take any. The copy *must*
@@ -433,6 +551,19 @@ impl Client {
         copy_out::copy_out(self.inner(), statement).await
     }
 
+    /// Executes a statement which starts a `COPY` in both directions, returning a duplex sink and stream used to
+    /// exchange the copy data with the server.
+    ///
+    /// The statement is sent using the simple query protocol. This is used by the streaming replication protocol, where
+    /// `START_REPLICATION` switches the connection into this mode. Closing the sink ends the client's half of the copy,
+    /// and the stream ends once the server has ended its half. Dropping the duplex ends the copy.
+    pub async fn copy_both<T>(&self, query: &str) -> Result<CopyBothDuplex<T>, Error>
+    where
+        T: Buf + 'static + Send,
+    {
+        copy_both::copy_both(self.inner(), query).await
+    }
+
     /// Executes a sequence of SQL statements using the simple query protocol, returning the resulting rows.
     ///
     /// Statements should be separated by semicolons. If an error occurs, execution of the sequence will stop at that
@@ -468,6 +599,144 @@ impl Client {
         simple_query::batch_execute(self.inner(), query).await
     }
 
+    /// Calls a function through the "fast-path" interface, bypassing the SQL parser.
+    ///
+    /// The function is identified by its OID, and its arguments must already be encoded in the binary format, with
+    /// `None` representing `NULL`. The result is returned in the requested format, or `None` if it is `NULL`.
+    pub async fn call_function(
+        &self,
+        oid: Oid,
+        args: &[Option<&[u8]>],
+        result_format: Format,
+    ) -> Result<Option<Bytes>, Error> {
+        function_call::call_function(self.inner(), oid, args, result_format).await
+    }
+
+    /// Requests identifying information about the server with the `IDENTIFY_SYSTEM` replication command.
+    ///
+    /// The connection must have been opened in replication mode (see `Config::replication_mode`).
+    pub async fn identify_system(&self) -> Result<IdentifySystem, Error> {
+        replication::identify_system(self.inner()).await
+    }
+
+    /// Requests the history file of a timeline with the `TIMELINE_HISTORY` replication command.
+    ///
+    /// The connection must have been opened in replication mode (see `Config::replication_mode`).
+    pub async fn timeline_history(&self, timeline: u32) -> Result<TimelineHistory, Error> {
+        replication::timeline_history(self.inner(), timeline).await
+    }
+
+    /// Creates a physical replication slot.
+    ///
+    /// A temporary slot is dropped when the connection is closed. If `reserve_wal` is true, the slot reserves WAL
+    /// immediately rather than when a replication client first connects to it.
+    ///
+    /// The connection must have been opened in replication mode (see `Config::replication_mode`).
+    pub async fn create_physical_replication_slot(
+        &self,
+        slot_name: &str,
+        temporary: bool,
+        reserve_wal: bool,
+    ) -> Result<CreateReplicationSlotResponse, Error> {
+        replication::create_physical_replication_slot(
+            self.inner(),
+            slot_name,
+            temporary,
+            reserve_wal,
+        )
+        .await
+    }
+
+    /// Creates a logical replication slot using the specified output plugin.
+    ///
+    /// A temporary slot is dropped when the connection is closed. `snapshot` controls what happens to the snapshot
+    /// created along with the slot; the server's default is to export it.
+    ///
+    /// The connection must have been opened in logical replication mode (see `Config::replication_mode`).
+    pub async fn create_logical_replication_slot(
+        &self,
+        slot_name: &str,
+        temporary: bool,
+        plugin: &str,
+        snapshot: Option<SnapshotAction>,
+    ) -> Result<CreateReplicationSlotResponse, Error> {
+        replication::create_logical_replication_slot(
+            self.inner(),
+            slot_name,
+            temporary,
+            plugin,
+            snapshot,
+        )
+        .await
+    }
+
+    /// Drops a replication slot.
+    ///
+    /// If the slot is in use by another connection, the command fails unless `wait` is true, in which case it blocks
+    /// until the slot becomes inactive.
+    ///
+    /// The connection must have been opened in replication mode (see `Config::replication_mode`).
+    pub async fn drop_replication_slot(&self, slot_name: &str, wait: bool) -> Result<(), Error> {
+        replication::drop_replication_slot(self.inner(), slot_name, wait).await
+    }
+
+    /// Starts streaming WAL from the server for physical replication.
+    ///
+    /// Streaming starts at `start_lsn` on the specified timeline, or the server's current timeline if none is
+    /// specified. If a slot is specified, the server uses it to retain the WAL the client has not yet flushed.
+    ///
+    /// The connection must have been opened in physical replication mode (see `Config::replication_mode`).
+    pub async fn start_physical_replication(
+        &self,
+        slot_name: Option<&str>,
+        start_lsn: PgLsn,
+        timeline: Option<u32>,
+    ) -> Result<PhysicalReplicationStream, Error> {
+        replication::start_physical_replication(self.inner(), slot_name, start_lsn, timeline).await
+    }
+
+    /// Starts a base backup of the database cluster, returning a stream of its contents.
+    ///
+    /// The backup is sent as a tar archive for each tablespace. The connection must have been opened in physical
+    /// replication mode (see `Config::replication_mode`).
+    pub async fn base_backup(
+        &self,
+        options: &BaseBackupOptions,
+    ) -> Result<BaseBackupStream, Error> {
+        replication::base_backup(self.inner(), options).await
+    }
+
+    /// Starts streaming logical replication changes from a replication slot.
+    ///
+    /// The connection must have been opened in logical replication mode (see `Config::replication_mode`), and the
+    /// slot must use the `pgoutput` output plugin. The `options` are passed through to the plugin; `pgoutput`
+    /// requires `proto_version` and `publication_names` to be set. Streaming starts at `start_lsn`, or at the slot's
+    /// confirmed position if that is later.
+    pub async fn start_logical_replication(
+        &self,
+        slot_name: &str,
+        start_lsn: PgLsn,
+        options: &[(&str, &str)],
+    ) -> Result<LogicalReplicationStream, Error> {
+        replication::start_logical_replication(self.inner(), slot_name, start_lsn, options).await
+    }
+
+    /// Clears the client's cache of prepared statements.
+    ///
+    /// Statements which are still in use elsewhere remain valid. See `Config::statement_cache_capacity`.
+    pub fn clear_statement_cache(&self) {
+        self.inner.clear_statement_cache();
+    }
+
+    /// Clears the client's cache of type information.
+    ///
+    /// The definitions of user-defined types are loaded from the database the first time they're used, and cached for
+    /// the lifetime of the client. If those definitions change, or the statements the client uses to look them up are
+    /// deallocated by `DISCARD ALL`, this method can be used to have them loaded again.
+    pub fn clear_type_cache(&self) {
+        self.inner.clear_type_cache();
+    }
+
     /// Begins a new database transaction.
     ///
     /// The transaction will roll back by default - use the `commit` method to commit it.
@@ -492,6 +761,7 @@ impl Client {
             #[cfg(feature = "runtime")]
             socket_config: self.socket_config.clone(),
             ssl_mode: self.ssl_mode,
+            ssl_negotiation: self.ssl_negotiation,
             process_id: self.process_id,
             secret_key: self.secret_key,
         }
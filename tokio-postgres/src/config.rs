@@ -8,16 +8,19 @@ use crate::tls::MakeTlsConnect;
 use crate::tls::TlsConnect;
 #[cfg(feature = "runtime")]
 use crate::Socket;
-use crate::{Client, Connection, Error};
+use crate::{password_file, service_file, Client, Connection, Error};
+use async_trait::async_trait;
 use std::borrow::Cow;
+use std::env;
 #[cfg(unix)]
 use std::ffi::OsStr;
+use std::future::Future;
 #[cfg(unix)]
 use std::os::unix::ffi::OsStrExt;
-#[cfg(unix)]
 use std::path::{Path, PathBuf};
 use std::str;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 use std::{error, fmt, iter, mem};
 use tokio::io::{AsyncRead, AsyncWrite};
@@ -30,6 +33,24 @@ pub enum TargetSessionAttrs {
     Any,
     /// The session must allow writes.
     ReadWrite,
+    /// The session must not allow writes.
+    ReadOnly,
+    /// The server must not be in hot standby mode.
+    Primary,
+    /// The server must be in hot standby mode.
+    Standby,
+    /// Standby servers are preferred, but a connection to any server is made if none are available.
+    PreferStandby,
+}
+
+/// Load balancing configuration.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum LoadBalanceHosts {
+    /// Try the hosts in the order they were specified.
+    Disable,
+    /// Try the hosts in a random order.
+    Random,
 }
 
 /// TLS configuration.
@@ -42,6 +63,24 @@ pub enum SslMode {
     Prefer,
     /// Require the use of TLS.
     Require,
+    /// Require the use of TLS, and verify that the server's certificate is signed by a trusted certificate authority.
+    VerifyCa,
+    /// Require the use of TLS, and verify both the server's certificate chain and that the certificate matches the
+    /// host being connected to.
+    VerifyFull,
+}
+
+/// TLS negotiation configuration.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SslNegotiation {
+    /// Request TLS with an `SSLRequest` message, and start the handshake once the server has agreed to it.
+    Postgres,
+    /// Start the TLS handshake immediately after connecting, using the `postgresql` ALPN protocol.
+    ///
+    /// This saves a round trip, but is only supported by PostgreSQL 17 and newer. The connection fails if the server
+    /// doesn't select the `postgresql` protocol, or if the TLS implementation can't report the protocol it selected.
+    Direct,
 }
 
 /// Channel binding configuration.
@@ -56,6 +95,114 @@ pub enum ChannelBinding {
     Require,
 }
 
+/// An authentication method which the server may request.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum AuthMethod {
+    /// No authentication, as with the `trust` method.
+    None,
+    /// A cleartext password.
+    Password,
+    /// An MD5-hashed password.
+    Md5,
+    /// GSSAPI authentication.
+    Gss,
+    /// SSPI authentication.
+    Sspi,
+    /// SCRAM-SHA-256 authentication, with or without channel binding.
+    ScramSha256,
+}
+
+impl AuthMethod {
+    const ALL: [AuthMethod; 6] = [
+        AuthMethod::None,
+        AuthMethod::Password,
+        AuthMethod::Md5,
+        AuthMethod::Gss,
+        AuthMethod::Sspi,
+        AuthMethod::ScramSha256,
+    ];
+
+    fn parse(s: &str) -> Option<AuthMethod> {
+        match s {
+            "none" => Some(AuthMethod::None),
+            "password" => Some(AuthMethod::Password),
+            "md5" => Some(AuthMethod::Md5),
+            "gss" => Some(AuthMethod::Gss),
+            "sspi" => Some(AuthMethod::Sspi),
+            "scram-sha-256" => Some(AuthMethod::ScramSha256),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AuthMethod {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            AuthMethod::None => "none",
+            AuthMethod::Password => "password",
+            AuthMethod::Md5 => "md5",
+            AuthMethod::Gss => "gss",
+            AuthMethod::Sspi => "sspi",
+            AuthMethod::ScramSha256 => "scram-sha-256",
+        };
+        fmt.write_str(s)
+    }
+}
+
+/// Replication mode configuration.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ReplicationMode {
+    /// Physical replication.
+    Physical,
+    /// Logical replication.
+    Logical,
+}
+
+/// A source of passwords used to authenticate new connections.
+///
+/// Unlike a password set with `Config::password`, a provider is asked for a password each time a connection
+/// authenticates, which allows short-lived credentials such as cloud IAM tokens to be used. It is implemented for
+/// closures returning a future.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_postgres::Config;
+///
+/// # async fn fetch_token() -> Result<String, Box<dyn std::error::Error + Sync + Send>> { unimplemented!() }
+/// let mut config = "host=db.example.com user=app".parse::<Config>().unwrap();
+/// config.password_provider(|| async { Ok(fetch_token().await?.into_bytes()) });
+/// ```
+#[async_trait]
+pub trait PasswordProvider: Sync + Send {
+    /// Returns the password to authenticate with.
+    async fn password(&self) -> Result<Vec<u8>, Box<dyn error::Error + Sync + Send>>;
+}
+
+#[async_trait]
+impl<F, T> PasswordProvider for F
+where
+    F: Fn() -> T + Sync + Send,
+    T: Future<Output = Result<Vec<u8>, Box<dyn error::Error + Sync + Send>>> + Send,
+{
+    async fn password(&self) -> Result<Vec<u8>, Box<dyn error::Error + Sync + Send>> {
+        self().await
+    }
+}
+
+// Providers are compared by identity so that `Config` can remain `PartialEq`.
+#[derive(Clone)]
+pub(crate) struct SharedPasswordProvider(pub(crate) Arc<dyn PasswordProvider>);
+
+impl PartialEq for SharedPasswordProvider {
+    fn eq(&self, other: &SharedPasswordProvider) -> bool {
+        &*self.0 as *const dyn PasswordProvider as *const u8
+            == &*other.0 as *const dyn PasswordProvider as *const u8
+    }
+}
+
 /// A host specification.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Host {
@@ -85,7 +232,20 @@ pub enum Host {
 /// * `options` - Command line options used to configure the server.
 /// * `application_name` - Sets the `application_name` parameter on the server.
 /// * `sslmode` - Controls usage of TLS. If set to `disable`, TLS will not be used. If set to `prefer`, TLS will be used
-///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. Defaults to `prefer`.
+///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. If set to `verify-ca`
+///     or `verify-full`, TLS will be forced to be used and the TLS connector is expected to verify the server's
+///     certificate chain, and for `verify-full` the hostname as well. Defaults to `prefer`.
+/// * `sslnegotiation` - Controls how TLS is negotiated with the server. If set to `postgres`, the client asks the server
+///     whether it supports TLS before starting the handshake. If set to `direct`, the handshake is started immediately
+///     using the `postgresql` ALPN protocol, which requires PostgreSQL 17 or newer and an `sslmode` of `require`,
+///     `verify-ca` or `verify-full`. Defaults to `postgres`.
+/// * `sslrootcert` - The path to a file containing the certificate authorities trusted to sign the server's
+///     certificate.
+/// * `sslcert` - The path to a file containing the client's certificate.
+/// * `sslkey` - The path to a file containing the private key for the client's certificate.
+/// * `sslpassword` - The password used to decrypt the client's private key.
+/// * `sslcrl` - The path to a file containing a certificate revocation list checked when verifying the server's
+///     certificate.
 /// * `host` - The host to connect to. On Unix platforms, if the host starts with a `/` character it is treated as the
 ///     path to the directory containing Unix domain sockets. Otherwise, it is treated as a hostname. Multiple hosts
 ///     can be specified, separated by commas. Each host will be tried in turn when connecting. Required if connecting
@@ -100,11 +260,30 @@ pub enum Host {
 /// * `keepalives_idle` - The number of seconds of inactivity after which a keepalive message is sent to the server.
 ///     This option is ignored when connecting with Unix sockets. Defaults to 2 hours.
 /// * `target_session_attrs` - Specifies requirements of the session. If set to `read-write`, the client will check that
-///     the `transaction_read_write` session parameter is set to `on`. This can be used to connect to the primary server
-///     in a database cluster as opposed to the secondary read-only mirrors. Defaults to `all`.
+///     the session allows writes, and if set to `read-only` that it does not. If set to `primary` or `standby`, the
+///     client will check whether the server is in hot standby mode. If set to `prefer-standby`, standby servers are
+///     tried first, and then all hosts are tried again without any requirements. This can be used to connect to the
+///     primary server in a database cluster as opposed to the secondary read-only mirrors. Defaults to `any`.
+/// * `load_balance_hosts` - Controls the order in which the client tries to connect to the available hosts. If set to
+///     `disable`, hosts are tried in the order they were specified. If set to `random`, hosts are tried in a random
+///     order. Defaults to `disable`.
+/// * `require_auth` - A comma-separated list of the authentication methods the server is allowed to request: `password`,
+///     `md5`, `gss`, `sspi`, `scram-sha-256` or `none`, where `none` allows the server to skip authentication. If each
+///     method is instead prefixed with `!`, the server is allowed to request any method except for those listed. The
+///     connection fails if the server requests any other method. Defaults to allowing all methods.
 /// * `channel_binding` - Controls usage of channel binding in the authentication process. If set to `disable`, channel
 ///     binding will not be used. If set to `prefer`, channel binding will be used if available, but not used otherwise.
 ///     If set to `require`, the authentication process will fail if channel binding is not used. Defaults to `prefer`.
+/// * `replication` - Puts the connection into streaming replication mode. If set to `true`, `on`, `yes` or `1`, the
+///     connection will be used for physical replication. If set to `database`, the connection will be used for logical
+///     replication from the database named by `dbname`. Defaults to `false`.
+/// * `statement_cache_capacity` - The maximum number of prepared statements the client caches for reuse, keyed by
+///     query text and parameter types. A value of 0 disables the cache. Defaults to 0.
+/// * `named_statements` - Controls the use of named prepared statements. If set to `false`, `off`, `no` or `0`, queries
+///     are only ever prepared as the unnamed statement, which is required when connecting through a pooler such as
+///     PgBouncer in transaction pooling mode. Defaults to `true`.
+/// * `service` - The name of an entry in the connection service file to take defaults from. This is only used by the
+///     `apply_env` method.
 ///
 /// ## Examples
 ///
@@ -144,21 +323,36 @@ pub enum Host {
 /// ```not_rust
 /// postgresql:///mydb?user=user&host=/var/lib/postgresql
 /// ```
-#[derive(PartialEq, Clone)]
+#[derive(Clone)]
 pub struct Config {
     pub(crate) user: Option<String>,
     pub(crate) password: Option<Vec<u8>>,
+    pub(crate) password_provider: Option<SharedPasswordProvider>,
     pub(crate) dbname: Option<String>,
     pub(crate) options: Option<String>,
     pub(crate) application_name: Option<String>,
     pub(crate) ssl_mode: SslMode,
+    pub(crate) ssl_negotiation: SslNegotiation,
+    pub(crate) ssl_root_cert: Option<PathBuf>,
+    pub(crate) ssl_cert: Option<PathBuf>,
+    pub(crate) ssl_key: Option<PathBuf>,
+    pub(crate) ssl_password: Option<Vec<u8>>,
+    pub(crate) ssl_crl: Option<PathBuf>,
     pub(crate) host: Vec<Host>,
     pub(crate) port: Vec<u16>,
     pub(crate) connect_timeout: Option<Duration>,
     pub(crate) keepalives: bool,
     pub(crate) keepalives_idle: Duration,
     pub(crate) target_session_attrs: TargetSessionAttrs,
+    pub(crate) load_balance_hosts: LoadBalanceHosts,
+    pub(crate) require_auth: Option<Vec<AuthMethod>>,
     pub(crate) channel_binding: ChannelBinding,
+    pub(crate) replication_mode: Option<ReplicationMode>,
+    pub(crate) statement_cache_capacity: usize,
+    pub(crate) named_statements: bool,
+    pub(crate) service: Option<String>,
+    // the settings with default values which have been set explicitly
+    explicit: u16,
 }
 
 impl Default for Config {
@@ -173,20 +367,43 @@ impl Config {
         Config {
             user: None,
             password: None,
+            password_provider: None,
             dbname: None,
             options: None,
             application_name: None,
             ssl_mode: SslMode::Prefer,
+            ssl_negotiation: SslNegotiation::Postgres,
+            ssl_root_cert: None,
+            ssl_cert: None,
+            ssl_key: None,
+            ssl_password: None,
+            ssl_crl: None,
             host: vec![],
             port: vec![],
             connect_timeout: None,
             keepalives: true,
             keepalives_idle: Duration::from_secs(2 * 60 * 60),
             target_session_attrs: TargetSessionAttrs::Any,
+            load_balance_hosts: LoadBalanceHosts::Disable,
+            require_auth: None,
             channel_binding: ChannelBinding::Prefer,
+            replication_mode: None,
+            statement_cache_capacity: 0,
+            named_statements: true,
+            service: None,
+            explicit: 0,
         }
     }
 
+    /// Creates a new configuration from the environment.
+    ///
+    /// This is equivalent to calling `apply_env` on an empty configuration.
+    pub fn from_env() -> Result<Config, Error> {
+        let mut config = Config::new();
+        config.apply_env()?;
+        Ok(config)
+    }
+
     /// Sets the user to authenticate with.
     ///
     /// Required.
@@ -216,6 +433,22 @@ impl Config {
         self.password.as_deref()
     }
 
+    /// Sets a provider which is asked for a password each time a connection authenticates.
+    ///
+    /// The provider takes precedence over a password set with the `password` method.
+    pub fn password_provider<P>(&mut self, password_provider: P) -> &mut Config
+    where
+        P: PasswordProvider + 'static,
+    {
+        self.password_provider = Some(SharedPasswordProvider(Arc::new(password_provider)));
+        self
+    }
+
+    /// Gets the password provider, if one has been set with the `password_provider` method.
+    pub fn get_password_provider(&self) -> Option<&dyn PasswordProvider> {
+        self.password_provider.as_ref().map(|p| &*p.0)
+    }
+
     /// Sets the name of the database to connect to.
     ///
     /// Defaults to the user.
@@ -259,6 +492,7 @@ impl Config {
     /// Defaults to `prefer`.
     pub fn ssl_mode(&mut self, ssl_mode: SslMode) -> &mut Config {
         self.ssl_mode = ssl_mode;
+        self.explicit |= SSL_MODE;
         self
     }
 
@@ -267,6 +501,101 @@ impl Config {
         self.ssl_mode
     }
 
+    /// Sets how TLS is negotiated with the server.
+    ///
+    /// Defaults to `postgres`.
+    pub fn ssl_negotiation(&mut self, ssl_negotiation: SslNegotiation) -> &mut Config {
+        self.ssl_negotiation = ssl_negotiation;
+        self.explicit |= SSL_NEGOTIATION;
+        self
+    }
+
+    /// Gets how TLS is negotiated with the server.
+    pub fn get_ssl_negotiation(&self) -> SslNegotiation {
+        self.ssl_negotiation
+    }
+
+    /// Sets the path to a file containing the certificate authorities trusted to sign the server's certificate.
+    ///
+    /// This is not used by `tokio-postgres` itself, but by TLS connectors built from the configuration.
+    pub fn ssl_root_cert<T>(&mut self, ssl_root_cert: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.ssl_root_cert = Some(ssl_root_cert.as_ref().to_path_buf());
+        self
+    }
+
+    /// Gets the path to the trusted certificate authorities, if one has been set with the `ssl_root_cert` method.
+    pub fn get_ssl_root_cert(&self) -> Option<&Path> {
+        self.ssl_root_cert.as_deref()
+    }
+
+    /// Sets the path to a file containing the client's certificate.
+    ///
+    /// This is not used by `tokio-postgres` itself, but by TLS connectors built from the configuration.
+    pub fn ssl_cert<T>(&mut self, ssl_cert: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.ssl_cert = Some(ssl_cert.as_ref().to_path_buf());
+        self
+    }
+
+    /// Gets the path to the client's certificate, if one has been set with the `ssl_cert` method.
+    pub fn get_ssl_cert(&self) -> Option<&Path> {
+        self.ssl_cert.as_deref()
+    }
+
+    /// Sets the path to a file containing the private key for the client's certificate.
+    ///
+    /// This is not used by `tokio-postgres` itself, but by TLS connectors built from the configuration.
+    pub fn ssl_key<T>(&mut self, ssl_key: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.ssl_key = Some(ssl_key.as_ref().to_path_buf());
+        self
+    }
+
+    /// Gets the path to the client's private key, if one has been set with the `ssl_key` method.
+    pub fn get_ssl_key(&self) -> Option<&Path> {
+        self.ssl_key.as_deref()
+    }
+
+    /// Sets the password used to decrypt the client's private key.
+    ///
+    /// This is not used by `tokio-postgres` itself, but by TLS connectors built from the configuration.
+    pub fn ssl_password<T>(&mut self, ssl_password: T) -> &mut Config
+    where
+        T: AsRef<[u8]>,
+    {
+        self.ssl_password = Some(ssl_password.as_ref().to_vec());
+        self
+    }
+
+    /// Gets the password used to decrypt the client's private key, if one has been set with the `ssl_password`
+    /// method.
+    pub fn get_ssl_password(&self) -> Option<&[u8]> {
+        self.ssl_password.as_deref()
+    }
+
+    /// Sets the path to a file containing a certificate revocation list checked when verifying the server's certificate.
+    ///
+    /// This is not used by `tokio-postgres` itself, but by TLS connectors built from the configuration.
+    pub fn ssl_crl<T>(&mut self, ssl_crl: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.ssl_crl = Some(ssl_crl.as_ref().to_path_buf());
+        self
+    }
+
+    /// Gets the path to the certificate revocation list, if one has been set with the `ssl_crl` method.
+    pub fn get_ssl_crl(&self) -> Option<&Path> {
+        self.ssl_crl.as_deref()
+    }
+
     /// Adds a host to the configuration.
     ///
     /// Multiple hosts can be specified by calling this method multiple times, and each will be tried in order. On Unix
@@ -335,6 +664,7 @@ impl Config {
     /// This is ignored for Unix domain socket connections. Defaults to `true`.
     pub fn keepalives(&mut self, keepalives: bool) -> &mut Config {
         self.keepalives = keepalives;
+        self.explicit |= KEEPALIVES;
         self
     }
 
@@ -348,6 +678,7 @@ impl Config {
     /// This is ignored for Unix domain sockets, or if the `keepalives` option is disabled. Defaults to 2 hours.
     pub fn keepalives_idle(&mut self, keepalives_idle: Duration) -> &mut Config {
         self.keepalives_idle = keepalives_idle;
+        self.explicit |= KEEPALIVES_IDLE;
         self
     }
 
@@ -366,6 +697,7 @@ impl Config {
         target_session_attrs: TargetSessionAttrs,
     ) -> &mut Config {
         self.target_session_attrs = target_session_attrs;
+        self.explicit |= TARGET_SESSION_ATTRS;
         self
     }
 
@@ -374,11 +706,43 @@ impl Config {
         self.target_session_attrs
     }
 
+    /// Sets the host load balancing behavior.
+    ///
+    /// If set to `Random`, the hosts are shuffled before each connection attempt, which spreads clients across a set of
+    /// equivalent servers such as read replicas. Defaults to `Disable`.
+    pub fn load_balance_hosts(&mut self, load_balance_hosts: LoadBalanceHosts) -> &mut Config {
+        self.load_balance_hosts = load_balance_hosts;
+        self.explicit |= LOAD_BALANCE_HOSTS;
+        self
+    }
+
+    /// Gets the host load balancing behavior.
+    pub fn get_load_balance_hosts(&self) -> LoadBalanceHosts {
+        self.load_balance_hosts
+    }
+
+    /// Sets the authentication methods the server is allowed to request.
+    ///
+    /// The connection fails with an authentication error if the server requests a method which is not in the list. Use
+    /// `AuthMethod::None` to allow servers which don't require authentication at all. Defaults to allowing all
+    /// methods.
+    pub fn require_auth(&mut self, require_auth: &[AuthMethod]) -> &mut Config {
+        self.require_auth = Some(require_auth.to_vec());
+        self
+    }
+
+    /// Gets the authentication methods the server is allowed to request, if they have been restricted with the
+    /// `require_auth` method.
+    pub fn get_require_auth(&self) -> Option<&[AuthMethod]> {
+        self.require_auth.as_deref()
+    }
+
     /// Sets the channel binding behavior.
     ///
     /// Defaults to `prefer`.
     pub fn channel_binding(&mut self, channel_binding: ChannelBinding) -> &mut Config {
         self.channel_binding = channel_binding;
+        self.explicit |= CHANNEL_BINDING;
         self
     }
 
@@ -387,8 +751,209 @@ impl Config {
         self.channel_binding
     }
 
+    /// Puts the connection into streaming replication mode.
+    ///
+    /// Only the simple query protocol and replication commands can be used on a connection in replication mode.
+    /// Defaults to no replication.
+    pub fn replication_mode(&mut self, replication_mode: ReplicationMode) -> &mut Config {
+        self.replication_mode = Some(replication_mode);
+        self
+    }
+
+    /// Gets the replication mode, if one has been set with the `replication_mode` method.
+    pub fn get_replication_mode(&self) -> Option<ReplicationMode> {
+        self.replication_mode
+    }
+
+    /// Sets the maximum number of prepared statements the client caches for reuse.
+    ///
+    /// When the cache is enabled, preparing a query with the same text and parameter types as a cached statement
+    /// returns the cached statement rather than preparing a new one. This also applies to queries passed as strings
+    /// to methods like `Client::query`. The least recently used statement is evicted when the cache is full.
+    ///
+    /// A capacity of 0 disables the cache. Defaults to 0.
+    pub fn statement_cache_capacity(&mut self, statement_cache_capacity: usize) -> &mut Config {
+        self.statement_cache_capacity = statement_cache_capacity;
+        self.explicit |= STATEMENT_CACHE_CAPACITY;
+        self
+    }
+
+    /// Gets the maximum number of prepared statements the client caches for reuse.
+    pub fn get_statement_cache_capacity(&self) -> usize {
+        self.statement_cache_capacity
+    }
+
+    /// Controls the use of named prepared statements.
+    ///
+    /// Named statements live on a specific server session, so they break when connections are multiplexed by a pooler
+    /// such as PgBouncer in transaction pooling mode. If disabled, queries passed as strings to methods like
+    /// `Client::query` and the client's internal type lookups are prepared as the unnamed statement instead, and that
+    /// statement is parsed again in the same round trip as each execution. Explicitly preparing a statement with
+    /// `Client::prepare` returns an error in this mode.
+    ///
+    /// Defaults to `true`.
+    pub fn named_statements(&mut self, named_statements: bool) -> &mut Config {
+        self.named_statements = named_statements;
+        self.explicit |= NAMED_STATEMENTS;
+        self
+    }
+
+    /// Reports whether named prepared statements will be used.
+    pub fn get_named_statements(&self) -> bool {
+        self.named_statements
+    }
+
+    /// Sets the name of the connection service file entry that `apply_env` takes defaults from.
+    pub fn service(&mut self, service: &str) -> &mut Config {
+        self.service = Some(service.to_string());
+        self
+    }
+
+    /// Gets the name of the connection service file entry, if one has been set with the `service` method.
+    pub fn get_service(&self) -> Option<&str> {
+        self.service.as_deref()
+    }
+
+    /// Fills in settings which have not been set from the environment, in the same way as libpq.
+    ///
+    /// Settings are taken from these sources, in order of precedence:
+    ///
+    /// 1. The settings already present in the configuration. A setting counts as present if it was set explicitly,
+    ///    even to its default value.
+    /// 2. The connection service file entry named by the `service` setting or the `PGSERVICE` environment variable.
+    ///    The user's service file is located by `PGSERVICEFILE`, defaulting to `~/.pg_service.conf`, and is searched
+    ///    before the system-wide `pg_service.conf` in the directory named by `PGSYSCONFDIR`.
+    /// 3. The `PGHOST`, `PGPORT`, `PGUSER`, `PGPASSWORD`, `PGDATABASE`, `PGOPTIONS`, `PGAPPNAME`, `PGSSLMODE`,
+    ///    `PGSSLNEGOTIATION`, `PGSSLROOTCERT`, `PGSSLCERT`, `PGSSLKEY`, `PGSSLCRL`, `PGCONNECT_TIMEOUT`,
+    ///    `PGTARGETSESSIONATTRS`, `PGLOADBALANCEHOSTS`, `PGCHANNELBINDING` and `PGREQUIREAUTH` environment variables.
+    /// 4. If no password has been set, the password file located by `PGPASSFILE`, defaulting to `~/.pgpass`. The file
+    ///    is ignored if it can be read by other users. Since the configuration holds a single password, the entry
+    ///    for the first host with a match is used.
+    ///
+    /// The `Config::connect` and `Config::connect_raw` methods don't read the environment on their own.
+    pub fn apply_env(&mut self) -> Result<&mut Config, Error> {
+        let service = match &self.service {
+            Some(service) => Some(service.clone()),
+            None => env::var("PGSERVICE").ok(),
+        };
+        if let Some(service) = service {
+            let mut config = Config::new();
+            for (key, value) in service_file::lookup(&service)? {
+                config.param(&key, &value)?;
+            }
+            self.fill_unset(config);
+        }
+
+        let mut config = Config::new();
+        for &(var, key) in ENV_VARS {
+            match env::var(var) {
+                Ok(ref value) if value.is_empty() => {}
+                Ok(value) => config.param(key, &value)?,
+                Err(env::VarError::NotPresent) => {}
+                Err(env::VarError::NotUnicode(_)) => {
+                    return Err(Error::config_parse(Box::new(InvalidValue(key))))
+                }
+            }
+        }
+        self.fill_unset(config);
+
+        if self.password.is_none() {
+            self.password = self.password_file_entry();
+        }
+
+        Ok(self)
+    }
+
+    fn fill_unset(&mut self, other: Config) {
+        macro_rules! fill {
+            ($($field:ident),*) => {
+                $(
+                    if self.$field.is_none() {
+                        self.$field = other.$field;
+                    }
+                )*
+            }
+        }
+
+        fill!(
+            user,
+            password,
+            dbname,
+            options,
+            application_name,
+            ssl_root_cert,
+            ssl_cert,
+            ssl_key,
+            ssl_password,
+            ssl_crl,
+            connect_timeout,
+            require_auth,
+            replication_mode
+        );
+
+        if self.host.is_empty() {
+            self.host = other.host;
+        }
+        if self.port.is_empty() {
+            self.port = other.port;
+        }
+
+        macro_rules! fill_explicit {
+            ($($field:ident: $flag:ident),*) => {
+                $(
+                    if self.explicit & $flag == 0 && other.explicit & $flag != 0 {
+                        self.$field = other.$field;
+                        self.explicit |= $flag;
+                    }
+                )*
+            }
+        }
+
+        fill_explicit!(
+            ssl_mode: SSL_MODE,
+            ssl_negotiation: SSL_NEGOTIATION,
+            keepalives: KEEPALIVES,
+            keepalives_idle: KEEPALIVES_IDLE,
+            target_session_attrs: TARGET_SESSION_ATTRS,
+            load_balance_hosts: LOAD_BALANCE_HOSTS,
+            channel_binding: CHANNEL_BINDING,
+            statement_cache_capacity: STATEMENT_CACHE_CAPACITY,
+            named_statements: NAMED_STATEMENTS
+        );
+    }
+
+    fn password_file_entry(&self) -> Option<Vec<u8>> {
+        let user = self.user.as_deref()?;
+        let dbname = self.dbname.as_deref().unwrap_or(user);
+
+        // libpq falls back to a Unix socket, which the password file calls `localhost`.
+        let default_host = [Host::Tcp("localhost".to_string())];
+        let hosts = if self.host.is_empty() {
+            &default_host[..]
+        } else {
+            &self.host[..]
+        };
+
+        hosts.iter().enumerate().find_map(|(i, host)| {
+            let port = *self
+                .port
+                .get(i)
+                .or_else(|| self.port.first())
+                .unwrap_or(&5432);
+            let host = match host {
+                Host::Tcp(host) => &**host,
+                #[cfg(unix)]
+                Host::Unix(_) => "localhost",
+            };
+            password_file::lookup(host, port, dbname, user)
+        })
+    }
+
     fn param(&mut self, key: &str, value: &str) -> Result<(), Error> {
         match key {
+            "service" => {
+                self.service(value);
+            }
             "user" => {
                 self.user(&value);
             }
@@ -409,10 +974,39 @@ impl Config {
                     "disable" => SslMode::Disable,
                     "prefer" => SslMode::Prefer,
                     "require" => SslMode::Require,
+                    "verify-ca" => SslMode::VerifyCa,
+                    "verify-full" => SslMode::VerifyFull,
                     _ => return Err(Error::config_parse(Box::new(InvalidValue("sslmode")))),
                 };
                 self.ssl_mode(mode);
             }
+            "sslnegotiation" => {
+                let negotiation = match value {
+                    "postgres" => SslNegotiation::Postgres,
+                    "direct" => SslNegotiation::Direct,
+                    _ => {
+                        return Err(Error::config_parse(Box::new(InvalidValue(
+                            "sslnegotiation",
+                        ))))
+                    }
+                };
+                self.ssl_negotiation(negotiation);
+            }
+            "sslrootcert" => {
+                self.ssl_root_cert(value);
+            }
+            "sslcert" => {
+                self.ssl_cert(value);
+            }
+            "sslkey" => {
+                self.ssl_key(value);
+            }
+            "sslpassword" => {
+                self.ssl_password(value);
+            }
+            "sslcrl" => {
+                self.ssl_crl(value);
+            }
             "host" => {
                 for host in value.split(',') {
                     self.host(host);
@@ -455,6 +1049,10 @@ impl Config {
                 let target_session_attrs = match &*value {
                     "any" => TargetSessionAttrs::Any,
                     "read-write" => TargetSessionAttrs::ReadWrite,
+                    "read-only" => TargetSessionAttrs::ReadOnly,
+                    "primary" => TargetSessionAttrs::Primary,
+                    "standby" => TargetSessionAttrs::Standby,
+                    "prefer-standby" => TargetSessionAttrs::PreferStandby,
                     _ => {
                         return Err(Error::config_parse(Box::new(InvalidValue(
                             "target_session_attrs",
@@ -463,6 +1061,46 @@ impl Config {
                 };
                 self.target_session_attrs(target_session_attrs);
             }
+            "load_balance_hosts" => {
+                let load_balance_hosts = match value {
+                    "disable" => LoadBalanceHosts::Disable,
+                    "random" => LoadBalanceHosts::Random,
+                    _ => {
+                        return Err(Error::config_parse(Box::new(InvalidValue(
+                            "load_balance_hosts",
+                        ))))
+                    }
+                };
+                self.load_balance_hosts(load_balance_hosts);
+            }
+            "require_auth" => {
+                let mut methods = vec![];
+                let mut negated = None;
+                for method in value.split(',') {
+                    let (is_negated, method) = match method.chars().next() {
+                        Some('!') => (true, &method[1..]),
+                        _ => (false, method),
+                    };
+                    let method = AuthMethod::parse(method).ok_or_else(|| {
+                        Error::config_parse(Box::new(InvalidValue("require_auth")))
+                    })?;
+                    // Negated and non-negated methods can't be mixed, and each method can only be listed once.
+                    if *negated.get_or_insert(is_negated) != is_negated || methods.contains(&method)
+                    {
+                        return Err(Error::config_parse(Box::new(InvalidValue("require_auth"))));
+                    }
+                    methods.push(method);
+                }
+
+                if negated == Some(true) {
+                    methods = AuthMethod::ALL
+                        .iter()
+                        .cloned()
+                        .filter(|m| !methods.contains(m))
+                        .collect();
+                }
+                self.require_auth(&methods);
+            }
             "channel_binding" => {
                 let channel_binding = match value {
                     "disable" => ChannelBinding::Disable,
@@ -476,6 +1114,33 @@ impl Config {
                 };
                 self.channel_binding(channel_binding);
             }
+            "replication" => {
+                let mode = match value {
+                    "off" | "false" | "no" | "0" => None,
+                    "on" | "true" | "yes" | "1" => Some(ReplicationMode::Physical),
+                    "database" => Some(ReplicationMode::Logical),
+                    _ => return Err(Error::config_parse(Box::new(InvalidValue("replication")))),
+                };
+                self.replication_mode = mode;
+            }
+            "statement_cache_capacity" => {
+                let capacity = value.parse::<usize>().map_err(|_| {
+                    Error::config_parse(Box::new(InvalidValue("statement_cache_capacity")))
+                })?;
+                self.statement_cache_capacity(capacity);
+            }
+            "named_statements" => {
+                let named_statements = match value {
+                    "off" | "false" | "no" | "0" => false,
+                    "on" | "true" | "yes" | "1" => true,
+                    _ => {
+                        return Err(Error::config_parse(Box::new(InvalidValue(
+                            "named_statements",
+                        ))))
+                    }
+                };
+                self.named_statements(named_statements);
+            }
             key => {
                 return Err(Error::config_parse(Box::new(UnknownOption(
                     key.to_string(),
@@ -525,6 +1190,38 @@ impl FromStr for Config {
 }
 
 // Omit password from debug output
+// Whether a setting was made explicitly only affects `apply_env`, so it isn't compared.
+impl PartialEq for Config {
+    fn eq(&self, other: &Config) -> bool {
+        self.user == other.user
+            && self.password == other.password
+            && self.password_provider == other.password_provider
+            && self.dbname == other.dbname
+            && self.options == other.options
+            && self.application_name == other.application_name
+            && self.ssl_mode == other.ssl_mode
+            && self.ssl_negotiation == other.ssl_negotiation
+            && self.ssl_root_cert == other.ssl_root_cert
+            && self.ssl_cert == other.ssl_cert
+            && self.ssl_key == other.ssl_key
+            && self.ssl_password == other.ssl_password
+            && self.ssl_crl == other.ssl_crl
+            && self.host == other.host
+            && self.port == other.port
+            && self.connect_timeout == other.connect_timeout
+            && self.keepalives == other.keepalives
+            && self.keepalives_idle == other.keepalives_idle
+            && self.target_session_attrs == other.target_session_attrs
+            && self.load_balance_hosts == other.load_balance_hosts
+            && self.require_auth == other.require_auth
+            && self.channel_binding == other.channel_binding
+            && self.replication_mode == other.replication_mode
+            && self.statement_cache_capacity == other.statement_cache_capacity
+            && self.named_statements == other.named_statements
+            && self.service == other.service
+    }
+}
+
 impl fmt::Debug for Config {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         struct Redaction {}
@@ -537,21 +1234,73 @@ impl fmt::Debug for Config {
         f.debug_struct("Config")
             .field("user", &self.user)
             .field("password", &self.password.as_ref().map(|_| Redaction {}))
+            .field(
+                "password_provider",
+                &self.password_provider.as_ref().map(|_| Redaction {}),
+            )
             .field("dbname", &self.dbname)
             .field("options", &self.options)
             .field("application_name", &self.application_name)
             .field("ssl_mode", &self.ssl_mode)
+            .field("ssl_negotiation", &self.ssl_negotiation)
+            .field("ssl_root_cert", &self.ssl_root_cert)
+            .field("ssl_cert", &self.ssl_cert)
+            .field("ssl_key", &self.ssl_key)
+            .field(
+                "ssl_password",
+                &self.ssl_password.as_ref().map(|_| Redaction {}),
+            )
+            .field("ssl_crl", &self.ssl_crl)
             .field("host", &self.host)
             .field("port", &self.port)
             .field("connect_timeout", &self.connect_timeout)
             .field("keepalives", &self.keepalives)
             .field("keepalives_idle", &self.keepalives_idle)
             .field("target_session_attrs", &self.target_session_attrs)
+            .field("load_balance_hosts", &self.load_balance_hosts)
+            .field("require_auth", &self.require_auth)
             .field("channel_binding", &self.channel_binding)
+            .field("replication_mode", &self.replication_mode)
+            .field("statement_cache_capacity", &self.statement_cache_capacity)
+            .field("named_statements", &self.named_statements)
+            .field("service", &self.service)
             .finish()
     }
 }
 
+// Settings which have default values, tracked so that `apply_env` doesn't override them when they're set explicitly.
+const SSL_MODE: u16 = 1 << 0;
+const SSL_NEGOTIATION: u16 = 1 << 1;
+const KEEPALIVES: u16 = 1 << 2;
+const KEEPALIVES_IDLE: u16 = 1 << 3;
+const TARGET_SESSION_ATTRS: u16 = 1 << 4;
+const LOAD_BALANCE_HOSTS: u16 = 1 << 5;
+const CHANNEL_BINDING: u16 = 1 << 6;
+const STATEMENT_CACHE_CAPACITY: u16 = 1 << 7;
+const NAMED_STATEMENTS: u16 = 1 << 8;
+
+// The environment variables read by `Config::apply_env`, along with the parameters they correspond to.
+const ENV_VARS: &[(&str, &str)] = &[
+    ("PGHOST", "host"),
+    ("PGPORT", "port"),
+    ("PGUSER", "user"),
+    ("PGPASSWORD", "password"),
+    ("PGDATABASE", "dbname"),
+    ("PGOPTIONS", "options"),
+    ("PGAPPNAME", "application_name"),
+    ("PGSSLMODE", "sslmode"),
+    ("PGSSLNEGOTIATION", "sslnegotiation"),
+    ("PGSSLROOTCERT", "sslrootcert"),
+    ("PGSSLCERT", "sslcert"),
+    ("PGSSLKEY", "sslkey"),
+    ("PGSSLCRL", "sslcrl"),
+    ("PGCONNECT_TIMEOUT", "connect_timeout"),
+    ("PGTARGETSESSIONATTRS", "target_session_attrs"),
+    ("PGLOADBALANCEHOSTS", "load_balance_hosts"),
+    ("PGCHANNELBINDING", "channel_binding"),
+    ("PGREQUIREAUTH", "require_auth"),
+];
+
 #[derive(Debug)]
 struct UnknownOption(String);
 
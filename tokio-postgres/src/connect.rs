@@ -1,12 +1,14 @@
 use crate::client::SocketConfig;
-use crate::config::{Host, TargetSessionAttrs};
+use crate::config::{Host, LoadBalanceHosts, TargetSessionAttrs};
 use crate::connect_raw::connect_raw;
 use crate::connect_socket::connect_socket;
 use crate::tls::{MakeTlsConnect, TlsConnect};
 use crate::{Client, Config, Connection, Error, SimpleQueryMessage, Socket};
 use futures::{future, pin_mut, Future, FutureExt, Stream};
+use rand::seq::SliceRandom;
 use std::io;
 use std::task::Poll;
+use tokio::io::{AsyncRead, AsyncWrite};
 
 pub async fn connect<T>(
     mut tls: T,
@@ -23,28 +25,50 @@ where
         return Err(Error::config("invalid number of ports".into()));
     }
 
+    let mut hosts = config
+        .host
+        .iter()
+        .enumerate()
+        .map(|(i, host)| {
+            let port = *config
+                .port
+                .get(i)
+                .or_else(|| config.port.get(0))
+                .unwrap_or(&5432);
+            (host, port)
+        })
+        .collect::<Vec<_>>();
+
+    if let LoadBalanceHosts::Random = config.load_balance_hosts {
+        hosts.shuffle(&mut rand::thread_rng());
+    }
+
+    // If no standby is available, prefer-standby falls back to trying every host again without any requirements.
+    let passes = match config.target_session_attrs {
+        TargetSessionAttrs::PreferStandby => {
+            vec![TargetSessionAttrs::Standby, TargetSessionAttrs::Any]
+        }
+        target_session_attrs => vec![target_session_attrs],
+    };
+
     let mut error = None;
-    for (i, host) in config.host.iter().enumerate() {
-        let port = *config
-            .port
-            .get(i)
-            .or_else(|| config.port.get(0))
-            .unwrap_or(&5432);
-
-        let hostname = match host {
-            Host::Tcp(host) => &**host,
-            // postgres doesn't support TLS over unix sockets, so the choice here doesn't matter
-            #[cfg(unix)]
-            Host::Unix(_) => "",
-        };
-
-        let tls = tls
-            .make_tls_connect(hostname)
-            .map_err(|e| Error::tls(e.into()))?;
-
-        match connect_once(host, port, tls, config).await {
-            Ok((client, connection)) => return Ok((client, connection)),
-            Err(e) => error = Some(e),
+    for target_session_attrs in passes {
+        for &(host, port) in &hosts {
+            let hostname = match host {
+                Host::Tcp(host) => &**host,
+                // postgres doesn't support TLS over unix sockets, so the choice here doesn't matter
+                #[cfg(unix)]
+                Host::Unix(_) => "",
+            };
+
+            let tls = tls
+                .make_tls_connect(hostname)
+                .map_err(|e| Error::tls(e.into()))?;
+
+            match connect_once(host, port, tls, config, target_session_attrs).await {
+                Ok((client, connection)) => return Ok((client, connection)),
+                Err(e) => error = Some(e),
+            }
         }
     }
 
@@ -56,6 +80,7 @@ async fn connect_once<T>(
     port: u16,
     tls: T,
     config: &Config,
+    target_session_attrs: TargetSessionAttrs,
 ) -> Result<(Client, Connection<Socket, T::Stream>), Error>
 where
     T: TlsConnect<Socket>,
@@ -70,44 +95,52 @@ where
     .await?;
     let (mut client, mut connection) = connect_raw(socket, tls, config).await?;
 
-    if let TargetSessionAttrs::ReadWrite = config.target_session_attrs {
-        let rows = client.simple_query_raw("SHOW transaction_read_only");
-        pin_mut!(rows);
+    match target_session_attrs {
+        TargetSessionAttrs::ReadWrite | TargetSessionAttrs::ReadOnly => {
+            // Servers since 14 report everything needed up front, which saves a round trip.
+            let read_only = match (
+                connection.parameter("in_hot_standby"),
+                connection.parameter("default_transaction_read_only"),
+            ) {
+                (Some(in_hot_standby), Some(default_transaction_read_only)) => {
+                    in_hot_standby == "on" || default_transaction_read_only == "on"
+                }
+                _ => {
+                    let value =
+                        query_value(&client, &mut connection, "SHOW transaction_read_only").await?;
+                    value.as_deref() == Some("on")
+                }
+            };
 
-        let rows = future::poll_fn(|cx| {
-            if connection.poll_unpin(cx)?.is_ready() {
-                return Poll::Ready(Err(Error::closed()));
+            if read_only && target_session_attrs == TargetSessionAttrs::ReadWrite {
+                return Err(session_attrs_error("database does not allow writes"));
             }
-
-            rows.as_mut().poll(cx)
-        })
-        .await?;
-        pin_mut!(rows);
-
-        loop {
-            let next = future::poll_fn(|cx| {
-                if connection.poll_unpin(cx)?.is_ready() {
-                    return Poll::Ready(Some(Err(Error::closed())));
+            if !read_only && target_session_attrs == TargetSessionAttrs::ReadOnly {
+                return Err(session_attrs_error("database allows writes"));
+            }
+        }
+        TargetSessionAttrs::Primary | TargetSessionAttrs::Standby => {
+            let in_hot_standby = match connection.parameter("in_hot_standby") {
+                Some(in_hot_standby) => in_hot_standby == "on",
+                None => {
+                    let value = query_value(
+                        &client,
+                        &mut connection,
+                        "SELECT pg_catalog.pg_is_in_recovery()",
+                    )
+                    .await?;
+                    value.as_deref() == Some("t")
                 }
+            };
 
-                rows.as_mut().poll_next(cx)
-            });
-
-            match next.await.transpose()? {
-                Some(SimpleQueryMessage::Row(row)) => {
-                    if row.try_get(0)? == Some("on") {
-                        return Err(Error::connect(io::Error::new(
-                            io::ErrorKind::PermissionDenied,
-                            "database does not allow writes",
-                        )));
-                    } else {
-                        break;
-                    }
-                }
-                Some(_) => {}
-                None => return Err(Error::unexpected_message()),
+            if in_hot_standby && target_session_attrs == TargetSessionAttrs::Primary {
+                return Err(session_attrs_error("database is in hot standby mode"));
+            }
+            if !in_hot_standby && target_session_attrs == TargetSessionAttrs::Standby {
+                return Err(session_attrs_error("database is not in hot standby mode"));
             }
         }
+        _ => {}
     }
 
     client.set_socket_config(SocketConfig {
@@ -120,3 +153,46 @@ where
 
     Ok((client, connection))
 }
+
+fn session_attrs_error(message: &str) -> Error {
+    Error::connect(io::Error::new(io::ErrorKind::PermissionDenied, message))
+}
+
+// Returns the first column of the first row of a query's results, driving the connection while waiting for them.
+async fn query_value<S>(
+    client: &Client,
+    connection: &mut Connection<Socket, S>,
+    query: &str,
+) -> Result<Option<String>, Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let rows = client.simple_query_raw(query);
+    pin_mut!(rows);
+
+    let rows = future::poll_fn(|cx| {
+        if connection.poll_unpin(cx)?.is_ready() {
+            return Poll::Ready(Err(Error::closed()));
+        }
+
+        rows.as_mut().poll(cx)
+    })
+    .await?;
+    pin_mut!(rows);
+
+    loop {
+        let next = future::poll_fn(|cx| {
+            if connection.poll_unpin(cx)?.is_ready() {
+                return Poll::Ready(Some(Err(Error::closed())));
+            }
+
+            rows.as_mut().poll_next(cx)
+        });
+
+        match next.await.transpose()? {
+            Some(SimpleQueryMessage::Row(row)) => return Ok(row.try_get(0)?.map(str::to_string)),
+            Some(_) => {}
+            None => return Err(Error::unexpected_message()),
+        }
+    }
+}
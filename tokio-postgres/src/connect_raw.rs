@@ -1,5 +1,5 @@
 use crate::codec::{BackendMessage, BackendMessages, FrontendMessage, PostgresCodec};
-use crate::config::{self, Config};
+use crate::config::{self, AuthMethod, Config, ReplicationMode};
 use crate::connect_tls::connect_tls;
 use crate::maybe_tls_stream::MaybeTlsStream;
 use crate::tls::{TlsConnect, TlsStream};
@@ -13,6 +13,7 @@ use postgres_protocol::authentication::sasl;
 use postgres_protocol::authentication::sasl::ScramSha256;
 use postgres_protocol::message::backend::{AuthenticationSaslBody, Message};
 use postgres_protocol::message::frontend;
+use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
 use std::io;
 use std::pin::Pin;
@@ -87,7 +88,7 @@ where
     S: AsyncRead + AsyncWrite + Unpin,
     T: TlsConnect<S>,
 {
-    let stream = connect_tls(stream, config.ssl_mode, tls).await?;
+    let stream = connect_tls(stream, config.ssl_mode, config.ssl_negotiation, tls).await?;
 
     let mut stream = StartupStream {
         inner: Framed::new(stream, PostgresCodec),
@@ -100,7 +101,15 @@ where
     let (process_id, secret_key, parameters) = read_info(&mut stream).await?;
 
     let (sender, receiver) = mpsc::unbounded();
-    let client = Client::new(sender, config.ssl_mode, process_id, secret_key);
+    let client = Client::new(
+        sender,
+        config.ssl_mode,
+        config.ssl_negotiation,
+        process_id,
+        secret_key,
+        config.statement_cache_capacity,
+        config.named_statements,
+    );
     let connection = Connection::new(stream.inner, stream.delayed, parameters, receiver);
 
     Ok((client, connection))
@@ -124,6 +133,12 @@ where
     if let Some(application_name) = &config.application_name {
         params.push(("application_name", &**application_name));
     }
+    if let Some(replication_mode) = &config.replication_mode {
+        match replication_mode {
+            ReplicationMode::Physical => params.push(("replication", "true")),
+            ReplicationMode::Logical => params.push(("replication", "database")),
+        }
+    }
 
     let mut buf = BytesMut::new();
     frontend::startup_message(params, &mut buf).map_err(Error::encode)?;
@@ -141,41 +156,48 @@ where
 {
     match stream.try_next().await.map_err(Error::io)? {
         Some(Message::AuthenticationOk) => {
+            check_auth_method(config, AuthMethod::None)?;
             can_skip_channel_binding(config)?;
             return Ok(());
         }
         Some(Message::AuthenticationCleartextPassword) => {
+            check_auth_method(config, AuthMethod::Password)?;
             can_skip_channel_binding(config)?;
 
-            let pass = config
-                .password
-                .as_ref()
-                .ok_or_else(|| Error::config("password missing".into()))?;
+            let pass = password(config).await?;
 
-            authenticate_password(stream, pass).await?;
+            authenticate_password(stream, &pass).await?;
         }
         Some(Message::AuthenticationMd5Password(body)) => {
+            check_auth_method(config, AuthMethod::Md5)?;
             can_skip_channel_binding(config)?;
 
             let user = config
                 .user
                 .as_ref()
                 .ok_or_else(|| Error::config("user missing".into()))?;
-            let pass = config
-                .password
-                .as_ref()
-                .ok_or_else(|| Error::config("password missing".into()))?;
+            let pass = password(config).await?;
 
-            let output = authentication::md5_hash(user.as_bytes(), pass, body.salt());
+            let output = authentication::md5_hash(user.as_bytes(), &pass, body.salt());
             authenticate_password(stream, output.as_bytes()).await?;
         }
         Some(Message::AuthenticationSasl(body)) => {
+            check_auth_method(config, AuthMethod::ScramSha256)?;
             authenticate_sasl(stream, body, config).await?;
         }
-        Some(Message::AuthenticationKerberosV5)
-        | Some(Message::AuthenticationScmCredential)
-        | Some(Message::AuthenticationGss)
-        | Some(Message::AuthenticationSspi) => {
+        Some(Message::AuthenticationGss) => {
+            check_auth_method(config, AuthMethod::Gss)?;
+            return Err(Error::authentication(
+                "unsupported authentication method".into(),
+            ));
+        }
+        Some(Message::AuthenticationSspi) => {
+            check_auth_method(config, AuthMethod::Sspi)?;
+            return Err(Error::authentication(
+                "unsupported authentication method".into(),
+            ));
+        }
+        Some(Message::AuthenticationKerberosV5) | Some(Message::AuthenticationScmCredential) => {
             return Err(Error::authentication(
                 "unsupported authentication method".into(),
             ))
@@ -193,6 +215,35 @@ where
     }
 }
 
+// A provider is asked for a fresh password each time a connection authenticates.
+async fn password(config: &Config) -> Result<Cow<'_, [u8]>, Error> {
+    if let Some(provider) = &config.password_provider {
+        let password = provider.0.password().await.map_err(Error::authentication)?;
+        return Ok(Cow::Owned(password));
+    }
+
+    config
+        .password
+        .as_deref()
+        .map(Cow::Borrowed)
+        .ok_or_else(|| Error::config("password missing".into()))
+}
+
+fn check_auth_method(config: &Config, method: AuthMethod) -> Result<(), Error> {
+    match &config.require_auth {
+        Some(methods) if !methods.contains(&method) => {
+            let message = match method {
+                AuthMethod::None => "server did not request authentication".to_string(),
+                method => format!("server requested {} authentication", method),
+            };
+            Err(Error::authentication(
+                format!("{}, which is not allowed by require_auth", message).into(),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
 fn can_skip_channel_binding(config: &Config) -> Result<(), Error> {
     match config.channel_binding {
         config::ChannelBinding::Disable | config::ChannelBinding::Prefer => Ok(()),
@@ -228,10 +279,7 @@ where
     S: AsyncRead + AsyncWrite + Unpin,
     T: TlsStream + Unpin,
 {
-    let password = config
-        .password
-        .as_ref()
-        .ok_or_else(|| Error::config("password missing".into()))?;
+    let password = password(config).await?;
 
     let mut has_scram = false;
     let mut has_scram_plus = false;
@@ -270,7 +318,7 @@ where
         can_skip_channel_binding(config)?;
     }
 
-    let mut scram = ScramSha256::new(password, channel_binding);
+    let mut scram = ScramSha256::new(&password, channel_binding);
 
     let mut buf = BytesMut::new();
     frontend::sasl_initial_response(mechanism, scram.message(), &mut buf).map_err(Error::encode)?;
@@ -1,7 +1,7 @@
-use crate::config::SslMode;
+use crate::config::{SslMode, SslNegotiation};
 use crate::maybe_tls_stream::MaybeTlsStream;
 use crate::tls::private::ForcePrivateApi;
-use crate::tls::TlsConnect;
+use crate::tls::{TlsConnect, TlsStream};
 use crate::Error;
 use bytes::BytesMut;
 use postgres_protocol::message::frontend;
@@ -10,32 +10,44 @@ use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 pub async fn connect_tls<S, T>(
     mut stream: S,
     mode: SslMode,
+    negotiation: SslNegotiation,
     tls: T,
 ) -> Result<MaybeTlsStream<S, T::Stream>, Error>
 where
     S: AsyncRead + AsyncWrite + Unpin,
     T: TlsConnect<S>,
 {
+    // With direct negotiation there's no way to fall back to an unencrypted connection.
+    if negotiation == SslNegotiation::Direct
+        && (mode == SslMode::Disable || mode == SslMode::Prefer)
+    {
+        return Err(Error::config(
+            "sslnegotiation=direct requires an sslmode of require, verify-ca or verify-full".into(),
+        ));
+    }
+
     match mode {
         SslMode::Disable => return Ok(MaybeTlsStream::Raw(stream)),
         SslMode::Prefer if !tls.can_connect(ForcePrivateApi) => {
             return Ok(MaybeTlsStream::Raw(stream))
         }
-        SslMode::Prefer | SslMode::Require => {}
+        SslMode::Prefer | SslMode::Require | SslMode::VerifyCa | SslMode::VerifyFull => {}
     }
 
-    let mut buf = BytesMut::new();
-    frontend::ssl_request(&mut buf);
-    stream.write_all(&buf).await.map_err(Error::io)?;
+    if negotiation == SslNegotiation::Postgres {
+        let mut buf = BytesMut::new();
+        frontend::ssl_request(&mut buf);
+        stream.write_all(&buf).await.map_err(Error::io)?;
 
-    let mut buf = [0];
-    stream.read_exact(&mut buf).await.map_err(Error::io)?;
+        let mut buf = [0];
+        stream.read_exact(&mut buf).await.map_err(Error::io)?;
 
-    if buf[0] != b'S' {
-        if SslMode::Require == mode {
-            return Err(Error::tls("server does not support TLS".into()));
-        } else {
-            return Ok(MaybeTlsStream::Raw(stream));
+        if buf[0] != b'S' {
+            if SslMode::Prefer == mode {
+                return Ok(MaybeTlsStream::Raw(stream));
+            } else {
+                return Err(Error::tls("server does not support TLS".into()));
+            }
         }
     }
 
@@ -44,5 +56,14 @@ where
         .await
         .map_err(|e| Error::tls(e.into()))?;
 
+    // Without the ALPN check, a direct connection could be redirected to a server speaking some other protocol.
+    if negotiation == SslNegotiation::Direct
+        && stream.alpn_protocol().as_deref() != Some(&b"postgresql"[..])
+    {
+        return Err(Error::tls(
+            "sslnegotiation=direct requires the postgresql ALPN protocol to be negotiated".into(),
+        ));
+    }
+
     Ok(MaybeTlsStream::Tls(stream))
 }
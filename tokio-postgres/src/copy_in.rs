@@ -213,7 +213,7 @@ where
         .await
         .map_err(|_| Error::closed())?;
 
-    match responses.next().await? {
+    match query::bind_response(&mut responses, &statement).await? {
         Message::BindComplete => {}
         _ => return Err(Error::unexpected_message()),
     }
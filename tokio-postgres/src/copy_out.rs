@@ -15,17 +15,21 @@ pub async fn copy_out(client: &InnerClient, statement: Statement) -> Result<Copy
     debug!("executing copy out statement {}", statement.name());
 
     let buf = query::encode(client, &statement, slice_iter(&[]))?;
-    let responses = start(client, buf).await?;
+    let responses = start(client, &statement, buf).await?;
     Ok(CopyOutStream {
         responses,
         _p: PhantomPinned,
     })
 }
 
-async fn start(client: &InnerClient, buf: Bytes) -> Result<Responses, Error> {
+async fn start(
+    client: &InnerClient,
+    statement: &Statement,
+    buf: Bytes,
+) -> Result<Responses, Error> {
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
 
-    match responses.next().await? {
+    match query::bind_response(&mut responses, statement).await? {
         Message::BindComplete => {}
         _ => return Err(Error::unexpected_message()),
     }
@@ -345,6 +345,7 @@ enum Kind {
     ConfigParse,
     Config,
     RowCount,
+    Aborted,
     #[cfg(feature = "runtime")]
     Connect,
 }
@@ -383,6 +384,9 @@ impl fmt::Display for Error {
             Kind::ConfigParse => fmt.write_str("invalid connection string")?,
             Kind::Config => fmt.write_str("invalid configuration")?,
             Kind::RowCount => fmt.write_str("query returned an unexpected number of rows")?,
+            Kind::Aborted => {
+                fmt.write_str("statement was not executed because an earlier statement failed")?
+            }
             #[cfg(feature = "runtime")]
             Kind::Connect => fmt.write_str("error connecting to server")?,
         };
@@ -480,6 +484,10 @@ impl Error {
         Error::new(Kind::RowCount, None)
     }
 
+    pub(crate) fn aborted() -> Error {
+        Error::new(Kind::Aborted, None)
+    }
+
     #[cfg(feature = "runtime")]
     pub(crate) fn connect(e: io::Error) -> Error {
         Error::new(Kind::Connect, Some(Box::new(e)))
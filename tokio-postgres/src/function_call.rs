@@ -0,0 +1,41 @@
+use crate::client::InnerClient;
+use crate::codec::FrontendMessage;
+use crate::connection::RequestMessages;
+use crate::types::{Format, Oid};
+use crate::Error;
+use bytes::Bytes;
+use log::debug;
+use postgres_protocol::message::backend::Message;
+use postgres_protocol::message::frontend;
+
+pub async fn call_function(
+    client: &InnerClient,
+    oid: Oid,
+    args: &[Option<&[u8]>],
+    result_format: Format,
+) -> Result<Option<Bytes>, Error> {
+    debug!("calling function {}", oid);
+
+    let buf = client.with_buf(|buf| {
+        frontend::function_call(
+            oid,
+            Some(Format::Binary.code()),
+            args.iter().cloned(),
+            result_format.code(),
+            buf,
+        )
+        .map_err(Error::encode)?;
+        Ok(buf.split().freeze())
+    })?;
+    let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+
+    let value = match responses.next().await? {
+        Message::FunctionCallResponse(body) => body.into_value(),
+        _ => return Err(Error::unexpected_message()),
+    };
+
+    match responses.next().await? {
+        Message::ReadyForQuery(_) => Ok(value),
+        _ => Err(Error::unexpected_message()),
+    }
+}
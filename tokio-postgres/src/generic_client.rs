@@ -54,6 +54,18 @@ pub trait GenericClient: private::Sealed {
         I: IntoIterator<Item = &'b dyn ToSql> + Sync + Send,
         I::IntoIter: ExactSizeIterator;
 
+    /// Like `Client::query_typed`.
+    async fn query_typed(
+        &self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error>;
+
+    /// Like `Client::query_typed_raw`.
+    async fn query_typed_raw<'b, I>(&self, query: &str, params: I) -> Result<RowStream, Error>
+    where
+        I: IntoIterator<Item = (&'b dyn ToSql, Type)> + Sync + Send;
+
     /// Like `Client::prepare`.
     async fn prepare(&self, query: &str) -> Result<Statement, Error>;
 
@@ -126,6 +138,21 @@ impl GenericClient for Client {
         self.query_raw(statement, params).await
     }
 
+    async fn query_typed(
+        &self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error> {
+        self.query_typed(query, params).await
+    }
+
+    async fn query_typed_raw<'b, I>(&self, query: &str, params: I) -> Result<RowStream, Error>
+    where
+        I: IntoIterator<Item = (&'b dyn ToSql, Type)> + Sync + Send,
+    {
+        self.query_typed_raw(query, params).await
+    }
+
     async fn prepare(&self, query: &str) -> Result<Statement, Error> {
         self.prepare(query).await
     }
@@ -202,6 +229,21 @@ impl GenericClient for Transaction<'_> {
         self.query_raw(statement, params).await
     }
 
+    async fn query_typed(
+        &self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error> {
+        self.query_typed(query, params).await
+    }
+
+    async fn query_typed_raw<'b, I>(&self, query: &str, params: I) -> Result<RowStream, Error>
+    where
+        I: IntoIterator<Item = (&'b dyn ToSql, Type)> + Sync + Send,
+    {
+        self.query_typed_raw(query, params).await
+    }
+
     async fn prepare(&self, query: &str) -> Result<Statement, Error> {
         self.prepare(query).await
     }
@@ -0,0 +1,407 @@
+//! Support for PostgreSQL large objects.
+//!
+//! Large objects store binary data which can be accessed in chunks, rather than all at once like a `BYTEA` column.
+//! They are created, opened and removed through a `Transaction`, and an open object implements `AsyncRead`,
+//! `AsyncWrite` and `AsyncSeek`. Open objects are closed automatically when the transaction ends.
+
+use crate::client::InnerClient;
+use crate::function_call;
+use crate::types::{Format, Oid};
+use crate::Error;
+use byteorder::{BigEndian, ByteOrder};
+use bytes::{Buf, Bytes};
+use futures::{future, ready};
+use std::cmp;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::io::{self, SeekFrom};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncWrite, AsyncWriteExt};
+
+// The OIDs of the server-side large object functions, which are fixed in pg_proc.
+const LO_CREATE: Oid = 715;
+const LO_OPEN: Oid = 952;
+const LO_CLOSE: Oid = 953;
+const LOREAD: Oid = 954;
+const LOWRITE: Oid = 955;
+const LO_UNLINK: Oid = 964;
+const LO_LSEEK64: Oid = 3170;
+const LO_TRUNCATE64: Oid = 3172;
+
+const INV_WRITE: i32 = 0x0002_0000;
+const INV_READ: i32 = 0x0004_0000;
+
+const SEEK_SET: i32 = 0;
+const SEEK_END: i32 = 2;
+
+// the largest amount of data read or written in a single call
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The mode in which a large object is opened.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// The object can only be read.
+    ///
+    /// Reads see the contents of the object as of the start of the transaction's snapshot.
+    Read,
+    /// The object can only be written.
+    Write,
+    /// The object can be both read and written.
+    ReadWrite,
+}
+
+impl Mode {
+    fn flags(self) -> i32 {
+        match self {
+            Mode::Read => INV_READ,
+            Mode::Write => INV_WRITE,
+            Mode::ReadWrite => INV_READ | INV_WRITE,
+        }
+    }
+}
+
+async fn call(client: &InnerClient, oid: Oid, args: &[Option<&[u8]>]) -> Result<Bytes, Error> {
+    match function_call::call_function(client, oid, args, Format::Binary).await? {
+        Some(value) => Ok(value),
+        None => Err(Error::unexpected_message()),
+    }
+}
+
+fn int4(value: &[u8]) -> Result<i32, Error> {
+    if value.len() != 4 {
+        return Err(Error::parse(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid buffer size",
+        )));
+    }
+    Ok(BigEndian::read_i32(value))
+}
+
+fn int8(value: &[u8]) -> Result<i64, Error> {
+    if value.len() != 8 {
+        return Err(Error::parse(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid buffer size",
+        )));
+    }
+    Ok(BigEndian::read_i64(value))
+}
+
+pub(crate) async fn create(client: &InnerClient) -> Result<Oid, Error> {
+    // an OID of 0 lets the server pick one
+    let oid = call(client, LO_CREATE, &[Some(&0u32.to_be_bytes())]).await?;
+    int4(&oid).map(|oid| oid as Oid)
+}
+
+pub(crate) async fn open(
+    client: &Arc<InnerClient>,
+    oid: Oid,
+    mode: Mode,
+) -> Result<LargeObject<'static>, Error> {
+    let fd = call(
+        client,
+        LO_OPEN,
+        &[Some(&oid.to_be_bytes()), Some(&mode.flags().to_be_bytes())],
+    )
+    .await?;
+    let fd = int4(&fd)?;
+
+    Ok(LargeObject {
+        client: client.clone(),
+        oid,
+        fd,
+        pos: 0,
+        buf: Bytes::new(),
+        state: State::Idle,
+        _p: PhantomData,
+    })
+}
+
+pub(crate) async fn unlink(client: &InnerClient, oid: Oid) -> Result<(), Error> {
+    call(client, LO_UNLINK, &[Some(&oid.to_be_bytes())]).await?;
+    Ok(())
+}
+
+pub(crate) async fn import<R>(client: &Arc<InnerClient>, mut reader: R) -> Result<Oid, Error>
+where
+    R: AsyncRead + Unpin,
+{
+    let oid = create(client).await?;
+    let mut object = open(client, oid, Mode::Write).await?;
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+        let len = reader.read(&mut buf).await.map_err(Error::io)?;
+        if len == 0 {
+            break;
+        }
+        object.write_all(&buf[..len]).await.map_err(unwrap_io)?;
+    }
+    object.close().await?;
+    Ok(oid)
+}
+
+pub(crate) async fn export<W>(
+    client: &Arc<InnerClient>,
+    oid: Oid,
+    mut writer: W,
+) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut object = open(client, oid, Mode::Read).await?;
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+        let len = object.read(&mut buf).await.map_err(unwrap_io)?;
+        if len == 0 {
+            break;
+        }
+        writer.write_all(&buf[..len]).await.map_err(Error::io)?;
+    }
+    writer.flush().await.map_err(Error::io)?;
+    object.close().await
+}
+
+fn into_io(e: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, e)
+}
+
+// recovers the database errors wrapped by `into_io`
+fn unwrap_io(e: io::Error) -> Error {
+    if e.get_ref().map_or(false, |e| e.is::<Error>()) {
+        *e.into_inner().unwrap().downcast::<Error>().unwrap()
+    } else {
+        Error::io(e)
+    }
+}
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send>>;
+
+enum State {
+    Idle,
+    Reading(BoxFuture<Bytes>),
+    Writing(BoxFuture<usize>),
+    Seeking(BoxFuture<u64>),
+}
+
+/// An open large object.
+///
+/// Reads and writes are sent to the server in chunks of at most 64 KiB, one request at a time.
+pub struct LargeObject<'a> {
+    client: Arc<InnerClient>,
+    oid: Oid,
+    fd: i32,
+    // the position of the server's cursor
+    pos: u64,
+    // data which has been read from the server but not yet returned
+    buf: Bytes,
+    state: State,
+    _p: PhantomData<&'a ()>,
+}
+
+impl<'a> LargeObject<'a> {
+    /// Returns the OID of the object.
+    pub fn oid(&self) -> Oid {
+        self.oid
+    }
+
+    /// Truncates or extends the object to the given length.
+    ///
+    /// The object's position is unchanged.
+    pub async fn truncate(&mut self, len: u64) -> Result<(), Error> {
+        future::poll_fn(|cx| self.poll_idle(cx)).await?;
+        let len = i64::try_from(len)
+            .map_err(|e| Error::encode(io::Error::new(io::ErrorKind::InvalidInput, e)))?;
+        call(
+            &self.client,
+            LO_TRUNCATE64,
+            &[Some(&self.fd.to_be_bytes()), Some(&len.to_be_bytes())],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Closes the object.
+    ///
+    /// Objects are closed automatically when their transaction ends, but this can be used to release them earlier.
+    pub async fn close(mut self) -> Result<(), Error> {
+        future::poll_fn(|cx| self.poll_idle(cx)).await?;
+        call(&self.client, LO_CLOSE, &[Some(&self.fd.to_be_bytes())]).await?;
+        Ok(())
+    }
+
+    fn begin_read(&mut self, len: usize) {
+        let client = self.client.clone();
+        let fd = self.fd;
+        let len = cmp::min(len, CHUNK_SIZE) as i32;
+        self.state = State::Reading(Box::pin(async move {
+            call(
+                &client,
+                LOREAD,
+                &[Some(&fd.to_be_bytes()), Some(&len.to_be_bytes())],
+            )
+            .await
+        }));
+    }
+
+    fn begin_write(&mut self, buf: &[u8]) {
+        let client = self.client.clone();
+        let fd = self.fd;
+        let data = Bytes::copy_from_slice(&buf[..cmp::min(buf.len(), CHUNK_SIZE)]);
+        self.state = State::Writing(Box::pin(async move {
+            let len = call(&client, LOWRITE, &[Some(&fd.to_be_bytes()), Some(&data)]).await?;
+            int4(&len).map(|len| len as usize)
+        }));
+    }
+
+    fn begin_seek(&mut self, offset: i64, whence: i32) {
+        let client = self.client.clone();
+        let fd = self.fd;
+        self.state = State::Seeking(Box::pin(async move {
+            let pos = call(
+                &client,
+                LO_LSEEK64,
+                &[
+                    Some(&fd.to_be_bytes()),
+                    Some(&offset.to_be_bytes()),
+                    Some(&whence.to_be_bytes()),
+                ],
+            )
+            .await?;
+            int8(&pos).map(|pos| pos as u64)
+        }));
+    }
+
+    // The position of the object as seen by the user, which lags the server's if buffered data hasn't been returned.
+    fn logical_pos(&self) -> u64 {
+        self.pos - self.buf.len() as u64
+    }
+
+    // Completes any in-flight request.
+    fn poll_idle(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        let r = match &mut self.state {
+            State::Idle => return Poll::Ready(Ok(())),
+            State::Reading(fut) => ready!(fut.as_mut().poll(cx)).map(|buf| {
+                self.pos += buf.len() as u64;
+                self.buf = buf;
+            }),
+            State::Writing(fut) => ready!(fut.as_mut().poll(cx)).map(|len| self.pos += len as u64),
+            State::Seeking(fut) => ready!(fut.as_mut().poll(cx)).map(|pos| self.pos = pos),
+        };
+        self.state = State::Idle;
+        Poll::Ready(r)
+    }
+}
+
+impl AsyncRead for LargeObject<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        loop {
+            if !this.buf.is_empty() {
+                let len = cmp::min(buf.len(), this.buf.len());
+                buf[..len].copy_from_slice(&this.buf[..len]);
+                this.buf.advance(len);
+                return Poll::Ready(Ok(len));
+            }
+
+            match this.state {
+                State::Idle if buf.is_empty() => return Poll::Ready(Ok(0)),
+                State::Idle => this.begin_read(buf.len()),
+                State::Reading(_) => {
+                    ready!(this.poll_idle(cx)).map_err(into_io)?;
+                    // the end of the object
+                    if this.buf.is_empty() {
+                        return Poll::Ready(Ok(0));
+                    }
+                }
+                _ => ready!(this.poll_idle(cx)).map_err(into_io)?,
+            }
+        }
+    }
+}
+
+impl AsyncWrite for LargeObject<'_> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        loop {
+            match this.state {
+                State::Idle if buf.is_empty() => return Poll::Ready(Ok(0)),
+                // move the server's cursor back to the data which was read but not returned
+                State::Idle if !this.buf.is_empty() => {
+                    let pos = this.logical_pos() as i64;
+                    this.buf.clear();
+                    this.begin_seek(pos, SEEK_SET);
+                }
+                State::Idle => this.begin_write(buf),
+                State::Writing(_) => {
+                    let pos = this.pos;
+                    ready!(this.poll_idle(cx)).map_err(into_io)?;
+                    return Poll::Ready(Ok((this.pos - pos) as usize));
+                }
+                _ => ready!(this.poll_idle(cx)).map_err(into_io)?,
+            }
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_idle(cx).map_err(into_io)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl AsyncSeek for LargeObject<'_> {
+    fn start_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        position: SeekFrom,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        ready!(this.poll_idle(cx)).map_err(into_io)?;
+
+        let (offset, whence) = match position {
+            SeekFrom::Start(offset) => (
+                i64::try_from(offset)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+                SEEK_SET,
+            ),
+            // seek relative to the user's position rather than the server's
+            SeekFrom::Current(offset) => {
+                let pos = i64::try_from(this.logical_pos())
+                    .ok()
+                    .and_then(|pos| pos.checked_add(offset))
+                    .filter(|&pos| pos >= 0)
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "invalid seek to a negative or overflowing position",
+                        )
+                    })?;
+                (pos, SEEK_SET)
+            }
+            SeekFrom::End(offset) => (offset, SEEK_END),
+        };
+        this.buf.clear();
+        this.begin_seek(offset, whence);
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        let this = &mut *self;
+        ready!(this.poll_idle(cx)).map_err(into_io)?;
+        Poll::Ready(Ok(this.logical_pos()))
+    }
+}
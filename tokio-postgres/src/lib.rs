@@ -94,8 +94,8 @@
 //!
 //! TLS support is implemented via external libraries. `Client::connect` and `Config::connect` take a TLS implementation
 //! as an argument. The `NoTls` type in this crate can be used when TLS is not required. Otherwise, the
-//! `postgres-openssl` and `postgres-native-tls` crates provide implementations backed by the `openssl` and `native-tls`
-//! crates, respectively.
+//! `postgres-openssl`, `postgres-native-tls` and `postgres-rustls` crates provide implementations backed by the
+//! `openssl`, `native-tls` and `rustls` crates, respectively.
 //!
 //! # Features
 //!
@@ -104,11 +104,13 @@
 //! | Feature | Description | Extra dependencies | Default |
 //! | ------- | ----------- | ------------------ | ------- |
 //! | `runtime` | Enable convenience API for the connection process based on the `tokio` crate. | [tokio](https://crates.io/crates/tokio) 0.2 with the features `dns`, `net` and `time` | yes |
+//! | `with-bigdecimal-0_2` | Enable support for the `bigdecimal` crate. | [bigdecimal](https://crates.io/crates/bigdecimal) 0.2 | no |
 //! | `with-bit-vec-0_6` | Enable support for the `bit-vec` crate. | [bit-vec](https://crates.io/crates/bit-vec) 0.6 | no |
 //! | `with-chrono-0_4` | Enable support for the `chrono` crate. | [chrono](https://crates.io/crates/chrono) 0.4 | no |
 //! | `with-eui48-0_4` | Enable support for the `eui48` crate. | [eui48](https://crates.io/crates/eui48) 0.4 | no |
 //! | `with-geo-types-0_4` | Enable support for the 0.4 version of the `geo-types` crate. | [geo-types](https://crates.io/crates/geo-types/0.4.0) 0.4 | no |
 //! | `with-geo-types-0_5` | Enable support for the 0.5 version of the `geo-types` crate. | [geo-types](https://crates.io/crates/geo-types/0.5.0) 0.5 | no |
+//! | `with-rust_decimal-1` | Enable support for the `rust_decimal` crate. | [rust_decimal](https://crates.io/crates/rust_decimal) 1.13 | no |
 //! | `with-serde_json-1` | Enable support for the `serde_json` crate. | [serde_json](https://crates.io/crates/serde_json) 1.0 | no |
 //! | `with-uuid-0_8` | Enable support for the `uuid` crate. | [uuid](https://crates.io/crates/uuid) 0.8 | no |
 //! | `with-time-0_2` | Enable support for the `time` crate. | [time](https://crates.io/crates/time) 0.2 | no |
@@ -119,11 +121,13 @@ pub use crate::cancel_token::CancelToken;
 pub use crate::client::Client;
 pub use crate::config::Config;
 pub use crate::connection::Connection;
+pub use crate::copy_both::CopyBothDuplex;
 pub use crate::copy_in::CopyInSink;
 pub use crate::copy_out::CopyOutStream;
 use crate::error::DbError;
 pub use crate::error::Error;
 pub use crate::generic_client::GenericClient;
+pub use crate::pipeline::{Pipeline, PipelineResponse, PipelineSync};
 pub use crate::portal::Portal;
 pub use crate::query::RowStream;
 pub use crate::row::{Row, SimpleQueryRow};
@@ -155,19 +159,29 @@ mod connect_raw;
 mod connect_socket;
 mod connect_tls;
 mod connection;
+mod copy_both;
 mod copy_in;
 mod copy_out;
 pub mod error;
+mod function_call;
 mod generic_client;
+pub mod large_object;
 mod maybe_tls_stream;
+mod password_file;
+mod pipeline;
 mod portal;
 mod prepare;
 mod query;
+#[cfg(feature = "runtime")]
+pub mod reconnect;
+pub mod replication;
 pub mod row;
+mod service_file;
 mod simple_query;
 #[cfg(feature = "runtime")]
 mod socket;
 mod statement;
+mod statement_cache;
 pub mod tls;
 mod to_statement;
 mod transaction;
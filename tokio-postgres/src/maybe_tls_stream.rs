@@ -107,4 +107,11 @@ where
             MaybeTlsStream::Tls(s) => s.channel_binding(),
         }
     }
+
+    fn alpn_protocol(&self) -> Option<Vec<u8>> {
+        match self {
+            MaybeTlsStream::Raw(_) => None,
+            MaybeTlsStream::Tls(s) => s.alpn_protocol(),
+        }
+    }
 }
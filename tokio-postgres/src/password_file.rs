@@ -0,0 +1,85 @@
+use log::warn;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Looks up a password in the password file, as specified by `PGPASSFILE` or in its default location.
+///
+/// Like libpq, problems reading the file are not errors, since the connection attempt can still proceed without a
+/// password.
+pub fn lookup(host: &str, port: u16, dbname: &str, user: &str) -> Option<Vec<u8>> {
+    let path = path()?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let metadata = fs::metadata(&path).ok()?;
+        if !metadata.is_file() {
+            return None;
+        }
+        if metadata.permissions().mode() & 0o077 != 0 {
+            warn!(
+                "password file {} has group or world access; permissions should be u=rw (0600) or less",
+                path.display(),
+            );
+            return None;
+        }
+    }
+
+    let contents = fs::read_to_string(&path).ok()?;
+    let port = port.to_string();
+    contents
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(parse_line)
+        .find(|entry| {
+            field_matches(&entry[0], host)
+                && field_matches(&entry[1], &port)
+                && field_matches(&entry[2], dbname)
+                && field_matches(&entry[3], user)
+        })
+        .map(|entry| entry[4].clone().into_bytes())
+}
+
+fn path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("PGPASSFILE") {
+        return Some(PathBuf::from(path));
+    }
+
+    if cfg!(windows) {
+        env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("postgresql/pgpass.conf"))
+    } else {
+        env::var_os("HOME").map(|dir| PathBuf::from(dir).join(".pgpass"))
+    }
+}
+
+fn field_matches(pattern: &str, value: &str) -> bool {
+    pattern == "*" || pattern == value
+}
+
+// Entries have the form `hostname:port:database:username:password`, where `:` and `\` can be escaped with `\`. Any
+// unescaped `:`s after the fourth are part of the password.
+fn parse_line(line: &str) -> Option<[String; 5]> {
+    let mut fields: [String; 5] = Default::default();
+    let mut field = 0;
+
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    fields[field].push(c);
+                }
+            }
+            ':' if field < 4 => field += 1,
+            c => fields[field].push(c),
+        }
+    }
+
+    if field == 4 {
+        Some(fields)
+    } else {
+        None
+    }
+}
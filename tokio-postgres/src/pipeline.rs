@@ -0,0 +1,263 @@
+use crate::client::{InnerClient, Responses};
+use crate::codec::FrontendMessage;
+use crate::connection::RequestMessages;
+use crate::query::{self, extract_row_affected};
+use crate::types::ToSql;
+use crate::{slice_iter, Client, Error, Row, Statement};
+use bytes::Bytes;
+use log::debug;
+use postgres_protocol::message::backend::Message;
+use postgres_protocol::message::frontend;
+
+/// How a pipeline synchronizes with the server.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PipelineSync {
+    /// A single `Sync` is sent after all of the statements in the pipeline.
+    ///
+    /// The statements run in a single implicit transaction (or in the current transaction, if one is open). If a
+    /// statement fails, the server skips all of the statements after it, and the effects of the statements before it
+    /// are rolled back when the implicit transaction ends, even though their results are `Ok`.
+    Single,
+    /// A `Sync` is sent after each statement in the pipeline.
+    ///
+    /// Outside of an explicit transaction, each statement runs in its own implicit transaction, so a failure only
+    /// affects the statement that failed.
+    PerStatement,
+}
+
+/// The result of a statement executed as part of a pipeline.
+#[derive(Debug)]
+pub struct PipelineResponse {
+    rows: Vec<Row>,
+    rows_affected: u64,
+}
+
+impl PipelineResponse {
+    /// Returns the rows returned by the statement.
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    /// Consumes the response, returning the rows returned by the statement.
+    pub fn into_rows(self) -> Vec<Row> {
+        self.rows
+    }
+
+    /// Returns the number of rows modified or returned by the statement.
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+}
+
+struct PipelineItem {
+    statement: Statement,
+    buf: Bytes,
+}
+
+/// A builder for a pipeline of statements sent to the server in a single write.
+///
+/// Each queued statement is bound and executed with the unnamed portal. Nothing is sent to the server until `run` is
+/// called.
+pub struct Pipeline<'a> {
+    client: &'a Client,
+    sync: PipelineSync,
+    items: Vec<PipelineItem>,
+}
+
+impl<'a> Pipeline<'a> {
+    pub(crate) fn new(client: &'a Client) -> Pipeline<'a> {
+        Pipeline {
+            client,
+            sync: PipelineSync::Single,
+            items: vec![],
+        }
+    }
+
+    /// Sets how the pipeline synchronizes with the server.
+    ///
+    /// Defaults to `PipelineSync::Single`.
+    pub fn sync(&mut self, sync: PipelineSync) -> &mut Pipeline<'a> {
+        self.sync = sync;
+        self
+    }
+
+    /// Queues the execution of a prepared statement with the specified parameters.
+    ///
+    /// The parameters are serialized immediately, so an error is returned if any of them fail to serialize.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of parameters provided does not match the number expected.
+    pub fn query(
+        &mut self,
+        statement: &Statement,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<&mut Pipeline<'a>, Error> {
+        let buf = self.client.inner().with_buf(|buf| {
+            query::encode_bind(statement, slice_iter(params), "", buf)?;
+            frontend::execute("", 0, buf).map_err(Error::encode)?;
+            Ok(buf.split().freeze())
+        })?;
+
+        self.items.push(PipelineItem {
+            statement: statement.clone(),
+            buf,
+        });
+        Ok(self)
+    }
+
+    /// Returns the number of statements queued in the pipeline.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Determines if the pipeline contains no statements.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Sends the queued statements to the server, returning a result for each of them in order.
+    ///
+    /// # Errors
+    ///
+    /// If the server reports an error for a statement in `PipelineSync::Single` mode, the statements after it are not
+    /// executed, and the results for them are errors.
+    ///
+    /// **Outside of an explicit transaction, the statements before the failed one are rolled back as well**, since
+    /// they all ran in the same implicit transaction. Their results are still `Ok` and contain what the server
+    /// reported while executing them, but none of their changes were committed. Check every result, not just the
+    /// last, before assuming that the pipeline's changes were applied. Inside an explicit transaction, the
+    /// transaction is left in the failed state and must be rolled back.
+    pub async fn run(self) -> Vec<Result<PipelineResponse, Error>> {
+        let client = self.client.inner();
+        debug!(
+            "executing pipeline of {} statements with {:?} sync",
+            self.items.len(),
+            self.sync
+        );
+
+        match self.sync {
+            PipelineSync::Single => run_single(client, self.items).await,
+            PipelineSync::PerStatement => run_per_statement(client, self.items).await,
+        }
+    }
+}
+
+async fn run_single(
+    client: &InnerClient,
+    items: Vec<PipelineItem>,
+) -> Vec<Result<PipelineResponse, Error>> {
+    if items.is_empty() {
+        return vec![];
+    }
+
+    let buf = client.with_buf(|buf| {
+        for item in &items {
+            buf.extend_from_slice(&item.buf);
+        }
+        frontend::sync(buf);
+        buf.split().freeze()
+    });
+
+    let mut responses = match client.send(RequestMessages::Single(FrontendMessage::Raw(buf))) {
+        Ok(responses) => responses,
+        Err(e) => {
+            let mut results = vec![Err(e)];
+            results.extend((1..items.len()).map(|_| Err(Error::aborted())));
+            return results;
+        }
+    };
+
+    let mut results = Vec::with_capacity(items.len());
+    let mut aborted = false;
+    for item in &items {
+        if aborted {
+            results.push(Err(Error::aborted()));
+            continue;
+        }
+        let result = read_response(client, &mut responses, &item.statement).await;
+        // the server only skips the rest of the pipeline after it reports an error, not after a client-side error
+        if let Err(ref e) = result {
+            aborted = e.code().is_some();
+        }
+        results.push(result);
+    }
+    results
+}
+
+async fn run_per_statement(
+    client: &InnerClient,
+    items: Vec<PipelineItem>,
+) -> Vec<Result<PipelineResponse, Error>> {
+    // Every request is queued before any responses are awaited so that the connection writes them all at once.
+    let mut requests = Vec::with_capacity(items.len());
+    for item in items {
+        let buf = client.with_buf(|buf| {
+            buf.extend_from_slice(&item.buf);
+            frontend::sync(buf);
+            buf.split().freeze()
+        });
+        let responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)));
+        requests.push((item.statement, responses));
+    }
+
+    let mut results = Vec::with_capacity(requests.len());
+    for (statement, responses) in requests {
+        let result = match responses {
+            Ok(mut responses) => read_response(client, &mut responses, &statement).await,
+            Err(e) => Err(e),
+        };
+        results.push(result);
+    }
+    results
+}
+
+async fn read_response(
+    client: &InnerClient,
+    responses: &mut Responses,
+    statement: &Statement,
+) -> Result<PipelineResponse, Error> {
+    match query::bind_response(responses, statement).await {
+        Ok(Message::BindComplete) => {}
+        Ok(_) => return Err(Error::unexpected_message()),
+        Err(e) => {
+            query::invalidate_on_plan_change(client, statement, &e);
+            return Err(e);
+        }
+    }
+
+    // The rest of the statement's response is consumed after a row fails to parse so that the responses to the
+    // statements after it can still be read.
+    let mut rows = vec![];
+    let mut error = None;
+    loop {
+        match responses.next().await? {
+            Message::DataRow(body) => {
+                if error.is_none() {
+                    match Row::new(statement.clone(), body) {
+                        Ok(row) => rows.push(row),
+                        Err(e) => error = Some(e),
+                    }
+                }
+            }
+            Message::CommandComplete(body) => {
+                if let Some(e) = error {
+                    return Err(e);
+                }
+                let rows_affected = extract_row_affected(&body)?;
+                return Ok(PipelineResponse {
+                    rows,
+                    rows_affected,
+                });
+            }
+            Message::EmptyQueryResponse => {
+                return Ok(PipelineResponse {
+                    rows,
+                    rows_affected: 0,
+                })
+            }
+            _ => return Err(Error::unexpected_message()),
+        }
+    }
+}
@@ -9,7 +9,7 @@ use bytes::Bytes;
 use fallible_iterator::FallibleIterator;
 use futures::{pin_mut, TryStreamExt};
 use log::debug;
-use postgres_protocol::message::backend::Message;
+use postgres_protocol::message::backend::{Message, RowDescriptionBody};
 use postgres_protocol::message::frontend;
 use std::future::Future;
 use std::pin::Pin;
@@ -56,6 +56,12 @@ AND attnum > 0
 ORDER BY attnum
 ";
 
+const TYPEINFO_MULTIRANGE_QUERY: &str = "\
+SELECT rngtypid
+FROM pg_catalog.pg_range
+WHERE rngmultitypid = $1
+";
+
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
 pub async fn prepare(
@@ -63,7 +69,11 @@ pub async fn prepare(
     query: &str,
     types: &[Type],
 ) -> Result<Statement, Error> {
-    let name = format!("s{}", NEXT_ID.fetch_add(1, Ordering::SeqCst));
+    let name = if client.named_statements() {
+        format!("s{}", NEXT_ID.fetch_add(1, Ordering::SeqCst))
+    } else {
+        String::new()
+    };
     let buf = encode(client, &name, query, types)?;
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
 
@@ -90,17 +100,43 @@ pub async fn prepare(
         parameters.push(type_);
     }
 
+    let columns = get_columns(client, row_description.as_ref()).await?;
+
+    if name.is_empty() {
+        Ok(Statement::unnamed(query.to_string(), parameters, columns))
+    } else {
+        Ok(Statement::new(&client, name, parameters, columns))
+    }
+}
+
+pub async fn get_columns(
+    client: &Arc<InnerClient>,
+    row_description: Option<&RowDescriptionBody>,
+) -> Result<Vec<Column>, Error> {
     let mut columns = vec![];
     if let Some(row_description) = row_description {
         let mut it = row_description.fields();
         while let Some(field) = it.next().map_err(Error::parse)? {
-            let type_ = get_type(&client, field.type_oid()).await?;
+            let type_ = get_type(client, field.type_oid()).await?;
             let column = Column::new(field.name().to_string(), type_);
             columns.push(column);
         }
     }
+    Ok(columns)
+}
+
+pub async fn prepare_cached(
+    client: &Arc<InnerClient>,
+    query: &str,
+    types: &[Type],
+) -> Result<Statement, Error> {
+    if let Some(statement) = client.cached_statement(query, types) {
+        return Ok(statement);
+    }
 
-    Ok(Statement::new(&client, name, parameters, columns))
+    let statement = prepare(client, query, types).await?;
+    client.set_cached_statement(query, types, &statement);
+    Ok(statement)
 }
 
 fn prepare_rec<'a>(
@@ -158,6 +194,9 @@ async fn get_type(client: &Arc<InnerClient>, oid: Oid) -> Result<Type, Error> {
         Kind::Enum(variants)
     } else if type_ == b'p' as i8 {
         Kind::Pseudo
+    } else if type_ == b'm' as i8 {
+        let type_ = get_multirange_range(client, oid).await?;
+        Kind::Multirange(type_)
     } else if basetype != 0 {
         let type_ = get_type_rec(client, basetype).await?;
         Kind::Domain(type_)
@@ -260,3 +299,28 @@ async fn typeinfo_composite_statement(client: &Arc<InnerClient>) -> Result<State
     client.set_typeinfo_composite(&stmt);
     Ok(stmt)
 }
+
+async fn get_multirange_range(client: &Arc<InnerClient>, oid: Oid) -> Result<Type, Error> {
+    let stmt = typeinfo_multirange_statement(client).await?;
+
+    let rows = query::query(client, stmt, slice_iter(&[&oid])).await?;
+    pin_mut!(rows);
+
+    let range_oid = match rows.try_next().await? {
+        Some(row) => row.try_get(0)?,
+        None => return Err(Error::unexpected_message()),
+    };
+
+    get_type_rec(client, range_oid).await
+}
+
+async fn typeinfo_multirange_statement(client: &Arc<InnerClient>) -> Result<Statement, Error> {
+    if let Some(stmt) = client.typeinfo_multirange() {
+        return Ok(stmt);
+    }
+
+    let stmt = prepare_rec(client, TYPEINFO_MULTIRANGE_QUERY, &[]).await?;
+
+    client.set_typeinfo_multirange(&stmt);
+    Ok(stmt)
+}
@@ -1,16 +1,19 @@
 use crate::client::{InnerClient, Responses};
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
-use crate::types::{IsNull, ToSql};
-use crate::{Error, Portal, Row, Statement};
+use crate::error::{DbError, SqlState};
+use crate::types::{IsNull, ToSql, Type};
+use crate::{prepare, Error, Portal, Row, Statement};
 use bytes::{Bytes, BytesMut};
 use futures::{ready, Stream};
 use log::{debug, log_enabled, Level};
 use pin_project_lite::pin_project;
-use postgres_protocol::message::backend::Message;
+use postgres_protocol::message::backend::{CommandCompleteBody, Message};
 use postgres_protocol::message::frontend;
+use std::error::Error as _;
 use std::marker::PhantomPinned;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
 pub async fn query<'a, I>(
@@ -33,7 +36,7 @@ where
     } else {
         encode(client, &statement, params)?
     };
-    let responses = start(client, buf).await?;
+    let responses = start(client, &statement, buf).await?;
     Ok(RowStream {
         statement,
         responses,
@@ -41,6 +44,61 @@ where
     })
 }
 
+pub async fn query_typed<'a, I>(
+    client: &Arc<InnerClient>,
+    query: &str,
+    params: I,
+) -> Result<RowStream, Error>
+where
+    I: IntoIterator<Item = (&'a dyn ToSql, Type)>,
+{
+    // The parameters aren't necessarily `Sync`, so they must be dropped before the first await.
+    let (buf, param_types) = {
+        let (params, param_types): (Vec<_>, Vec<_>) = params.into_iter().unzip();
+
+        debug!(
+            "executing typed query {} with parameters: {:?}",
+            query, params
+        );
+
+        let buf = client.with_buf(|buf| {
+            frontend::parse("", query, param_types.iter().map(Type::oid), buf)
+                .map_err(Error::encode)?;
+            encode_bind_raw("", params.iter().cloned().zip(param_types.iter()), "", buf)?;
+            frontend::describe(b'P', "", buf).map_err(Error::encode)?;
+            frontend::execute("", 0, buf).map_err(Error::encode)?;
+            frontend::sync(buf);
+            Ok(buf.split().freeze())
+        })?;
+        (buf, param_types)
+    };
+
+    let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+
+    match responses.next().await? {
+        Message::ParseComplete => {}
+        _ => return Err(Error::unexpected_message()),
+    }
+
+    match responses.next().await? {
+        Message::BindComplete => {}
+        _ => return Err(Error::unexpected_message()),
+    }
+
+    let row_description = match responses.next().await? {
+        Message::RowDescription(body) => Some(body),
+        Message::NoData => None,
+        _ => return Err(Error::unexpected_message()),
+    };
+    let columns = prepare::get_columns(client, row_description.as_ref()).await?;
+
+    Ok(RowStream {
+        statement: Statement::unnamed(query.to_string(), param_types, columns),
+        responses,
+        _p: PhantomPinned,
+    })
+}
+
 pub async fn query_portal(
     client: &InnerClient,
     portal: &Portal,
@@ -81,39 +139,81 @@ where
     } else {
         encode(client, &statement, params)?
     };
-    let mut responses = start(client, buf).await?;
+    let mut responses = start(client, &statement, buf).await?;
 
     loop {
         match responses.next().await? {
             Message::DataRow(_) => {}
-            Message::CommandComplete(body) => {
-                let rows = body
-                    .tag()
-                    .map_err(Error::parse)?
-                    .rsplit(' ')
-                    .next()
-                    .unwrap()
-                    .parse()
-                    .unwrap_or(0);
-                return Ok(rows);
-            }
+            Message::CommandComplete(body) => return extract_row_affected(&body),
             Message::EmptyQueryResponse => return Ok(0),
             _ => return Err(Error::unexpected_message()),
         }
     }
 }
 
-async fn start(client: &InnerClient, buf: Bytes) -> Result<Responses, Error> {
+pub fn extract_row_affected(body: &CommandCompleteBody) -> Result<u64, Error> {
+    let rows = body
+        .tag()
+        .map_err(Error::parse)?
+        .rsplit(' ')
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap_or(0);
+    Ok(rows)
+}
+
+async fn start(
+    client: &InnerClient,
+    statement: &Statement,
+    buf: Bytes,
+) -> Result<Responses, Error> {
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
 
-    match responses.next().await? {
-        Message::BindComplete => {}
-        _ => return Err(Error::unexpected_message()),
+    match bind_response(&mut responses, statement).await {
+        Ok(Message::BindComplete) => {}
+        Ok(_) => return Err(Error::unexpected_message()),
+        Err(e) => {
+            invalidate_on_plan_change(client, statement, &e);
+            return Err(e);
+        }
     }
 
     Ok(responses)
 }
 
+// A statement prepared as the unnamed statement is parsed again ahead of each bind, so the `ParseComplete` response
+// has to be skipped before reading the response to the bind itself.
+pub async fn bind_response(
+    responses: &mut Responses,
+    statement: &Statement,
+) -> Result<Message, Error> {
+    if statement.unnamed_query().is_some() {
+        match responses.next().await? {
+            Message::ParseComplete => {}
+            _ => return Err(Error::unexpected_message()),
+        }
+    }
+
+    responses.next().await
+}
+
+// The server refuses to execute a prepared statement whose result columns have changed since it was prepared (for
+// example, after an `ALTER TABLE`), so a cached copy of it has to be prepared again.
+pub fn invalidate_on_plan_change(client: &InnerClient, statement: &Statement, e: &Error) {
+    if e.code() != Some(&SqlState::FEATURE_NOT_SUPPORTED) {
+        return;
+    }
+
+    let routine = e
+        .source()
+        .and_then(|e| e.downcast_ref::<DbError>())
+        .and_then(DbError::routine);
+    if routine == Some("RevalidateCachedQuery") {
+        client.invalidate_cached_statement(statement);
+    }
+}
+
 pub fn encode<'a, I>(client: &InnerClient, statement: &Statement, params: I) -> Result<Bytes, Error>
 where
     I: IntoIterator<Item = &'a dyn ToSql>,
@@ -146,12 +246,35 @@ where
         params.len()
     );
 
+    if let Some(query) = statement.unnamed_query() {
+        frontend::parse("", query, statement.params().iter().map(Type::oid), buf)
+            .map_err(Error::encode)?;
+    }
+
+    encode_bind_raw(
+        statement.name(),
+        params.zip(statement.params()),
+        portal,
+        buf,
+    )
+}
+
+fn encode_bind_raw<'a, 'b, I>(
+    statement: &str,
+    params: I,
+    portal: &str,
+    buf: &mut BytesMut,
+) -> Result<(), Error>
+where
+    I: IntoIterator<Item = (&'a dyn ToSql, &'b Type)>,
+    I::IntoIter: ExactSizeIterator,
+{
     let mut error_idx = 0;
     let r = frontend::bind(
         portal,
-        statement.name(),
+        statement,
         Some(1),
-        params.zip(statement.params()).enumerate(),
+        params.into_iter().enumerate(),
         |(idx, (param, ty)), buf| match param.to_sql_checked(ty, buf) {
             Ok(IsNull::No) => Ok(postgres_protocol::IsNull::No),
             Ok(IsNull::Yes) => Ok(postgres_protocol::IsNull::Yes),
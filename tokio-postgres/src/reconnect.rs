@@ -0,0 +1,550 @@
+//! Clients which automatically reconnect to the database.
+//!
+//! A `ReconnectingClient` wraps a `Client`, replacing it with a new connection when the old one is lost, for example
+//! because the server restarted. Reconnection tries each host in the `Config` in turn, as `Config::connect` does,
+//! waiting with an exponential backoff between rounds of attempts.
+//!
+//! Each new connection is set up to match the last one: session parameters set with
+//! `ReconnectingClient::set_session_parameter` are set again, and if `Config::statement_cache_capacity` is nonzero, the
+//! statements in the old connection's cache are prepared again. Statements prepared directly by the user belong to
+//! the connection they were prepared on, and can't be used with a new one.
+//!
+//! Requests are never retried. A request which was sent over a connection that is then lost fails, since the client
+//! can't know whether the server executed it. Only requests which are made once the loss has been noticed wait for the
+//! new connection.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use tokio_postgres::reconnect::ReconnectOptions;
+//! use tokio_postgres::{Config, NoTls};
+//!
+//! # async fn f() -> Result<(), tokio_postgres::Error> {
+//! let config = "host=localhost user=postgres".parse::<Config>()?;
+//! let (client, connection) = ReconnectOptions::new().connect(&config, NoTls).await?;
+//!
+//! // The connection object drives the current connection, and reconnects when it's lost.
+//! tokio::spawn(connection);
+//!
+//! client.set_session_parameter("search_path", "app").await?;
+//! let rows = client.query("SELECT * FROM users", &[]).await?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::tls::{MakeTlsConnect, TlsConnect};
+use crate::types::{ToSql, Type};
+use crate::{
+    connect, prepare, AsyncMessage, Client, Config, Connection, Error, Row, SimpleQueryMessage,
+    Socket,
+};
+use futures::{future, ready, Future};
+use log::{debug, info, warn};
+use parking_lot::Mutex;
+use std::cmp;
+use std::fmt;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use tokio::time::{self, Delay};
+
+const SET_PARAMETER_QUERY: &str = "SELECT set_config($1, $2, false)";
+
+/// Options controlling how a `ReconnectingClient` reconnects.
+#[derive(Debug, Clone)]
+pub struct ReconnectOptions {
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectOptions {
+    fn default() -> ReconnectOptions {
+        ReconnectOptions::new()
+    }
+}
+
+impl ReconnectOptions {
+    /// Creates a new set of options with the default configuration.
+    pub fn new() -> ReconnectOptions {
+        ReconnectOptions {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            max_attempts: None,
+        }
+    }
+
+    /// Sets the time to wait after the first failed attempt to reconnect.
+    ///
+    /// The time doubles after each failed attempt, up to the maximum backoff. The first attempt is made immediately
+    /// after the connection is lost. Defaults to 100 milliseconds.
+    pub fn initial_backoff(&mut self, initial_backoff: Duration) -> &mut ReconnectOptions {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Sets the maximum time to wait between attempts to reconnect.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn max_backoff(&mut self, max_backoff: Duration) -> &mut ReconnectOptions {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Sets the number of consecutive failed attempts after which the client gives up on reconnecting.
+    ///
+    /// Each attempt tries every host in the configuration. Once the client has given up, all of its requests fail.
+    /// Defaults to no limit.
+    pub fn max_attempts(&mut self, max_attempts: Option<u32>) -> &mut ReconnectOptions {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Opens a connection to the database, returning a client which reconnects when it's lost.
+    ///
+    /// Errors opening the initial connection are returned rather than retried.
+    pub async fn connect<T>(
+        &self,
+        config: &Config,
+        tls: T,
+    ) -> Result<(ReconnectingClient, ReconnectingConnection<T>), Error>
+    where
+        T: MakeTlsConnect<Socket> + Clone + 'static + Send,
+        T::TlsConnect: Send,
+        T::Stream: Send,
+        <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+    {
+        let (client, connection) = connect::connect(tls.clone(), config).await?;
+
+        let shared = Arc::new(Mutex::new(Shared {
+            client: Some(Arc::new(client)),
+            closed: false,
+            parameters: vec![],
+            waiters: vec![],
+        }));
+
+        let connection = ReconnectingConnection {
+            shared: Arc::downgrade(&shared),
+            config: config.clone(),
+            tls,
+            options: self.clone(),
+            state: State::Connected(connection),
+            attempts: 0,
+        };
+
+        Ok((ReconnectingClient { shared }, connection))
+    }
+}
+
+/// An event reported by a `ReconnectingConnection`.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ReconnectEvent {
+    /// An asynchronous message from the server over the current connection.
+    Message(AsyncMessage),
+    /// The connection was lost with the given error. Requests which were in flight have failed.
+    Disconnected(Error),
+    /// An attempt to reconnect failed with the given error. Another attempt will be made after the given delay.
+    ///
+    /// Once the maximum number of attempts has been made, the error is returned from `poll_event` instead.
+    ReconnectFailed(Error, Duration),
+    /// A new connection was established, and the client's session parameters and cached statements were restored.
+    Reconnected,
+}
+
+struct Shared {
+    client: Option<Arc<Client>>,
+    closed: bool,
+    parameters: Vec<(String, String)>,
+    waiters: Vec<Waker>,
+}
+
+impl Shared {
+    fn wake(&mut self) {
+        for waker in self.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+/// A client which reconnects to the database when its connection is lost.
+///
+/// The client can be cheaply cloned, with each clone using the same connection. Its connection is closed once all
+/// clones have been dropped.
+#[derive(Clone)]
+pub struct ReconnectingClient {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl fmt::Debug for ReconnectingClient {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shared = self.shared.lock();
+        fmt.debug_struct("ReconnectingClient")
+            .field("connected", &shared.client.is_some())
+            .field("closed", &shared.closed)
+            .field("parameters", &shared.parameters)
+            .finish()
+    }
+}
+
+impl ReconnectingClient {
+    /// Returns the client for the current connection, waiting for a new connection if the last one has been lost.
+    ///
+    /// The returned client continues to refer to the same connection, so its requests fail once that connection has
+    /// been lost. Use the methods of this type to have each request use the current connection instead.
+    pub async fn client(&self) -> Result<Arc<Client>, Error> {
+        future::poll_fn(|cx| {
+            let mut shared = self.shared.lock();
+            if let Some(client) = &shared.client {
+                if !client.is_closed() {
+                    return Poll::Ready(Ok(client.clone()));
+                }
+            }
+            if shared.closed {
+                return Poll::Ready(Err(Error::closed()));
+            }
+            shared.waiters.push(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Like `Client::query`.
+    pub async fn query(
+        &self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<Row>, Error> {
+        self.client().await?.query(query, params).await
+    }
+
+    /// Like `Client::query_one`.
+    pub async fn query_one(
+        &self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Row, Error> {
+        self.client().await?.query_one(query, params).await
+    }
+
+    /// Like `Client::query_opt`.
+    pub async fn query_opt(
+        &self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Option<Row>, Error> {
+        self.client().await?.query_opt(query, params).await
+    }
+
+    /// Like `Client::execute`.
+    pub async fn execute(&self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<u64, Error> {
+        self.client().await?.execute(query, params).await
+    }
+
+    /// Like `Client::simple_query`.
+    pub async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
+        self.client().await?.simple_query(query).await
+    }
+
+    /// Like `Client::batch_execute`.
+    pub async fn batch_execute(&self, query: &str) -> Result<(), Error> {
+        self.client().await?.batch_execute(query).await
+    }
+
+    /// Sets a session parameter, recording it so that it is set again on each new connection.
+    ///
+    /// This is equivalent to `SET name = value`, but parameters set directly with `SET` are lost when the connection
+    /// is.
+    pub async fn set_session_parameter(&self, name: &str, value: &str) -> Result<(), Error> {
+        self.execute(SET_PARAMETER_QUERY, &[&name, &value]).await?;
+
+        let mut shared = self.shared.lock();
+        match shared.parameters.iter_mut().find(|(n, _)| n == name) {
+            Some(parameter) => parameter.1 = value.to_string(),
+            None => shared
+                .parameters
+                .push((name.to_string(), value.to_string())),
+        }
+        Ok(())
+    }
+}
+
+type ConnectFuture<T> =
+    Pin<Box<dyn Future<Output = Result<(Client, Connection<Socket, T>), Error>> + Send + 'static>>;
+type InitializeFuture = Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'static>>;
+
+enum State<T> {
+    Connected(Connection<Socket, T>),
+    Connecting {
+        future: ConnectFuture<T>,
+        statements: Vec<(String, Vec<Type>)>,
+    },
+    Initializing {
+        client: Arc<Client>,
+        connection: Connection<Socket, T>,
+        future: InitializeFuture,
+        statements: Vec<(String, Vec<Type>)>,
+    },
+    Waiting {
+        delay: Delay,
+        statements: Vec<(String, Vec<Type>)>,
+    },
+    Done,
+}
+
+/// A connection which reconnects to the database when it's lost.
+///
+/// Like `Connection`, this drives the communication with the server, and is typically spawned off onto an executor.
+/// It resolves once all of the clients using it have been dropped, or with an error once it gives up on reconnecting.
+#[must_use = "futures do nothing unless polled"]
+pub struct ReconnectingConnection<T>
+where
+    T: MakeTlsConnect<Socket>,
+{
+    shared: Weak<Mutex<Shared>>,
+    config: Config,
+    tls: T,
+    options: ReconnectOptions,
+    state: State<T::Stream>,
+    attempts: u32,
+}
+
+impl<T> ReconnectingConnection<T>
+where
+    T: MakeTlsConnect<Socket> + Clone + 'static + Send,
+    T::TlsConnect: Send,
+    T::Stream: Send,
+    <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    fn connect(&self, statements: Vec<(String, Vec<Type>)>) -> State<T::Stream> {
+        let config = self.config.clone();
+        let tls = self.tls.clone();
+        State::Connecting {
+            future: Box::pin(async move { connect::connect(tls, &config).await }),
+            statements,
+        }
+    }
+
+    fn backoff(&self) -> Duration {
+        let mut backoff = self.options.initial_backoff;
+        for _ in 1..self.attempts {
+            backoff = cmp::min(backoff * 2, self.options.max_backoff);
+        }
+        cmp::min(backoff, self.options.max_backoff)
+    }
+
+    fn close(&mut self) {
+        self.state = State::Done;
+        if let Some(shared) = self.shared.upgrade() {
+            let mut shared = shared.lock();
+            shared.client = None;
+            shared.closed = true;
+            shared.wake();
+        }
+    }
+
+    fn reconnect_failed(
+        &mut self,
+        e: Error,
+        statements: Vec<(String, Vec<Type>)>,
+    ) -> Poll<Option<Result<ReconnectEvent, Error>>> {
+        self.attempts += 1;
+        if let Some(max_attempts) = self.options.max_attempts {
+            if self.attempts >= max_attempts {
+                self.close();
+                return Poll::Ready(Some(Err(e)));
+            }
+        }
+
+        let backoff = self.backoff();
+        self.state = State::Waiting {
+            delay: time::delay_for(backoff),
+            statements,
+        };
+        Poll::Ready(Some(Ok(ReconnectEvent::ReconnectFailed(e, backoff))))
+    }
+
+    /// Polls for events from the connection.
+    ///
+    /// This drives the current connection and any reconnection attempts, and is an alternative to polling the
+    /// connection as a future. The stream ends once all of the clients using the connection have been dropped. An
+    /// error is returned if the connection gives up on reconnecting.
+    pub fn poll_event(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<ReconnectEvent, Error>>> {
+        loop {
+            match &mut self.state {
+                State::Connected(connection) => match ready!(connection.poll_message(cx)) {
+                    Some(Ok(message)) => {
+                        return Poll::Ready(Some(Ok(ReconnectEvent::Message(message))))
+                    }
+                    Some(Err(e)) => {
+                        let shared = match self.shared.upgrade() {
+                            Some(shared) => shared,
+                            None => {
+                                self.state = State::Done;
+                                return Poll::Ready(None);
+                            }
+                        };
+                        // The old client is dropped after the lock is released, since that may close statements.
+                        let client = shared.lock().client.take();
+                        let statements = match &client {
+                            Some(client) => client.inner().cached_queries(),
+                            None => vec![],
+                        };
+                        drop(client);
+
+                        debug!("connection lost, reconnecting: {}", e);
+                        self.attempts = 0;
+                        self.state = self.connect(statements);
+                        return Poll::Ready(Some(Ok(ReconnectEvent::Disconnected(e))));
+                    }
+                    None => {
+                        self.state = State::Done;
+                        return Poll::Ready(None);
+                    }
+                },
+                State::Connecting { future, statements } => {
+                    let r = ready!(future.as_mut().poll(cx));
+                    let statements = mem::take(statements);
+                    let shared = match self.shared.upgrade() {
+                        Some(shared) => shared,
+                        None => {
+                            self.state = State::Done;
+                            return Poll::Ready(None);
+                        }
+                    };
+
+                    match r {
+                        Ok((client, connection)) => {
+                            let client = Arc::new(client);
+                            let parameters = shared.lock().parameters.clone();
+                            self.state = State::Initializing {
+                                client: client.clone(),
+                                connection,
+                                future: Box::pin(initialize(
+                                    client,
+                                    parameters,
+                                    statements.clone(),
+                                )),
+                                statements,
+                            };
+                        }
+                        Err(e) => return self.reconnect_failed(e, statements),
+                    }
+                }
+                State::Initializing {
+                    client,
+                    connection,
+                    future,
+                    statements,
+                } => {
+                    // The new connection has to be driven while it's being initialized.
+                    if let Poll::Ready(message) = connection.poll_message(cx) {
+                        match message {
+                            Some(Ok(message)) => {
+                                return Poll::Ready(Some(Ok(ReconnectEvent::Message(message))))
+                            }
+                            Some(Err(e)) => {
+                                let statements = mem::take(statements);
+                                return self.reconnect_failed(e, statements);
+                            }
+                            None => {
+                                self.state = State::Done;
+                                return Poll::Ready(None);
+                            }
+                        }
+                    }
+
+                    match ready!(future.as_mut().poll(cx)) {
+                        Ok(()) => {
+                            let client = client.clone();
+                            let shared = match self.shared.upgrade() {
+                                Some(shared) => shared,
+                                None => {
+                                    self.state = State::Done;
+                                    return Poll::Ready(None);
+                                }
+                            };
+
+                            let state = mem::replace(&mut self.state, State::Done);
+                            if let State::Initializing { connection, .. } = state {
+                                self.state = State::Connected(connection);
+                            }
+                            self.attempts = 0;
+
+                            let mut shared = shared.lock();
+                            shared.client = Some(client);
+                            shared.wake();
+                            return Poll::Ready(Some(Ok(ReconnectEvent::Reconnected)));
+                        }
+                        Err(e) => {
+                            let statements = mem::take(statements);
+                            return self.reconnect_failed(e, statements);
+                        }
+                    }
+                }
+                State::Waiting { delay, statements } => {
+                    ready!(Pin::new(delay).poll(cx));
+                    let statements = mem::take(statements);
+                    if self.shared.upgrade().is_none() {
+                        self.state = State::Done;
+                        return Poll::Ready(None);
+                    }
+                    self.state = self.connect(statements);
+                }
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl<T> Future for ReconnectingConnection<T>
+where
+    T: MakeTlsConnect<Socket> + Clone + 'static + Send + Unpin,
+    T::TlsConnect: Send,
+    T::Stream: Send,
+    <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    type Output = Result<(), Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        while let Some(event) = ready!(self.poll_event(cx)?) {
+            match event {
+                ReconnectEvent::Message(AsyncMessage::Notice(notice)) => {
+                    info!("{}: {}", notice.severity(), notice.message())
+                }
+                ReconnectEvent::Disconnected(e) => warn!("connection lost: {}", e),
+                ReconnectEvent::ReconnectFailed(e, backoff) => {
+                    warn!("error reconnecting, retrying in {:?}: {}", backoff, e)
+                }
+                ReconnectEvent::Reconnected => info!("reconnected"),
+                _ => {}
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+async fn initialize(
+    client: Arc<Client>,
+    parameters: Vec<(String, String)>,
+    statements: Vec<(String, Vec<Type>)>,
+) -> Result<(), Error> {
+    for (name, value) in &parameters {
+        client.execute(SET_PARAMETER_QUERY, &[name, value]).await?;
+    }
+
+    // A statement may no longer be valid on the new connection if the schema has changed, in which case the error is
+    // left to be reported when it's next used.
+    for (query, types) in &statements {
+        if let Err(e) = prepare::prepare_cached(client.inner(), query, types).await {
+            debug!("error preparing cached statement: {}", e);
+        }
+    }
+
+    Ok(())
+}
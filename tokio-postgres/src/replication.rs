@@ -0,0 +1,887 @@
+//! Streaming replication support.
+//!
+//! Replication connections are opened by setting the `replication` connection parameter (see
+//! [`Config::replication_mode`]). Once a replication stream has been started, the server sends a continuous stream of
+//! WAL data and keepalive messages, and the client periodically reports its progress back with standby status
+//! updates.
+//!
+//! [`Config::replication_mode`]: ../config/struct.Config.html#method.replication_mode
+
+use crate::client::{InnerClient, Responses};
+use crate::codec::FrontendMessage;
+use crate::connection::RequestMessages;
+use crate::types::{PgLsn, WasNull};
+use crate::{copy_both, simple_query, CopyBothDuplex, Error, SimpleQueryMessage, SimpleQueryRow};
+use byteorder::{BigEndian, ByteOrder};
+use bytes::{Bytes, BytesMut};
+use fallible_iterator::FallibleIterator;
+use futures::{pin_mut, ready, Sink, SinkExt, Stream, TryStreamExt};
+use log::debug;
+use pin_project_lite::pin_project;
+use postgres_protocol::message::backend::Message;
+use postgres_protocol::message::frontend;
+use std::error;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::str::{self, FromStr};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[doc(inline)]
+pub use postgres_protocol::message::backend::{
+    BeginBody, Column, CommitBody, DeleteBody, InsertBody, LogicalReplicationMessage, OriginBody,
+    PrimaryKeepAliveBody, RelationBody, ReplicaIdentity, ReplicationMessage, TruncateBody, Tuple,
+    TupleData, TypeBody, UpdateBody, XLogDataBody,
+};
+
+// Number of seconds from 1970-01-01 to 2000-01-01
+const TIME_SEC_CONVERSION: u64 = 946_684_800;
+
+pin_project! {
+    /// A stream of raw replication messages.
+    ///
+    /// This can be used with both physical and logical replication; the payload of each `XLogData` message is left
+    /// undecoded. Dropping the stream ends replication.
+    pub struct ReplicationStream {
+        #[pin]
+        stream: CopyBothDuplex<Bytes>,
+    }
+}
+
+impl ReplicationStream {
+    pub(crate) fn new(stream: CopyBothDuplex<Bytes>) -> ReplicationStream {
+        ReplicationStream { stream }
+    }
+
+    /// Sends a standby status update to the server, reporting the WAL positions written, flushed, and applied by the
+    /// client.
+    ///
+    /// If `reply` is true, the server will immediately respond with a keepalive message.
+    pub async fn standby_status_update(
+        self: Pin<&mut Self>,
+        write_lsn: PgLsn,
+        flush_lsn: PgLsn,
+        apply_lsn: PgLsn,
+        reply: bool,
+    ) -> Result<(), Error> {
+        let mut this = self.project();
+
+        let buf = encode_standby_status_update(write_lsn, flush_lsn, apply_lsn, reply);
+        this.stream.send(buf).await
+    }
+
+    fn poll_send(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: Bytes,
+    ) -> Poll<Result<(), Error>> {
+        let mut this = self.project();
+
+        ready!(this.stream.as_mut().poll_ready(cx))?;
+        this.stream.start_send(buf)?;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Stream for ReplicationStream {
+    type Item = Result<ReplicationMessage<Bytes>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        match ready!(this.stream.poll_next(cx)) {
+            Some(Ok(buf)) => {
+                Poll::Ready(Some(ReplicationMessage::parse(&buf).map_err(Error::parse)))
+            }
+            Some(Err(err)) => Poll::Ready(Some(Err(err))),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+pin_project! {
+    /// A stream of logical replication messages decoded from the `pgoutput` plugin's format.
+    ///
+    /// Dropping the stream ends replication.
+    pub struct LogicalReplicationStream {
+        #[pin]
+        stream: ReplicationStream,
+    }
+}
+
+impl LogicalReplicationStream {
+    pub(crate) fn new(stream: CopyBothDuplex<Bytes>) -> LogicalReplicationStream {
+        LogicalReplicationStream {
+            stream: ReplicationStream::new(stream),
+        }
+    }
+
+    /// Sends a standby status update to the server, reporting the WAL positions written, flushed, and applied by the
+    /// client.
+    ///
+    /// If `reply` is true, the server will immediately respond with a keepalive message.
+    pub async fn standby_status_update(
+        self: Pin<&mut Self>,
+        write_lsn: PgLsn,
+        flush_lsn: PgLsn,
+        apply_lsn: PgLsn,
+        reply: bool,
+    ) -> Result<(), Error> {
+        self.project()
+            .stream
+            .standby_status_update(write_lsn, flush_lsn, apply_lsn, reply)
+            .await
+    }
+}
+
+impl Stream for LogicalReplicationStream {
+    type Item = Result<ReplicationMessage<LogicalReplicationMessage>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        match ready!(this.stream.poll_next(cx)) {
+            Some(Ok(ReplicationMessage::XLogData(body))) => {
+                let body = body
+                    .map_data(|buf| LogicalReplicationMessage::parse(&buf))
+                    .map_err(Error::parse)?;
+                Poll::Ready(Some(Ok(ReplicationMessage::XLogData(body))))
+            }
+            Some(Ok(ReplicationMessage::PrimaryKeepAlive(body))) => {
+                Poll::Ready(Some(Ok(ReplicationMessage::PrimaryKeepAlive(body))))
+            }
+            Some(Ok(_)) => Poll::Ready(Some(Err(Error::unexpected_message()))),
+            Some(Err(err)) => Poll::Ready(Some(Err(err))),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+pin_project! {
+    /// A stream of WAL data from a physical replication connection.
+    ///
+    /// Keepalive messages from the server are handled internally: if the server requests a reply, a standby status
+    /// update is sent reporting the end of the WAL received so far as written, and the position last passed to
+    /// `set_flush_lsn` as flushed. Dropping the stream ends replication.
+    pub struct PhysicalReplicationStream {
+        #[pin]
+        stream: ReplicationStream,
+        write_lsn: PgLsn,
+        flush_lsn: PgLsn,
+        reply: Option<Bytes>,
+    }
+}
+
+impl PhysicalReplicationStream {
+    fn new(stream: CopyBothDuplex<Bytes>, start_lsn: PgLsn) -> PhysicalReplicationStream {
+        PhysicalReplicationStream {
+            stream: ReplicationStream::new(stream),
+            write_lsn: start_lsn,
+            flush_lsn: PgLsn::from(0),
+            reply: None,
+        }
+    }
+
+    /// Returns the end of the WAL received so far.
+    pub fn write_lsn(&self) -> PgLsn {
+        self.write_lsn
+    }
+
+    /// Sets the WAL position which has been durably stored by the client.
+    ///
+    /// This is reported to the server in subsequent standby status updates.
+    pub fn set_flush_lsn(self: Pin<&mut Self>, lsn: PgLsn) {
+        *self.project().flush_lsn = lsn;
+    }
+
+    /// Sends a standby status update to the server immediately.
+    ///
+    /// If `reply` is true, the server will respond with a keepalive message.
+    pub async fn standby_status_update(self: Pin<&mut Self>, reply: bool) -> Result<(), Error> {
+        let this = self.project();
+        this.stream
+            .standby_status_update(*this.write_lsn, *this.flush_lsn, PgLsn::from(0), reply)
+            .await
+    }
+}
+
+impl Stream for PhysicalReplicationStream {
+    type Item = Result<XLogDataBody<Bytes>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(buf) = this.reply.take() {
+                match this.stream.as_mut().poll_send(cx, buf.clone()) {
+                    Poll::Ready(Ok(())) => {}
+                    Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    Poll::Pending => {
+                        *this.reply = Some(buf);
+                        return Poll::Pending;
+                    }
+                }
+            }
+
+            match ready!(this.stream.as_mut().poll_next(cx)) {
+                Some(Ok(ReplicationMessage::XLogData(body))) => {
+                    let end = PgLsn::from(body.wal_start() + body.data().len() as u64);
+                    if end > *this.write_lsn {
+                        *this.write_lsn = end;
+                    }
+                    return Poll::Ready(Some(Ok(body)));
+                }
+                Some(Ok(ReplicationMessage::PrimaryKeepAlive(body))) => {
+                    if body.reply() == 1 {
+                        *this.reply = Some(encode_standby_status_update(
+                            *this.write_lsn,
+                            *this.flush_lsn,
+                            PgLsn::from(0),
+                            false,
+                        ));
+                    }
+                }
+                Some(Ok(_)) => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// The response to an `IDENTIFY_SYSTEM` command.
+#[derive(Debug, Clone)]
+pub struct IdentifySystem {
+    systemid: String,
+    timeline: u32,
+    xlogpos: PgLsn,
+    dbname: Option<String>,
+}
+
+impl IdentifySystem {
+    /// Returns the unique identifier of the database cluster.
+    pub fn systemid(&self) -> &str {
+        &self.systemid
+    }
+
+    /// Returns the current timeline ID.
+    pub fn timeline(&self) -> u32 {
+        self.timeline
+    }
+
+    /// Returns the current WAL flush location.
+    pub fn xlogpos(&self) -> PgLsn {
+        self.xlogpos
+    }
+
+    /// Returns the database connected to, if the connection is a logical replication connection.
+    pub fn dbname(&self) -> Option<&str> {
+        self.dbname.as_deref()
+    }
+}
+
+/// The response to a `TIMELINE_HISTORY` command.
+#[derive(Debug, Clone)]
+pub struct TimelineHistory {
+    filename: String,
+    content: Vec<u8>,
+}
+
+impl TimelineHistory {
+    /// Returns the file name of the timeline history file.
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Returns the contents of the timeline history file.
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+}
+
+/// The response to a `CREATE_REPLICATION_SLOT` command.
+#[derive(Debug, Clone)]
+pub struct CreateReplicationSlotResponse {
+    slot_name: String,
+    consistent_point: PgLsn,
+    snapshot_name: Option<String>,
+    output_plugin: Option<String>,
+}
+
+impl CreateReplicationSlotResponse {
+    /// Returns the name of the slot.
+    pub fn slot_name(&self) -> &str {
+        &self.slot_name
+    }
+
+    /// Returns the WAL location at which the slot became consistent.
+    ///
+    /// This is the earliest location from which streaming can start on the slot.
+    pub fn consistent_point(&self) -> PgLsn {
+        self.consistent_point
+    }
+
+    /// Returns the name of the snapshot exported by the command, if any.
+    pub fn snapshot_name(&self) -> Option<&str> {
+        self.snapshot_name.as_deref()
+    }
+
+    /// Returns the name of the output plugin used by the slot, if it is a logical slot.
+    pub fn output_plugin(&self) -> Option<&str> {
+        self.output_plugin.as_deref()
+    }
+}
+
+/// How a snapshot created along with a logical replication slot is handled.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SnapshotAction {
+    /// Export the snapshot for use by other sessions.
+    Export,
+    /// Don't export the snapshot.
+    NoExport,
+    /// Use the snapshot in the current transaction.
+    ///
+    /// The command must be executed inside of a `REPEATABLE READ` transaction which has not run any queries yet.
+    Use,
+}
+
+/// Options for a `BASE_BACKUP` command.
+#[derive(Debug, Clone, Default)]
+pub struct BaseBackupOptions {
+    label: Option<String>,
+    progress: bool,
+    fast: bool,
+    wal: bool,
+    wait: bool,
+    max_rate: Option<u32>,
+    tablespace_map: bool,
+}
+
+impl BaseBackupOptions {
+    /// Creates a new set of options with the server's defaults.
+    pub fn new() -> BaseBackupOptions {
+        BaseBackupOptions {
+            wait: true,
+            ..BaseBackupOptions::default()
+        }
+    }
+
+    /// Sets the label of the backup.
+    pub fn label(&mut self, label: &str) -> &mut BaseBackupOptions {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Requests that the server report the progress of the backup.
+    pub fn progress(&mut self, progress: bool) -> &mut BaseBackupOptions {
+        self.progress = progress;
+        self
+    }
+
+    /// Requests a fast checkpoint at the start of the backup rather than a spread out one.
+    pub fn fast(&mut self, fast: bool) -> &mut BaseBackupOptions {
+        self.fast = fast;
+        self
+    }
+
+    /// Requests that the WAL needed to restore the backup be included in the main archive.
+    pub fn wal(&mut self, wal: bool) -> &mut BaseBackupOptions {
+        self.wal = wal;
+        self
+    }
+
+    /// Sets whether the server will wait for the WAL generated during the backup to be archived before ending it.
+    ///
+    /// Defaults to `true`.
+    pub fn wait(&mut self, wait: bool) -> &mut BaseBackupOptions {
+        self.wait = wait;
+        self
+    }
+
+    /// Limits the rate at which the server sends data, in kilobytes per second.
+    pub fn max_rate(&mut self, max_rate: u32) -> &mut BaseBackupOptions {
+        self.max_rate = Some(max_rate);
+        self
+    }
+
+    /// Requests that a `tablespace_map` file be included in the main archive.
+    pub fn tablespace_map(&mut self, tablespace_map: bool) -> &mut BaseBackupOptions {
+        self.tablespace_map = tablespace_map;
+        self
+    }
+}
+
+/// A WAL location and timeline reported at the start or end of a base backup.
+#[derive(Debug, Copy, Clone)]
+pub struct BackupPosition {
+    lsn: PgLsn,
+    timeline: u32,
+}
+
+impl BackupPosition {
+    /// Returns the WAL location.
+    pub fn lsn(&self) -> PgLsn {
+        self.lsn
+    }
+
+    /// Returns the timeline ID.
+    pub fn timeline(&self) -> u32 {
+        self.timeline
+    }
+}
+
+/// A tablespace included in a base backup.
+#[derive(Debug, Clone)]
+pub struct Tablespace {
+    oid: Option<u32>,
+    location: Option<String>,
+    size: Option<i64>,
+}
+
+impl Tablespace {
+    /// Returns the OID of the tablespace, or `None` for the main data directory.
+    pub fn oid(&self) -> Option<u32> {
+        self.oid
+    }
+
+    /// Returns the path of the tablespace's directory, or `None` for the main data directory.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// Returns the estimated size of the tablespace in kilobytes, if progress reporting was requested.
+    pub fn size(&self) -> Option<i64> {
+        self.size
+    }
+}
+
+/// A tar archive sent as part of a base backup.
+#[derive(Debug, Clone)]
+pub struct Archive {
+    name: String,
+    location: Option<String>,
+}
+
+impl Archive {
+    /// Returns the name of the archive, for example `base.tar`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path of the tablespace stored in the archive, or `None` for the main data directory.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+}
+
+/// A message returned by a `BaseBackupStream`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BaseBackupMessage {
+    /// The backup has started at the specified position.
+    Start(BackupPosition),
+    /// A tablespace which will be included in the backup.
+    Tablespace(Tablespace),
+    /// The start of a new archive.
+    ///
+    /// The `Data` messages which follow contain the archive's contents.
+    Archive(Archive),
+    /// A chunk of the current archive's contents.
+    Data(Bytes),
+    /// The number of bytes of the backup which have been sent so far.
+    ///
+    /// This is only reported by servers running PostgreSQL 15 or newer, and only if progress reporting was requested.
+    Progress(u64),
+    /// The backup has ended at the specified position.
+    End(BackupPosition),
+}
+
+pin_project! {
+    /// A stream of the contents of a base backup.
+    pub struct BaseBackupStream {
+        responses: Responses,
+        legacy: bool,
+        columns: Option<Arc<[String]>>,
+        result_sets: usize,
+        tablespaces: Vec<Tablespace>,
+        archives: usize,
+        #[pin]
+        _p: PhantomPinned,
+    }
+}
+
+impl Stream for BaseBackupStream {
+    type Item = Result<BaseBackupMessage, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        loop {
+            match ready!(this.responses.poll_next(cx)?) {
+                Message::RowDescription(body) => {
+                    let columns = body
+                        .fields()
+                        .map(|f| Ok(f.name().to_string()))
+                        .collect::<Vec<_>>()
+                        .map_err(Error::parse)?
+                        .into();
+                    *this.columns = Some(columns);
+                    *this.result_sets += 1;
+                }
+                Message::DataRow(body) => {
+                    let row = match &this.columns {
+                        Some(columns) => SimpleQueryRow::new(columns.clone(), body)?,
+                        None => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+                    };
+                    let message = match *this.result_sets {
+                        1 => BaseBackupMessage::Start(parse_backup_position(&row)?),
+                        2 => {
+                            let tablespace = Tablespace {
+                                oid: parse_nullable_column(&row, 0)?,
+                                location: row.try_get(1)?.map(str::to_string),
+                                size: parse_nullable_column(&row, 2)?,
+                            };
+                            this.tablespaces.push(tablespace.clone());
+                            BaseBackupMessage::Tablespace(tablespace)
+                        }
+                        3 => BaseBackupMessage::End(parse_backup_position(&row)?),
+                        _ => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+                    };
+                    return Poll::Ready(Some(Ok(message)));
+                }
+                // Servers before PostgreSQL 15 send each tablespace as a separate COPY in the order they were listed.
+                Message::CopyOutResponse(_) if *this.legacy => {
+                    let tablespace = match this.tablespaces.get(*this.archives) {
+                        Some(tablespace) => tablespace,
+                        None => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+                    };
+                    *this.archives += 1;
+                    let name = match tablespace.oid {
+                        Some(oid) => format!("{}.tar", oid),
+                        None => "base.tar".to_string(),
+                    };
+                    let archive = Archive {
+                        name,
+                        location: tablespace.location.clone(),
+                    };
+                    return Poll::Ready(Some(Ok(BaseBackupMessage::Archive(archive))));
+                }
+                Message::CopyData(body) if *this.legacy => {
+                    return Poll::Ready(Some(Ok(BaseBackupMessage::Data(body.into_bytes()))));
+                }
+                Message::CopyData(body) => {
+                    let message = parse_copy_stream_message(body.into_bytes())?;
+                    return Poll::Ready(Some(Ok(message)));
+                }
+                Message::CopyOutResponse(_) | Message::CopyDone | Message::CommandComplete(_) => {}
+                Message::ReadyForQuery(_) => return Poll::Ready(None),
+                _ => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+            }
+        }
+    }
+}
+
+// Since PostgreSQL 15, the whole backup is sent as a single COPY in which each message is tagged with its type.
+fn parse_copy_stream_message(mut buf: Bytes) -> Result<BaseBackupMessage, Error> {
+    if buf.is_empty() {
+        return Err(Error::unexpected_message());
+    }
+    let tag = buf.split_to(1)[0];
+
+    match tag {
+        b'n' => {
+            let mut fields = buf.split(|b| *b == 0);
+            let name = fields.next().unwrap_or(&[]);
+            let location = fields.next().ok_or_else(Error::unexpected_message)?;
+            let name = str::from_utf8(name).map_err(|_| Error::unexpected_message())?;
+            let location = str::from_utf8(location).map_err(|_| Error::unexpected_message())?;
+            Ok(BaseBackupMessage::Archive(Archive {
+                name: name.to_string(),
+                location: if location.is_empty() {
+                    None
+                } else {
+                    Some(location.to_string())
+                },
+            }))
+        }
+        b'd' => Ok(BaseBackupMessage::Data(buf)),
+        b'p' if buf.len() == 8 => Ok(BaseBackupMessage::Progress(BigEndian::read_u64(&buf))),
+        _ => Err(Error::unexpected_message()),
+    }
+}
+
+fn parse_backup_position(row: &SimpleQueryRow) -> Result<BackupPosition, Error> {
+    Ok(BackupPosition {
+        lsn: parse_column(row, 0)?,
+        timeline: parse_column(row, 1)?,
+    })
+}
+
+fn parse_column<T>(row: &SimpleQueryRow, idx: usize) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: error::Error + Sync + Send + 'static,
+{
+    match parse_nullable_column(row, idx)? {
+        Some(value) => Ok(value),
+        None => Err(Error::from_sql(Box::new(WasNull), idx)),
+    }
+}
+
+fn parse_nullable_column<T>(row: &SimpleQueryRow, idx: usize) -> Result<Option<T>, Error>
+where
+    T: FromStr,
+    T::Err: error::Error + Sync + Send + 'static,
+{
+    match row.try_get(idx)? {
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|e| Error::from_sql(Box::new(e), idx)),
+        None => Ok(None),
+    }
+}
+
+async fn simple_query_row(client: &InnerClient, query: &str) -> Result<SimpleQueryRow, Error> {
+    let stream = simple_query::simple_query(client, query).await?;
+    pin_mut!(stream);
+
+    let mut row = None;
+    while let Some(message) = stream.try_next().await? {
+        if let SimpleQueryMessage::Row(r) = message {
+            if row.is_none() {
+                row = Some(r);
+            }
+        }
+    }
+
+    row.ok_or_else(Error::unexpected_message)
+}
+
+pub(crate) async fn identify_system(client: &InnerClient) -> Result<IdentifySystem, Error> {
+    let row = simple_query_row(client, "IDENTIFY_SYSTEM").await?;
+
+    Ok(IdentifySystem {
+        systemid: parse_column(&row, 0)?,
+        timeline: parse_column(&row, 1)?,
+        xlogpos: parse_column(&row, 2)?,
+        dbname: row.try_get(3)?.map(str::to_string),
+    })
+}
+
+pub(crate) async fn timeline_history(
+    client: &InnerClient,
+    timeline: u32,
+) -> Result<TimelineHistory, Error> {
+    let query = format!("TIMELINE_HISTORY {}", timeline);
+    let row = simple_query_row(client, &query).await?;
+
+    Ok(TimelineHistory {
+        filename: parse_column(&row, 0)?,
+        content: parse_column::<String>(&row, 1)?.into_bytes(),
+    })
+}
+
+pub(crate) async fn create_physical_replication_slot(
+    client: &InnerClient,
+    slot_name: &str,
+    temporary: bool,
+    reserve_wal: bool,
+) -> Result<CreateReplicationSlotResponse, Error> {
+    let mut query = format!("CREATE_REPLICATION_SLOT {}", escape_identifier(slot_name));
+    if temporary {
+        query.push_str(" TEMPORARY");
+    }
+    query.push_str(" PHYSICAL");
+    if reserve_wal {
+        query.push_str(" RESERVE_WAL");
+    }
+
+    create_replication_slot(client, &query).await
+}
+
+pub(crate) async fn create_logical_replication_slot(
+    client: &InnerClient,
+    slot_name: &str,
+    temporary: bool,
+    plugin: &str,
+    snapshot: Option<SnapshotAction>,
+) -> Result<CreateReplicationSlotResponse, Error> {
+    let mut query = format!("CREATE_REPLICATION_SLOT {}", escape_identifier(slot_name));
+    if temporary {
+        query.push_str(" TEMPORARY");
+    }
+    query.push_str(" LOGICAL ");
+    query.push_str(&escape_identifier(plugin));
+    match snapshot {
+        Some(SnapshotAction::Export) => query.push_str(" EXPORT_SNAPSHOT"),
+        Some(SnapshotAction::NoExport) => query.push_str(" NOEXPORT_SNAPSHOT"),
+        Some(SnapshotAction::Use) => query.push_str(" USE_SNAPSHOT"),
+        None => {}
+    }
+
+    create_replication_slot(client, &query).await
+}
+
+async fn create_replication_slot(
+    client: &InnerClient,
+    query: &str,
+) -> Result<CreateReplicationSlotResponse, Error> {
+    let row = simple_query_row(client, query).await?;
+
+    Ok(CreateReplicationSlotResponse {
+        slot_name: parse_column(&row, 0)?,
+        consistent_point: parse_column(&row, 1)?,
+        snapshot_name: row.try_get(2)?.map(str::to_string),
+        output_plugin: row.try_get(3)?.map(str::to_string),
+    })
+}
+
+pub(crate) async fn drop_replication_slot(
+    client: &InnerClient,
+    slot_name: &str,
+    wait: bool,
+) -> Result<(), Error> {
+    let mut query = format!("DROP_REPLICATION_SLOT {}", escape_identifier(slot_name));
+    if wait {
+        query.push_str(" WAIT");
+    }
+
+    simple_query::batch_execute(client, &query).await
+}
+
+pub(crate) async fn start_physical_replication(
+    client: &InnerClient,
+    slot_name: Option<&str>,
+    start_lsn: PgLsn,
+    timeline: Option<u32>,
+) -> Result<PhysicalReplicationStream, Error> {
+    let mut query = "START_REPLICATION".to_string();
+    if let Some(slot_name) = slot_name {
+        query.push_str(" SLOT ");
+        query.push_str(&escape_identifier(slot_name));
+    }
+    query.push_str(&format!(" PHYSICAL {}", start_lsn));
+    if let Some(timeline) = timeline {
+        query.push_str(&format!(" TIMELINE {}", timeline));
+    }
+
+    let stream = copy_both::copy_both(client, &query).await?;
+    Ok(PhysicalReplicationStream::new(stream, start_lsn))
+}
+
+pub(crate) async fn base_backup(
+    client: &InnerClient,
+    options: &BaseBackupOptions,
+) -> Result<BaseBackupStream, Error> {
+    let row = simple_query_row(client, "SHOW server_version_num").await?;
+    let version = parse_column::<i32>(&row, 0)?;
+
+    // PostgreSQL 15 replaced the original option syntax with a parenthesized list.
+    let legacy = version < 150_000;
+    let mut opts = vec![];
+    if let Some(label) = &options.label {
+        opts.push(format!("LABEL {}", escape_literal(label)));
+    }
+    if options.progress {
+        opts.push("PROGRESS".to_string());
+    }
+    if options.fast {
+        opts.push(if legacy { "FAST" } else { "CHECKPOINT 'fast'" }.to_string());
+    }
+    if options.wal {
+        opts.push("WAL".to_string());
+    }
+    if !options.wait {
+        opts.push(if legacy { "NOWAIT" } else { "WAIT false" }.to_string());
+    }
+    if let Some(max_rate) = options.max_rate {
+        opts.push(format!("MAX_RATE {}", max_rate));
+    }
+    if options.tablespace_map {
+        opts.push("TABLESPACE_MAP".to_string());
+    }
+
+    let query = if opts.is_empty() {
+        "BASE_BACKUP".to_string()
+    } else if legacy {
+        format!("BASE_BACKUP {}", opts.join(" "))
+    } else {
+        format!("BASE_BACKUP ({})", opts.join(", "))
+    };
+
+    debug!("executing base backup: {}", query);
+    let buf = simple_query::encode(client, &query)?;
+    let responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+
+    Ok(BaseBackupStream {
+        responses,
+        legacy,
+        columns: None,
+        result_sets: 0,
+        tablespaces: vec![],
+        archives: 0,
+        _p: PhantomPinned,
+    })
+}
+
+pub(crate) async fn start_logical_replication(
+    client: &InnerClient,
+    slot_name: &str,
+    start_lsn: PgLsn,
+    options: &[(&str, &str)],
+) -> Result<LogicalReplicationStream, Error> {
+    let mut query = format!(
+        "START_REPLICATION SLOT {} LOGICAL {}",
+        escape_identifier(slot_name),
+        start_lsn
+    );
+    if !options.is_empty() {
+        let options = options
+            .iter()
+            .map(|(name, value)| format!("{} {}", escape_identifier(name), escape_literal(value)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        query.push_str(" (");
+        query.push_str(&options);
+        query.push(')');
+    }
+
+    let stream = copy_both::copy_both(client, &query).await?;
+    Ok(LogicalReplicationStream::new(stream))
+}
+
+fn escape_identifier(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+fn escape_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn encode_standby_status_update(
+    write_lsn: PgLsn,
+    flush_lsn: PgLsn,
+    apply_lsn: PgLsn,
+    reply: bool,
+) -> Bytes {
+    let mut buf = BytesMut::new();
+    frontend::standby_status_update(
+        write_lsn.into(),
+        flush_lsn.into(),
+        apply_lsn.into(),
+        current_timestamp(),
+        reply as u8,
+        &mut buf,
+    );
+    buf.freeze()
+}
+
+fn current_timestamp() -> i64 {
+    let since_unix = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_else(|_| Duration::from_secs(0));
+    since_unix.as_micros() as i64 - (TIME_SEC_CONVERSION * 1_000_000) as i64
+}
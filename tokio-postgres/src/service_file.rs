@@ -0,0 +1,100 @@
+use crate::Error;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Looks up the parameters of a service in the connection service file.
+///
+/// The user's service file is searched first, followed by the system-wide file in `PGSYSCONFDIR`.
+pub fn lookup(name: &str) -> Result<Vec<(String, String)>, Error> {
+    for path in paths() {
+        if let Some(params) = lookup_in(&path, name)? {
+            return Ok(params);
+        }
+    }
+
+    Err(Error::config(
+        format!("definition of service \"{}\" not found", name).into(),
+    ))
+}
+
+fn paths() -> Vec<PathBuf> {
+    let mut paths = vec![];
+
+    if let Some(path) = env::var_os("PGSERVICEFILE") {
+        paths.push(PathBuf::from(path));
+    } else if cfg!(windows) {
+        if let Some(dir) = env::var_os("APPDATA") {
+            paths.push(PathBuf::from(dir).join("postgresql/.pg_service.conf"));
+        }
+    } else if let Some(dir) = env::var_os("HOME") {
+        paths.push(PathBuf::from(dir).join(".pg_service.conf"));
+    }
+
+    if let Some(dir) = env::var_os("PGSYSCONFDIR") {
+        paths.push(PathBuf::from(dir).join("pg_service.conf"));
+    }
+
+    paths
+}
+
+fn lookup_in(path: &Path, name: &str) -> Result<Option<Vec<(String, String)>>, Error> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(Error::config(Box::new(e))),
+    };
+
+    let mut params = None;
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            if params.is_some() {
+                break;
+            }
+            if line.trim_start_matches('[').trim_end_matches(']') == name {
+                params = Some(vec![]);
+            }
+            continue;
+        }
+
+        let params = match &mut params {
+            Some(params) => params,
+            None => continue,
+        };
+
+        let mut it = line.splitn(2, '=');
+        let key = it.next().unwrap().trim();
+        let value = match it.next() {
+            Some(value) => value.trim(),
+            None => {
+                return Err(Error::config(
+                    format!(
+                        "syntax error in service file {}, line {}",
+                        path.display(),
+                        i + 1
+                    )
+                    .into(),
+                ))
+            }
+        };
+        if key == "service" {
+            return Err(Error::config(
+                format!(
+                    "nested service specifications not supported in service file {}, line {}",
+                    path.display(),
+                    i + 1
+                )
+                .into(),
+            ));
+        }
+        params.push((key.to_string(), value.to_string()));
+    }
+
+    Ok(params)
+}
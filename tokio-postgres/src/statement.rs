@@ -11,6 +11,7 @@ use std::{
 struct StatementInner {
     client: Weak<InnerClient>,
     name: String,
+    query: Option<String>,
     params: Vec<Type>,
     columns: Vec<Column>,
 }
@@ -44,6 +45,19 @@ impl Statement {
         Statement(Arc::new(StatementInner {
             client: Arc::downgrade(inner),
             name,
+            query: None,
+            params,
+            columns,
+        }))
+    }
+
+    // The unnamed statement is replaced by the server when the next query is parsed, so it's never explicitly closed.
+    // Its query is instead parsed again each time it's bound.
+    pub(crate) fn unnamed(query: String, params: Vec<Type>, columns: Vec<Column>) -> Statement {
+        Statement(Arc::new(StatementInner {
+            client: Weak::new(),
+            name: String::new(),
+            query: Some(query),
             params,
             columns,
         }))
@@ -53,6 +67,15 @@ impl Statement {
         &self.0.name
     }
 
+    pub(crate) fn unnamed_query(&self) -> Option<&str> {
+        self.0.query.as_deref()
+    }
+
+    // Determines if both handles refer to the same prepared statement.
+    pub(crate) fn ptr_eq(&self, other: &Statement) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
     /// Returns the expected types of the statement's parameters.
     pub fn params(&self) -> &[Type] {
         &self.0.params
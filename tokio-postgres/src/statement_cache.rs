@@ -0,0 +1,143 @@
+use crate::types::Type;
+use crate::Statement;
+use std::collections::HashMap;
+use std::mem;
+
+struct Entry {
+    types: Vec<Type>,
+    statement: Statement,
+    last_used: u64,
+}
+
+/// A least recently used cache of prepared statements, keyed by query text and explicit parameter types.
+pub struct StatementCache {
+    capacity: usize,
+    len: usize,
+    tick: u64,
+    entries: HashMap<String, Vec<Entry>>,
+}
+
+impl StatementCache {
+    pub fn new(capacity: usize) -> StatementCache {
+        StatementCache {
+            capacity,
+            len: 0,
+            tick: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    pub fn get(&mut self, query: &str, types: &[Type]) -> Option<Statement> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        let entry = self
+            .entries
+            .get_mut(query)?
+            .iter_mut()
+            .find(|e| e.types == types)?;
+        entry.last_used = tick;
+        Some(entry.statement.clone())
+    }
+
+    // Statements removed from the cache are returned rather than dropped, since dropping the last reference to a
+    // statement closes it, which requires the client's state lock.
+    pub fn insert(
+        &mut self,
+        query: &str,
+        types: &[Type],
+        statement: &Statement,
+    ) -> Option<Statement> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        self.tick += 1;
+        let tick = self.tick;
+
+        let entries = self.entries.entry(query.to_string()).or_default();
+        if let Some(entry) = entries.iter_mut().find(|e| e.types == types) {
+            entry.last_used = tick;
+            return Some(mem::replace(&mut entry.statement, statement.clone()));
+        }
+        entries.push(Entry {
+            types: types.to_vec(),
+            statement: statement.clone(),
+            last_used: tick,
+        });
+        self.len += 1;
+
+        if self.len > self.capacity {
+            self.evict()
+        } else {
+            None
+        }
+    }
+
+    // Statements are matched by identity rather than by name, since every unnamed statement has the same name.
+    pub fn remove(&mut self, statement: &Statement) -> Option<Statement> {
+        let (query, idx) = self.entries.iter().find_map(|(query, entries)| {
+            entries
+                .iter()
+                .position(|e| e.statement.ptr_eq(statement))
+                .map(|idx| (query.clone(), idx))
+        })?;
+
+        let entries = self.entries.get_mut(&query).unwrap();
+        let entry = entries.remove(idx);
+        if entries.is_empty() {
+            self.entries.remove(&query);
+        }
+        self.len -= 1;
+        Some(entry.statement)
+    }
+
+    // Returns the cached queries and their parameter types, least recently used first.
+    pub fn queries(&self) -> Vec<(String, Vec<Type>)> {
+        let mut queries = self
+            .entries
+            .iter()
+            .flat_map(|(query, entries)| entries.iter().map(move |e| (query, e)))
+            .collect::<Vec<_>>();
+        queries.sort_by_key(|(_, e)| e.last_used);
+        queries
+            .into_iter()
+            .map(|(query, e)| (query.clone(), e.types.clone()))
+            .collect()
+    }
+
+    pub fn clear(&mut self) -> Vec<Statement> {
+        self.len = 0;
+        self.entries
+            .drain()
+            .flat_map(|(_, entries)| entries)
+            .map(|e| e.statement)
+            .collect()
+    }
+
+    fn evict(&mut self) -> Option<Statement> {
+        let oldest = self
+            .entries
+            .iter()
+            .flat_map(|(query, entries)| entries.iter().map(move |e| (query, e)))
+            .min_by_key(|(_, e)| e.last_used)
+            .map(|(query, e)| (query.clone(), e.last_used));
+
+        let (query, last_used) = oldest?;
+        let entries = self.entries.get_mut(&query).unwrap();
+        let idx = entries
+            .iter()
+            .position(|e| e.last_used == last_used)
+            .unwrap();
+        let entry = entries.remove(idx);
+        if entries.is_empty() {
+            self.entries.remove(&query);
+        }
+        self.len -= 1;
+        Some(entry.statement)
+    }
+}
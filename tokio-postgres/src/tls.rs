@@ -72,6 +72,14 @@ pub trait TlsConnect<S> {
 pub trait TlsStream: AsyncRead + AsyncWrite {
     /// Returns channel binding information for the session.
     fn channel_binding(&self) -> ChannelBinding;
+
+    /// Returns the ALPN protocol selected by the server during the handshake, if any.
+    ///
+    /// Connections using `sslnegotiation=direct` are rejected unless this is `postgresql`, so implementations which
+    /// can't determine the protocol don't support direct negotiation.
+    fn alpn_protocol(&self) -> Option<Vec<u8>> {
+        None
+    }
 }
 
 /// A `MakeTlsConnect` and `TlsConnect` implementation which simply returns an error.
@@ -149,6 +157,10 @@ impl TlsStream for NoTlsStream {
     fn channel_binding(&self) -> ChannelBinding {
         match *self {}
     }
+
+    fn alpn_protocol(&self) -> Option<Vec<u8>> {
+        match *self {}
+    }
 }
 
 /// The error returned by `NoTls`.
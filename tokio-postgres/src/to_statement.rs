@@ -2,7 +2,7 @@ use crate::to_statement::private::{Sealed, ToStatementType};
 use crate::Statement;
 
 mod private {
-    use crate::{Client, Error, Statement};
+    use crate::{prepare, Client, Error, Statement};
 
     pub trait Sealed {}
 
@@ -15,7 +15,7 @@ mod private {
         pub async fn into_statement(self, client: &Client) -> Result<Statement, Error> {
             match self {
                 ToStatementType::Statement(s) => Ok(s.clone()),
-                ToStatementType::Query(s) => client.prepare(s).await,
+                ToStatementType::Query(s) => prepare::prepare_cached(client.inner(), s, &[]).await,
             }
         }
     }
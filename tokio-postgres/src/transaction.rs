@@ -1,18 +1,19 @@
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
 use crate::copy_out::CopyOutStream;
+use crate::large_object::{self, LargeObject, Mode};
 use crate::query::RowStream;
 #[cfg(feature = "runtime")]
 use crate::tls::MakeTlsConnect;
 use crate::tls::TlsConnect;
-use crate::types::{ToSql, Type};
+use crate::types::{Format, Oid, ToSql, Type};
 #[cfg(feature = "runtime")]
 use crate::Socket;
 use crate::{
-    bind, query, slice_iter, CancelToken, Client, CopyInSink, Error, Portal, Row,
+    bind, query, slice_iter, CancelToken, Client, CopyInSink, Error, Pipeline, Portal, Row,
     SimpleQueryMessage, Statement, ToStatement,
 };
-use bytes::Buf;
+use bytes::{Buf, Bytes};
 use futures::TryStreamExt;
 use postgres_protocol::message::frontend;
 use tokio::io::{AsyncRead, AsyncWrite};
@@ -148,6 +149,23 @@ impl<'a> Transaction<'a> {
         self.client.query_raw(statement, params).await
     }
 
+    /// Like `Client::query_typed`.
+    pub async fn query_typed(
+        &self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error> {
+        self.client.query_typed(query, params).await
+    }
+
+    /// Like `Client::query_typed_raw`.
+    pub async fn query_typed_raw<'b, I>(&self, query: &str, params: I) -> Result<RowStream, Error>
+    where
+        I: IntoIterator<Item = (&'b dyn ToSql, Type)>,
+    {
+        self.client.query_typed_raw(query, params).await
+    }
+
     /// Like `Client::execute`.
     pub async fn execute<T>(
         &self,
@@ -224,6 +242,11 @@ impl<'a> Transaction<'a> {
         query::query_portal(self.client.inner(), portal, max_rows).await
     }
 
+    /// Like `Client::pipeline`.
+    pub fn pipeline(&self) -> Pipeline<'_> {
+        self.client.pipeline()
+    }
+
     /// Like `Client::copy_in`.
     pub async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
     where
@@ -251,6 +274,47 @@ impl<'a> Transaction<'a> {
         self.client.batch_execute(query).await
     }
 
+    /// Like `Client::call_function`.
+    pub async fn call_function(
+        &self,
+        oid: Oid,
+        args: &[Option<&[u8]>],
+        result_format: Format,
+    ) -> Result<Option<Bytes>, Error> {
+        self.client.call_function(oid, args, result_format).await
+    }
+
+    /// Creates a new, empty large object, returning its OID.
+    pub async fn create_large_object(&self) -> Result<Oid, Error> {
+        large_object::create(self.client.inner()).await
+    }
+
+    /// Opens a large object.
+    pub async fn open_large_object(&self, oid: Oid, mode: Mode) -> Result<LargeObject<'_>, Error> {
+        large_object::open(self.client.inner(), oid, mode).await
+    }
+
+    /// Deletes a large object.
+    pub async fn unlink_large_object(&self, oid: Oid) -> Result<(), Error> {
+        large_object::unlink(self.client.inner(), oid).await
+    }
+
+    /// Creates a new large object with the contents of a reader, returning its OID.
+    pub async fn import_large_object<R>(&self, reader: R) -> Result<Oid, Error>
+    where
+        R: AsyncRead + Unpin,
+    {
+        large_object::import(self.client.inner(), reader).await
+    }
+
+    /// Writes the contents of a large object to a writer.
+    pub async fn export_large_object<W>(&self, oid: Oid, writer: W) -> Result<(), Error>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        large_object::export(self.client.inner(), oid, writer).await
+    }
+
     /// Like `Client::cancel_token`.
     pub fn cancel_token(&self) -> CancelToken {
         self.client.cancel_token()
@@ -0,0 +1,126 @@
+//! Tests of `Config::apply_env`.
+//!
+//! These modify the process's environment, so they live in their own test binary rather than running concurrently
+//! with the tests which connect to the server, and everything touching the environment is in a single test.
+
+use std::time::Duration;
+use std::{env, fs, process};
+use tokio_postgres::config::{Config, SslMode};
+
+#[test]
+fn env() {
+    let dir = env::temp_dir().join(format!("tokio-postgres-env-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let pgpass = dir.join("pgpass");
+    fs::write(
+        &pgpass,
+        "# comment\n\
+         otherhost:*:*:*:wrong\n\
+         localhost:5433:*:ssl\\:user:pass:word\n\
+         *:*:*:*:fallback\n",
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&pgpass, fs::Permissions::from_mode(0o600)).unwrap();
+    }
+
+    let services = dir.join("pg_service.conf");
+    fs::write(
+        &services,
+        "[other]\n\
+         port=1\n\
+         \n\
+         [mydb]\n\
+         # comment\n\
+         host=localhost\n\
+         port = 5433\n\
+         dbname=mydb\n\
+         application_name=service\n",
+    )
+    .unwrap();
+
+    env::set_var("PGPASSFILE", &pgpass);
+    env::set_var("PGSERVICEFILE", &services);
+    env::set_var("PGUSER", "ssl:user");
+    env::set_var("PGAPPNAME", "env");
+    env::set_var("PGSSLMODE", "require");
+    env::set_var("PGCONNECT_TIMEOUT", "5");
+
+    assert_eq!(
+        Config::from_env().unwrap(),
+        *Config::new()
+            .user("ssl:user")
+            .password("fallback")
+            .application_name("env")
+            .ssl_mode(SslMode::Require)
+            .connect_timeout(Duration::from_secs(5)),
+    );
+
+    // the service file takes precedence over the environment, and explicit settings over both
+    let mut config = "service=mydb dbname=explicit".parse::<Config>().unwrap();
+    config.apply_env().unwrap();
+    assert_eq!(
+        config,
+        *Config::new()
+            .service("mydb")
+            .user("ssl:user")
+            .password("pass:word")
+            .dbname("explicit")
+            .host("localhost")
+            .port(5433)
+            .application_name("service")
+            .ssl_mode(SslMode::Require)
+            .connect_timeout(Duration::from_secs(5)),
+    );
+
+    env::set_var("PGSERVICE", "mydb");
+    let config = Config::from_env().unwrap();
+    assert_eq!(config.get_application_name(), Some("service"));
+    env::remove_var("PGSERVICE");
+
+    env::set_var("PGPASSWORD", "env");
+    let config = Config::from_env().unwrap();
+    assert_eq!(config.get_password(), Some(&b"env"[..]));
+    env::remove_var("PGPASSWORD");
+
+    // explicit settings take precedence even when they match the default
+    let mut config = "sslmode=prefer".parse::<Config>().unwrap();
+    env::set_var("PGSSLMODE", "disable");
+    config.apply_env().unwrap();
+    assert_eq!(config.get_ssl_mode(), SslMode::Prefer);
+    env::set_var("PGSSLMODE", "require");
+
+    let mut config = "password=explicit".parse::<Config>().unwrap();
+    config.apply_env().unwrap();
+    assert_eq!(config.get_password(), Some(&b"explicit"[..]));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&pgpass, fs::Permissions::from_mode(0o644)).unwrap();
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.get_password(), None);
+    }
+
+    "service=missing"
+        .parse::<Config>()
+        .unwrap()
+        .apply_env()
+        .err()
+        .unwrap();
+
+    for var in &[
+        "PGPASSFILE",
+        "PGSERVICEFILE",
+        "PGUSER",
+        "PGAPPNAME",
+        "PGSSLMODE",
+        "PGCONNECT_TIMEOUT",
+    ] {
+        env::remove_var(var);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
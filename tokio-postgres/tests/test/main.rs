@@ -6,19 +6,30 @@ use futures::{
     future, join, pin_mut, stream, try_join, FutureExt, SinkExt, StreamExt, TryStreamExt,
 };
 use std::fmt::Write;
+use std::io::{self, SeekFrom};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 use tokio::time;
 use tokio_postgres::error::SqlState;
+use tokio_postgres::large_object::Mode;
 use tokio_postgres::tls::{NoTls, NoTlsStream};
-use tokio_postgres::types::{Kind, Type};
+use tokio_postgres::types::{Format, Kind, Type};
 use tokio_postgres::{
-    AsyncMessage, Client, Config, Connection, Error, IsolationLevel, SimpleQueryMessage,
+    AsyncMessage, Client, Config, Connection, Error, IsolationLevel, PipelineSync,
+    SimpleQueryMessage,
 };
 
 mod binary_copy;
+#[cfg(feature = "runtime")]
+mod mock;
 mod parse;
 #[cfg(feature = "runtime")]
+mod reconnect;
+mod replication;
+#[cfg(feature = "runtime")]
 mod runtime;
 mod types;
 
@@ -101,6 +112,78 @@ async fn scram_password_ok() {
     connect("user=scram_user password=password dbname=postgres").await;
 }
 
+#[tokio::test]
+async fn require_auth() {
+    connect("user=pass_user password=password dbname=postgres require_auth=password").await;
+    connect("user=md5_user password=password dbname=postgres require_auth=md5,scram-sha-256").await;
+    connect("user=scram_user password=password dbname=postgres require_auth=scram-sha-256").await;
+    connect("user=postgres require_auth=none").await;
+    connect("user=scram_user password=password dbname=postgres require_auth=!password,!md5").await;
+
+    for s in &[
+        "user=pass_user password=password dbname=postgres require_auth=scram-sha-256",
+        "user=md5_user password=password dbname=postgres require_auth=!md5",
+        "user=scram_user password=password dbname=postgres require_auth=password,md5",
+        "user=postgres require_auth=scram-sha-256",
+    ] {
+        match connect_raw(s).await {
+            Ok(_) => panic!("unexpected success for `{}`", s),
+            Err(e) => assert!(
+                e.to_string()
+                    .ends_with("which is not allowed by require_auth"),
+                "{}",
+                e
+            ),
+        }
+    }
+}
+
+#[tokio::test]
+async fn direct_ssl_negotiation_requires_tls() {
+    for s in &[
+        "user=postgres sslnegotiation=direct",
+        "user=postgres sslnegotiation=direct sslmode=disable",
+    ] {
+        connect_raw(s).await.err().unwrap();
+    }
+}
+
+#[tokio::test]
+async fn password_provider() {
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    for user in &["pass_user", "md5_user", "scram_user"] {
+        let mut config = format!("user={} password=wrong dbname=postgres", user)
+            .parse::<Config>()
+            .unwrap();
+        let provider_calls = calls.clone();
+        config.password_provider(move || {
+            provider_calls.fetch_add(1, Ordering::SeqCst);
+            async { Ok(b"password".to_vec()) }
+        });
+
+        let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+        let (_client, _connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    }
+
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn password_provider_error() {
+    let mut config = "user=scram_user dbname=postgres".parse::<Config>().unwrap();
+    config.password_provider(|| async { Err("token service unavailable".into()) });
+
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    match config.connect_raw(socket, NoTls).await {
+        Ok(_) => panic!("unexpected success"),
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "authentication error: token service unavailable"
+        ),
+    }
+}
+
 #[tokio::test]
 async fn pipelined_prepare() {
     let client = connect("user=postgres").await;
@@ -301,6 +384,101 @@ async fn simple_query() {
     assert_eq!(messages.len(), 5);
 }
 
+#[tokio::test]
+async fn call_function() {
+    let client = connect("user=postgres").await;
+
+    let oid = client
+        .query_one("SELECT 'int4pl'::regproc::oid", &[])
+        .await
+        .unwrap()
+        .get::<_, u32>(0);
+    let a = 1i32.to_be_bytes();
+    let b = 2i32.to_be_bytes();
+
+    let value = client
+        .call_function(oid, &[Some(&a), Some(&b)], Format::Binary)
+        .await
+        .unwrap();
+    assert_eq!(value.as_deref(), Some(&3i32.to_be_bytes()[..]));
+
+    let value = client
+        .call_function(oid, &[Some(&a), Some(&b)], Format::Text)
+        .await
+        .unwrap();
+    assert_eq!(value.as_deref(), Some(&b"3"[..]));
+
+    // int4pl is strict, so a NULL argument produces a NULL result
+    let value = client
+        .call_function(oid, &[Some(&a), None], Format::Binary)
+        .await
+        .unwrap();
+    assert_eq!(value, None);
+
+    let err = client
+        .call_function(0, &[], Format::Binary)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::UNDEFINED_FUNCTION));
+
+    // the connection is still usable after an error
+    client.batch_execute("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn large_object() {
+    let mut client = connect("user=postgres").await;
+    let transaction = client.transaction().await.unwrap();
+
+    let oid = transaction.create_large_object().await.unwrap();
+    let mut object = transaction
+        .open_large_object(oid, Mode::ReadWrite)
+        .await
+        .unwrap();
+    assert_eq!(object.oid(), oid);
+    object.write_all(b"hello world").await.unwrap();
+
+    object.seek(SeekFrom::Start(6)).await.unwrap();
+    let mut buf = [0; 3];
+    object.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"wor");
+
+    // writes happen at the position of the last read byte
+    object.write_all(b"LD").await.unwrap();
+    assert_eq!(object.seek(SeekFrom::Current(0)).await.unwrap(), 11);
+
+    object.truncate(5).await.unwrap();
+    object.seek(SeekFrom::Start(0)).await.unwrap();
+    let mut contents = vec![];
+    object.read_to_end(&mut contents).await.unwrap();
+    assert_eq!(contents, b"hello");
+
+    for &offset in &[-6, i64::max_value()] {
+        let err = object.seek(SeekFrom::Current(offset)).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+    assert_eq!(object.seek(SeekFrom::Current(-5)).await.unwrap(), 0);
+    object.close().await.unwrap();
+
+    let data = (0..200_000).map(|i| i as u8).collect::<Vec<_>>();
+    let oid2 = transaction.import_large_object(&data[..]).await.unwrap();
+    let mut exported = vec![];
+    transaction
+        .export_large_object(oid2, &mut exported)
+        .await
+        .unwrap();
+    assert_eq!(exported, data);
+
+    transaction.unlink_large_object(oid).await.unwrap();
+    transaction.unlink_large_object(oid2).await.unwrap();
+    let err = transaction
+        .open_large_object(oid, Mode::Read)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), Some(&SqlState::UNDEFINED_OBJECT));
+}
+
 #[tokio::test]
 async fn cancel_query_raw() {
     let client = connect("user=postgres").await;
@@ -799,3 +977,306 @@ async fn query_opt() {
         .err()
         .unwrap();
 }
+
+#[tokio::test]
+async fn query_typed() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "
+                CREATE TEMPORARY TABLE foo (
+                    name TEXT,
+                    age INT
+                );
+                INSERT INTO foo (name, age) VALUES ('alice', 20), ('bob', 30), ('carol', 40);
+            ",
+        )
+        .await
+        .unwrap();
+
+    let rows = client
+        .query_typed(
+            "SELECT name, age FROM foo WHERE age > $1 ORDER BY age",
+            &[(&25i32, Type::INT4)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].columns()[1].type_(), &Type::INT4);
+    assert_eq!(rows[0].get::<_, &str>(0), "bob");
+    assert_eq!(rows[1].get::<_, i32>("age"), 40);
+
+    // the explicit type is used rather than the one the server would infer
+    let rows = client
+        .query_typed("SELECT $1::TEXT", &[(&"hello", Type::VARCHAR)])
+        .await
+        .unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "hello");
+
+    let rows = client
+        .query_typed("UPDATE foo SET age = age + 1", &[])
+        .await
+        .unwrap();
+    assert!(rows.is_empty());
+
+    client
+        .query_typed("SELECT nope FROM foo", &[])
+        .await
+        .unwrap_err();
+
+    // the connection is still usable after an error
+    let rows = client
+        .query_typed("SELECT count(*) FROM foo", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows[0].get::<_, i64>(0), 3);
+}
+
+#[tokio::test]
+async fn pipeline() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY, name TEXT)")
+        .await
+        .unwrap();
+
+    let insert = client
+        .prepare("INSERT INTO foo (id, name) VALUES ($1, $2)")
+        .await
+        .unwrap();
+    let select = client
+        .prepare("SELECT name FROM foo ORDER BY id")
+        .await
+        .unwrap();
+
+    let mut pipeline = client.pipeline();
+    pipeline
+        .query(&insert, &[&1i32, &"alice"])
+        .unwrap()
+        .query(&insert, &[&2i32, &"bob"])
+        .unwrap()
+        .query(&select, &[])
+        .unwrap();
+    assert_eq!(pipeline.len(), 3);
+    let results = pipeline.run().await;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().rows_affected(), 1);
+    assert_eq!(results[1].as_ref().unwrap().rows_affected(), 1);
+    let rows = results[2].as_ref().unwrap().rows();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<_, &str>(0), "alice");
+    assert_eq!(rows[1].get::<_, &str>(0), "bob");
+}
+
+#[tokio::test]
+async fn pipeline_single_sync_error() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY)")
+        .await
+        .unwrap();
+
+    let insert = client
+        .prepare("INSERT INTO foo (id) VALUES ($1)")
+        .await
+        .unwrap();
+
+    let mut pipeline = client.pipeline();
+    pipeline
+        .query(&insert, &[&1i32])
+        .unwrap()
+        .query(&insert, &[&1i32])
+        .unwrap()
+        .query(&insert, &[&2i32])
+        .unwrap();
+    let results = pipeline.run().await;
+
+    assert!(results[0].is_ok());
+    assert_eq!(
+        results[1].as_ref().err().unwrap().code(),
+        Some(&SqlState::UNIQUE_VIOLATION)
+    );
+    assert!(results[2].is_err());
+
+    let rows = client.query("SELECT id FROM foo", &[]).await.unwrap();
+    assert_eq!(rows.len(), 0);
+}
+
+#[tokio::test]
+async fn pipeline_per_statement_sync_error() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY)")
+        .await
+        .unwrap();
+
+    let insert = client
+        .prepare("INSERT INTO foo (id) VALUES ($1)")
+        .await
+        .unwrap();
+
+    let mut pipeline = client.pipeline();
+    pipeline
+        .sync(PipelineSync::PerStatement)
+        .query(&insert, &[&1i32])
+        .unwrap()
+        .query(&insert, &[&1i32])
+        .unwrap()
+        .query(&insert, &[&2i32])
+        .unwrap();
+    let results = pipeline.run().await;
+
+    assert!(results[0].is_ok());
+    assert_eq!(
+        results[1].as_ref().err().unwrap().code(),
+        Some(&SqlState::UNIQUE_VIOLATION)
+    );
+    assert!(results[2].is_ok());
+
+    let rows = client
+        .query("SELECT id FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    assert_eq!(rows[1].get::<_, i32>(0), 2);
+}
+
+async fn prepared_statement_count(client: &Client) -> String {
+    let messages = client
+        .simple_query("SELECT count(*) FROM pg_prepared_statements")
+        .await
+        .unwrap();
+    match &messages[0] {
+        SimpleQueryMessage::Row(row) => row.get(0).unwrap().to_string(),
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[tokio::test]
+async fn statement_cache() {
+    let client = connect("user=postgres statement_cache_capacity=2").await;
+
+    for _ in 0..3 {
+        client.query("SELECT 1", &[]).await.unwrap();
+    }
+    assert_eq!(prepared_statement_count(&client).await, "1");
+
+    client.query("SELECT 2", &[]).await.unwrap();
+    client.query("SELECT 3", &[]).await.unwrap();
+    assert_eq!(prepared_statement_count(&client).await, "2");
+
+    client.query("SELECT 1", &[]).await.unwrap();
+    assert_eq!(prepared_statement_count(&client).await, "2");
+
+    client.clear_statement_cache();
+    assert_eq!(prepared_statement_count(&client).await, "0");
+}
+
+#[tokio::test]
+async fn statement_cache_disabled() {
+    let client = connect("user=postgres").await;
+
+    for _ in 0..3 {
+        client.query("SELECT 1", &[]).await.unwrap();
+    }
+    assert_eq!(prepared_statement_count(&client).await, "0");
+}
+
+#[tokio::test]
+async fn statement_cache_invalidation() {
+    let client = connect("user=postgres statement_cache_capacity=10").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (a INT)")
+        .await
+        .unwrap();
+
+    let rows = client.query("SELECT * FROM foo", &[]).await.unwrap();
+    assert_eq!(rows.len(), 0);
+
+    client
+        .batch_execute("ALTER TABLE foo ADD COLUMN b INT; INSERT INTO foo VALUES (1, 2)")
+        .await
+        .unwrap();
+
+    let err = client.query("SELECT * FROM foo", &[]).await.unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::FEATURE_NOT_SUPPORTED));
+
+    let rows = client.query("SELECT * FROM foo", &[]).await.unwrap();
+    assert_eq!(rows[0].len(), 2);
+}
+
+#[tokio::test]
+async fn named_statements_disabled() {
+    let mut client = connect("user=postgres named_statements=false").await;
+
+    client
+        .batch_execute(
+            "
+                CREATE TYPE pg_temp.mood AS ENUM ('sad', 'ok', 'happy');
+                CREATE TEMPORARY TABLE foo (id INT, mood mood);
+            ",
+        )
+        .await
+        .unwrap();
+
+    match client.prepare("SELECT 1").await {
+        Err(e) => assert!(e.to_string().contains("named statements are disabled")),
+        Ok(_) => panic!("unexpected success"),
+    }
+
+    let inserted = client
+        .execute(
+            "INSERT INTO foo (id, mood) VALUES ($1, 'happy'), ($2, 'sad')",
+            &[&1i32, &2i32],
+        )
+        .await
+        .unwrap();
+    assert_eq!(inserted, 2);
+
+    let rows = client
+        .query("SELECT id, mood FROM foo WHERE id > $1", &[&1i32])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, i32>(0), 2);
+    assert_eq!(rows[0].columns()[1].type_().name(), "mood");
+
+    let transaction = client.transaction().await.unwrap();
+    let portal = transaction
+        .bind("SELECT id FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    // the portal outlives the unnamed statement it was bound from
+    let row = transaction
+        .query_one("SELECT count(*) FROM foo", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i64>(0), 2);
+    let rows = transaction.query_portal(&portal, 1).await.unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    let rows = transaction.query_portal(&portal, 1).await.unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 2);
+    transaction.commit().await.unwrap();
+
+    let stream = client
+        .copy_out("COPY (SELECT id FROM foo ORDER BY id) TO STDOUT")
+        .await
+        .unwrap();
+    let data = stream
+        .try_fold(BytesMut::new(), |mut buf, chunk| {
+            buf.extend_from_slice(&chunk);
+            future::ok(buf)
+        })
+        .await
+        .unwrap();
+    assert_eq!(&data[..], b"1\n2\n");
+
+    assert_eq!(prepared_statement_count(&client).await, "0");
+}
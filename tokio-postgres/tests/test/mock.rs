@@ -0,0 +1,243 @@
+use futures::channel::mpsc;
+use futures::{join, stream, FutureExt, StreamExt, TryStreamExt};
+use postgres_mock::{Auth, Builder, Column, MockServer, Received, Response};
+use std::time::Duration;
+#[cfg(unix)]
+use std::{env, fs, process};
+use tokio::time;
+use tokio_postgres::error::SqlState;
+use tokio_postgres::types::Type;
+use tokio_postgres::{AsyncMessage, Client, Config, Error, NoTls, SimpleQueryMessage};
+
+async fn connect_raw(server: &MockServer, s: &str) -> Result<Client, Error> {
+    let config = format!("{} {}", server.connection_string(), s)
+        .parse::<Config>()
+        .unwrap();
+    let (client, connection) = config.connect(NoTls).await?;
+    tokio::spawn(connection.map(|r| r.unwrap()));
+    Ok(client)
+}
+
+async fn connect(server: &MockServer) -> Client {
+    connect_raw(server, "user=postgres").await.unwrap()
+}
+
+#[tokio::test]
+async fn simple_query() {
+    let server = Builder::new()
+        .respond(
+            "SELECT name FROM users",
+            Response::rows(vec![Column::new("name", Type::TEXT)])
+                .row(vec![Some("alice")])
+                .row(vec![None::<&str>]),
+        )
+        .start()
+        .await
+        .unwrap();
+    let client = connect(&server).await;
+
+    let messages = client.simple_query("SELECT name FROM users").await.unwrap();
+    match &messages[..] {
+        [SimpleQueryMessage::Row(a), SimpleQueryMessage::Row(b), SimpleQueryMessage::CommandComplete(2)] =>
+        {
+            assert_eq!(a.get("name"), Some("alice"));
+            assert_eq!(b.get(0), None);
+        }
+        _ => panic!("unexpected messages"),
+    }
+
+    let received = server.received();
+    match &received[0] {
+        Received::Startup(parameters) => assert!(parameters
+            .iter()
+            .any(|(k, v)| k == "user" && v == "postgres")),
+        m => panic!("unexpected message {:?}", m),
+    }
+    assert_eq!(
+        received[1],
+        Received::Query("SELECT name FROM users".to_string())
+    );
+}
+
+#[tokio::test]
+async fn md5_password() {
+    let server = Builder::new()
+        .auth(Auth::Md5("hunter2".to_string()))
+        .start()
+        .await
+        .unwrap();
+
+    connect_raw(&server, "user=md5_user password=hunter2")
+        .await
+        .unwrap();
+    match connect_raw(&server, "user=md5_user password=foo").await {
+        Err(ref e) if e.code() == Some(&SqlState::INVALID_PASSWORD) => {}
+        r => panic!("unexpected result {:?}", r.map(|_| ())),
+    }
+}
+
+#[tokio::test]
+async fn scram_password() {
+    let server = Builder::new()
+        .auth(Auth::ScramSha256("hunter2".to_string()))
+        .start()
+        .await
+        .unwrap();
+
+    connect_raw(&server, "user=scram_user password=hunter2")
+        .await
+        .unwrap();
+    match connect_raw(&server, "user=scram_user password=foo").await {
+        Err(ref e) if e.code() == Some(&SqlState::INVALID_PASSWORD) => {}
+        r => panic!("unexpected result {:?}", r.map(|_| ())),
+    }
+}
+
+#[tokio::test]
+async fn extended_query() {
+    let server = Builder::new()
+        .respond(
+            "SELECT $1::INT4 + 1",
+            Response::rows(vec![Column::new("?column?", Type::INT4)])
+                .parameters(vec![Type::INT4])
+                .row(vec![Some(8i32.to_be_bytes())]),
+        )
+        .start()
+        .await
+        .unwrap();
+    let client = connect(&server).await;
+
+    let row = client
+        .query_one("SELECT $1::INT4 + 1", &[&7i32])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i32>(0), 8);
+
+    let received = server.received();
+    assert!(received.iter().any(|m| match m {
+        Received::Parse {
+            query,
+            parameter_types,
+            ..
+        } => query == "SELECT $1::INT4 + 1" && parameter_types.is_empty(),
+        _ => false,
+    }));
+    assert!(received.iter().any(|m| match m {
+        Received::Bind { values, .. } => values[..] == [Some(7i32.to_be_bytes().to_vec())],
+        _ => false,
+    }));
+}
+
+#[tokio::test]
+async fn errors() {
+    let server = Builder::new()
+        .respond(
+            "INSERT INTO users (name) VALUES ($1)",
+            Response::error("23505", "duplicate key value").parameters(vec![Type::TEXT]),
+        )
+        .respond("SELECT 1", Response::command("SELECT 0"))
+        .start()
+        .await
+        .unwrap();
+    let client = connect(&server).await;
+
+    let err = client
+        .execute("INSERT INTO users (name) VALUES ($1)", &[&"alice"])
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::UNIQUE_VIOLATION));
+
+    match client.prepare("SELECT nope").await {
+        Err(ref e) if e.code() == Some(&SqlState::SYNTAX_ERROR) => {}
+        r => panic!("unexpected result {:?}", r.map(|_| ())),
+    }
+
+    // the connection is still usable after an error
+    client.batch_execute("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn notices_and_notifications() {
+    let server = Builder::new()
+        .respond(
+            "DO",
+            Response::command("DO")
+                .notice("hello")
+                .notification("chan", "payload"),
+        )
+        .start()
+        .await
+        .unwrap();
+
+    let config = format!("{} user=postgres", server.connection_string())
+        .parse::<Config>()
+        .unwrap();
+    let (client, mut connection) = config.connect(NoTls).await.unwrap();
+    let (tx, rx) = mpsc::unbounded();
+    let stream =
+        stream::poll_fn(move |cx| connection.poll_message(cx)).map_err(|e| panic!("{}", e));
+    tokio::spawn(stream.forward(tx).map(|r| r.unwrap()));
+
+    client.batch_execute("DO").await.unwrap();
+    drop(client);
+
+    let messages = rx.collect::<Vec<_>>().await;
+    assert_eq!(messages.len(), 2);
+    match &messages[0] {
+        AsyncMessage::Notice(n) => assert_eq!(n.message(), "hello"),
+        _ => panic!("unexpected message"),
+    }
+    match &messages[1] {
+        AsyncMessage::Notification(n) => {
+            assert_eq!(n.channel(), "chan");
+            assert_eq!(n.payload(), "payload");
+        }
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[tokio::test]
+async fn cancel_query() {
+    let server = Builder::new()
+        .respond("SELECT pg_sleep(100)", Response::wait_for_cancel())
+        .start()
+        .await
+        .unwrap();
+    let client = connect(&server).await;
+
+    let cancel_token = client.cancel_token();
+    let cancel = cancel_token.cancel_query(NoTls);
+    let cancel = time::delay_for(Duration::from_millis(100)).then(|()| cancel);
+
+    let sleep = client.batch_execute("SELECT pg_sleep(100)");
+
+    match join!(sleep, cancel) {
+        (Err(ref e), Ok(())) if e.code() == Some(&SqlState::QUERY_CANCELED) => {}
+        t => panic!("unexpected return: {:?}", t),
+    }
+    assert!(server.received().iter().any(|m| match m {
+        Received::CancelRequest { .. } => true,
+        _ => false,
+    }));
+}
+
+#[tokio::test]
+#[cfg(unix)]
+async fn unix_socket() {
+    let dir = env::temp_dir().join(format!("postgres-mock-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir(&dir).unwrap();
+
+    let server = Builder::new()
+        .respond("SELECT 1", Response::command("SELECT 0"))
+        .start_unix(&dir, 54_321)
+        .await
+        .unwrap();
+    let client = connect(&server).await;
+
+    client.batch_execute("SELECT 1").await.unwrap();
+    drop(server);
+    assert!(!dir.join(".s.PGSQL.54321").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
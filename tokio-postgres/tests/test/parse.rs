@@ -1,5 +1,8 @@
 use std::time::Duration;
-use tokio_postgres::config::{Config, TargetSessionAttrs};
+use tokio_postgres::config::{
+    AuthMethod, Config, LoadBalanceHosts, ReplicationMode, SslMode, SslNegotiation,
+    TargetSessionAttrs,
+};
 
 fn check(s: &str, config: &Config) {
     assert_eq!(s.parse::<Config>().expect(s), *config, "`{}`", s);
@@ -36,6 +39,153 @@ fn settings() {
     );
 }
 
+#[test]
+fn target_session_attrs() {
+    check(
+        "target_session_attrs=read-only",
+        Config::new().target_session_attrs(TargetSessionAttrs::ReadOnly),
+    );
+    check(
+        "target_session_attrs=primary",
+        Config::new().target_session_attrs(TargetSessionAttrs::Primary),
+    );
+    check(
+        "target_session_attrs=standby",
+        Config::new().target_session_attrs(TargetSessionAttrs::Standby),
+    );
+    check(
+        "target_session_attrs=prefer-standby",
+        Config::new().target_session_attrs(TargetSessionAttrs::PreferStandby),
+    );
+    "target_session_attrs=read-mostly"
+        .parse::<Config>()
+        .err()
+        .unwrap();
+}
+
+#[test]
+fn load_balance_hosts() {
+    check(
+        "host=host1,host2 load_balance_hosts=random",
+        Config::new()
+            .host("host1")
+            .host("host2")
+            .load_balance_hosts(LoadBalanceHosts::Random),
+    );
+    check(
+        "load_balance_hosts=disable",
+        Config::new().load_balance_hosts(LoadBalanceHosts::Disable),
+    );
+    "load_balance_hosts=shuffle"
+        .parse::<Config>()
+        .err()
+        .unwrap();
+}
+
+#[test]
+fn require_auth() {
+    check(
+        "require_auth=scram-sha-256",
+        Config::new().require_auth(&[AuthMethod::ScramSha256]),
+    );
+    check(
+        "require_auth=password,md5,none",
+        Config::new().require_auth(&[AuthMethod::Password, AuthMethod::Md5, AuthMethod::None]),
+    );
+    check(
+        "require_auth=!password,!md5",
+        Config::new().require_auth(&[
+            AuthMethod::None,
+            AuthMethod::Gss,
+            AuthMethod::Sspi,
+            AuthMethod::ScramSha256,
+        ]),
+    );
+    for s in &[
+        "require_auth=kerberos",
+        "require_auth=password,!md5",
+        "require_auth=md5,md5",
+        "require_auth=",
+    ] {
+        s.parse::<Config>().err().unwrap();
+    }
+}
+
+#[test]
+fn ssl() {
+    check(
+        "sslmode=verify-full sslrootcert=/certs/root.crt sslcert=/certs/client.crt \
+         sslkey=/certs/client.key sslpassword=hunter2 sslcrl=/certs/root.crl",
+        Config::new()
+            .ssl_mode(SslMode::VerifyFull)
+            .ssl_root_cert("/certs/root.crt")
+            .ssl_cert("/certs/client.crt")
+            .ssl_key("/certs/client.key")
+            .ssl_password("hunter2")
+            .ssl_crl("/certs/root.crl"),
+    );
+    check(
+        "sslmode=verify-ca",
+        Config::new().ssl_mode(SslMode::VerifyCa),
+    );
+    "sslmode=verify".parse::<Config>().err().unwrap();
+
+    // Explicitly setting a default doesn't affect equality.
+    check("sslmode=prefer", &Config::new());
+    assert_eq!(Config::new(), *Config::new().ssl_mode(SslMode::Prefer));
+}
+
+#[test]
+fn ssl_negotiation() {
+    check(
+        "sslmode=require sslnegotiation=direct",
+        Config::new()
+            .ssl_mode(SslMode::Require)
+            .ssl_negotiation(SslNegotiation::Direct),
+    );
+    check(
+        "sslnegotiation=postgres",
+        Config::new().ssl_negotiation(SslNegotiation::Postgres),
+    );
+    "sslnegotiation=tls".parse::<Config>().err().unwrap();
+}
+
+#[test]
+fn replication() {
+    check(
+        "replication=true",
+        Config::new().replication_mode(ReplicationMode::Physical),
+    );
+    check(
+        "replication=database",
+        Config::new().replication_mode(ReplicationMode::Logical),
+    );
+    check("replication=off", &Config::new());
+    "replication=foo".parse::<Config>().err().unwrap();
+}
+
+#[test]
+fn statement_cache_capacity() {
+    check(
+        "statement_cache_capacity=100",
+        Config::new().statement_cache_capacity(100),
+    );
+    "statement_cache_capacity=-1"
+        .parse::<Config>()
+        .err()
+        .unwrap();
+}
+
+#[test]
+fn named_statements() {
+    check(
+        "named_statements=false",
+        Config::new().named_statements(false),
+    );
+    check("named_statements=on", Config::new().named_statements(true));
+    "named_statements=maybe".parse::<Config>().err().unwrap();
+}
+
 #[test]
 fn url() {
     check("postgresql://", &Config::new());
@@ -0,0 +1,188 @@
+use futures::channel::mpsc;
+use futures::{join, stream, FutureExt, StreamExt};
+use std::time::Duration;
+use tokio::time;
+use tokio_postgres::reconnect::{ReconnectEvent, ReconnectOptions, ReconnectingClient};
+use tokio_postgres::{Config, NoTls};
+
+async fn connect(s: &str) -> (ReconnectingClient, mpsc::UnboundedReceiver<ReconnectEvent>) {
+    let config = s.parse::<Config>().unwrap();
+    let (client, mut connection) = ReconnectOptions::new()
+        .connect(&config, NoTls)
+        .await
+        .unwrap();
+
+    let (tx, rx) = mpsc::unbounded();
+    let events = stream::poll_fn(move |cx| connection.poll_event(cx)).map(|r| Ok(r.unwrap()));
+    tokio::spawn(events.forward(tx).map(|r| r.unwrap()));
+
+    (client, rx)
+}
+
+async fn next_event(events: &mut mpsc::UnboundedReceiver<ReconnectEvent>) -> ReconnectEvent {
+    loop {
+        match events.next().await.unwrap() {
+            ReconnectEvent::Message(_) => {}
+            event => return event,
+        }
+    }
+}
+
+async fn backend_pid(client: &ReconnectingClient) -> i32 {
+    client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get(0)
+}
+
+async fn terminate(pid: i32) {
+    let (admin, connection) =
+        tokio_postgres::connect("host=localhost port=5433 user=postgres", NoTls)
+            .await
+            .unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+    admin
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn reconnect() {
+    let (client, mut events) =
+        connect("host=localhost port=5433 user=postgres statement_cache_capacity=10").await;
+
+    client
+        .set_session_parameter("application_name", "reconnecting")
+        .await
+        .unwrap();
+    client.query("SELECT $1::TEXT", &[&"hi"]).await.unwrap();
+
+    let pid = backend_pid(&client).await;
+    terminate(pid).await;
+
+    match next_event(&mut events).await {
+        ReconnectEvent::Disconnected(_) => {}
+        event => panic!("unexpected event {:?}", event),
+    }
+    match next_event(&mut events).await {
+        ReconnectEvent::Reconnected => {}
+        event => panic!("unexpected event {:?}", event),
+    }
+
+    let row = client
+        .query_one(
+            "SELECT
+                pg_backend_pid(),
+                current_setting('application_name'),
+                (SELECT count(*) FROM pg_prepared_statements WHERE statement = 'SELECT $1::TEXT')",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_ne!(row.get::<_, i32>(0), pid);
+    assert_eq!(row.get::<_, &str>(1), "reconnecting");
+    assert_eq!(row.get::<_, i64>(2), 1);
+}
+
+#[tokio::test]
+async fn requests_wait_for_reconnect() {
+    let (client, mut events) = connect("host=localhost port=5433 user=postgres").await;
+
+    let pid = backend_pid(&client).await;
+    terminate(pid).await;
+    match next_event(&mut events).await {
+        ReconnectEvent::Disconnected(_) => {}
+        event => panic!("unexpected event {:?}", event),
+    }
+
+    let row = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap();
+    assert_ne!(row.get::<_, i32>(0), pid);
+}
+
+#[tokio::test]
+async fn in_flight_request_not_retried() {
+    let (client, mut events) = connect("host=localhost port=5433 user=postgres").await;
+
+    let pid = backend_pid(&client).await;
+    let (r, ()) = join!(
+        client.batch_execute("SELECT pg_sleep(10)"),
+        time::delay_for(Duration::from_millis(100)).then(|()| terminate(pid)),
+    );
+    r.unwrap_err();
+
+    match next_event(&mut events).await {
+        ReconnectEvent::Disconnected(_) => {}
+        event => panic!("unexpected event {:?}", event),
+    }
+    client.batch_execute("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn initialization_failure_keeps_statements() {
+    let (admin, connection) =
+        tokio_postgres::connect("host=localhost port=5433 user=postgres", NoTls)
+            .await
+            .unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+    admin
+        .batch_execute(
+            "DROP ROLE IF EXISTS reconnect_init_role;
+             CREATE ROLE reconnect_init_role",
+        )
+        .await
+        .unwrap();
+
+    let (client, mut events) =
+        connect("host=localhost port=5433 user=postgres statement_cache_capacity=10").await;
+    client
+        .set_session_parameter("role", "reconnect_init_role")
+        .await
+        .unwrap();
+    client.query("SELECT $1::TEXT", &[&"hi"]).await.unwrap();
+
+    // Setting the role fails on the new connection until it's been created again.
+    admin
+        .batch_execute("DROP ROLE reconnect_init_role")
+        .await
+        .unwrap();
+    let pid = backend_pid(&client).await;
+    terminate(pid).await;
+
+    match next_event(&mut events).await {
+        ReconnectEvent::Disconnected(_) => {}
+        event => panic!("unexpected event {:?}", event),
+    }
+    match next_event(&mut events).await {
+        ReconnectEvent::ReconnectFailed(_, _) => {}
+        event => panic!("unexpected event {:?}", event),
+    }
+    admin
+        .batch_execute("CREATE ROLE reconnect_init_role")
+        .await
+        .unwrap();
+    match next_event(&mut events).await {
+        ReconnectEvent::Reconnected => {}
+        event => panic!("unexpected event {:?}", event),
+    }
+
+    let count = client
+        .query_one(
+            "SELECT count(*) FROM pg_prepared_statements WHERE statement = 'SELECT $1::TEXT'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get::<_, i64>(0);
+    assert_eq!(count, 1);
+
+    drop(client);
+    admin
+        .batch_execute("DROP ROLE reconnect_init_role")
+        .await
+        .unwrap();
+}
@@ -0,0 +1,254 @@
+use bytes::{Bytes, BytesMut};
+use futures::{pin_mut, SinkExt, StreamExt, TryStreamExt};
+use postgres_protocol::message::frontend;
+use std::time::Duration;
+use tokio::time;
+use tokio_postgres::replication::{
+    BaseBackupMessage, BaseBackupOptions, LogicalReplicationMessage, ReplicationMessage,
+    SnapshotAction, TupleData,
+};
+use tokio_postgres::types::PgLsn;
+
+#[tokio::test]
+async fn logical_replication() {
+    let client = crate::connect("user=postgres").await;
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS replication_logical;
+             CREATE TABLE replication_logical (id INTEGER PRIMARY KEY, name TEXT);
+             DROP PUBLICATION IF EXISTS replication_logical_pub;
+             CREATE PUBLICATION replication_logical_pub FOR TABLE replication_logical;",
+        )
+        .await
+        .unwrap();
+
+    let repl_client = crate::connect("user=postgres replication=database").await;
+    let slot = repl_client
+        .create_logical_replication_slot(
+            "replication_logical_slot",
+            true,
+            "pgoutput",
+            Some(SnapshotAction::NoExport),
+        )
+        .await
+        .unwrap();
+    assert_eq!(slot.slot_name(), "replication_logical_slot");
+    assert_eq!(slot.output_plugin(), Some("pgoutput"));
+    let consistent_point = slot.consistent_point();
+
+    client
+        .batch_execute("INSERT INTO replication_logical (id, name) VALUES (1, 'steven')")
+        .await
+        .unwrap();
+
+    let stream = repl_client
+        .start_logical_replication(
+            "replication_logical_slot",
+            consistent_point,
+            &[
+                ("proto_version", "1"),
+                ("publication_names", "replication_logical_pub"),
+            ],
+        )
+        .await
+        .unwrap();
+    pin_mut!(stream);
+
+    let mut messages = vec![];
+    loop {
+        let message = time::timeout(Duration::from_secs(10), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let ReplicationMessage::XLogData(body) = message {
+            messages.push(body.into_data());
+            if let Some(LogicalReplicationMessage::Commit(_)) = messages.last() {
+                break;
+            }
+        }
+    }
+
+    match &messages[..] {
+        [LogicalReplicationMessage::Begin(_), LogicalReplicationMessage::Relation(relation), LogicalReplicationMessage::Insert(insert), LogicalReplicationMessage::Commit(_)] =>
+        {
+            assert_eq!(relation.namespace().unwrap(), "public");
+            assert_eq!(relation.name().unwrap(), "replication_logical");
+            let columns = relation
+                .columns()
+                .iter()
+                .map(|c| c.name().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(columns, ["id", "name"]);
+
+            assert_eq!(insert.rel_id(), relation.rel_id());
+            let values = insert
+                .tuple()
+                .tuple_data()
+                .iter()
+                .map(|d| match d {
+                    TupleData::Text(b) => &b[..],
+                    _ => panic!("unexpected tuple data"),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(values, [&b"1"[..], &b"steven"[..]]);
+        }
+        _ => panic!("unexpected messages: {:?}", messages),
+    }
+
+    stream
+        .standby_status_update(consistent_point, consistent_point, consistent_point, true)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn copy_both() {
+    let client = crate::connect("user=postgres replication=true").await;
+
+    let xlogpos = client.identify_system().await.unwrap().xlogpos();
+
+    let duplex = client
+        .copy_both::<Bytes>(&format!("START_REPLICATION PHYSICAL {}", xlogpos))
+        .await
+        .unwrap();
+    pin_mut!(duplex);
+
+    let mut buf = BytesMut::new();
+    frontend::standby_status_update(
+        xlogpos.into(),
+        xlogpos.into(),
+        xlogpos.into(),
+        0,
+        1,
+        &mut buf,
+    );
+    duplex.send(buf.freeze()).await.unwrap();
+
+    loop {
+        let data = time::timeout(Duration::from_secs(10), duplex.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if data[0] == b'k' {
+            break;
+        }
+    }
+
+    duplex.close().await.unwrap();
+    while let Some(data) = duplex.next().await {
+        data.unwrap();
+    }
+
+    client.identify_system().await.unwrap();
+}
+
+#[tokio::test]
+async fn physical_replication() {
+    let client = crate::connect("user=postgres").await;
+    client
+        .batch_execute(
+            "DROP TABLE IF EXISTS replication_physical;
+             CREATE TABLE replication_physical (id INTEGER)",
+        )
+        .await
+        .unwrap();
+
+    let repl_client = crate::connect("user=postgres replication=true").await;
+    let system = repl_client.identify_system().await.unwrap();
+    assert_eq!(system.dbname(), None);
+
+    let slot = repl_client
+        .create_physical_replication_slot("replication_physical_slot", true, true)
+        .await
+        .unwrap();
+    assert_eq!(slot.slot_name(), "replication_physical_slot");
+    assert_eq!(slot.output_plugin(), None);
+
+    let stream = repl_client
+        .start_physical_replication(
+            Some("replication_physical_slot"),
+            system.xlogpos(),
+            Some(system.timeline()),
+        )
+        .await
+        .unwrap();
+    pin_mut!(stream);
+
+    client
+        .batch_execute("INSERT INTO replication_physical (id) VALUES (1)")
+        .await
+        .unwrap();
+
+    let data = time::timeout(Duration::from_secs(10), stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(PgLsn::from(data.wal_start()) >= system.xlogpos());
+    assert!(stream.write_lsn() > system.xlogpos());
+
+    let write_lsn = stream.write_lsn();
+    stream.as_mut().set_flush_lsn(write_lsn);
+    stream.standby_status_update(false).await.unwrap();
+}
+
+#[tokio::test]
+async fn drop_replication_slot() {
+    let client = crate::connect("user=postgres replication=true").await;
+
+    client
+        .create_physical_replication_slot("replication_drop_slot", false, false)
+        .await
+        .unwrap();
+    client
+        .drop_replication_slot("replication_drop_slot", false)
+        .await
+        .unwrap();
+    client
+        .drop_replication_slot("replication_drop_slot", false)
+        .await
+        .err()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn base_backup() {
+    let client = crate::connect("user=postgres replication=true").await;
+
+    let stream = client
+        .base_backup(
+            BaseBackupOptions::new()
+                .label("rust-postgres test")
+                .fast(true)
+                .wait(false),
+        )
+        .await
+        .unwrap();
+    pin_mut!(stream);
+
+    let mut start = None;
+    let mut end = None;
+    let mut archives = vec![];
+    let mut data = 0;
+    while let Some(message) = stream.try_next().await.unwrap() {
+        match message {
+            BaseBackupMessage::Start(position) => start = Some(position),
+            BaseBackupMessage::Archive(archive) => archives.push(archive),
+            BaseBackupMessage::Data(buf) => data += buf.len(),
+            BaseBackupMessage::End(position) => end = Some(position),
+            _ => {}
+        }
+    }
+
+    let start = start.unwrap();
+    let end = end.unwrap();
+    assert!(end.lsn() >= start.lsn());
+    assert!(archives
+        .iter()
+        .any(|a| a.name() == "base.tar" && a.location().is_none()));
+    assert!(data > 0);
+
+    client.identify_system().await.unwrap();
+}
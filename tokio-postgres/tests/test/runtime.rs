@@ -66,6 +66,54 @@ async fn target_session_attrs_err() {
     .unwrap();
 }
 
+#[tokio::test]
+async fn target_session_attrs_read_only() {
+    smoke_test(
+        "host=localhost port=5433 user=postgres target_session_attrs=read-only
+         options='-c default_transaction_read_only=on'",
+    )
+    .await;
+
+    tokio_postgres::connect(
+        "host=localhost port=5433 user=postgres target_session_attrs=read-only",
+        NoTls,
+    )
+    .await
+    .err()
+    .unwrap();
+}
+
+#[tokio::test]
+async fn target_session_attrs_primary() {
+    smoke_test("host=localhost port=5433 user=postgres target_session_attrs=primary").await;
+}
+
+#[tokio::test]
+async fn target_session_attrs_standby_err() {
+    tokio_postgres::connect(
+        "host=localhost port=5433 user=postgres target_session_attrs=standby",
+        NoTls,
+    )
+    .await
+    .err()
+    .unwrap();
+}
+
+#[tokio::test]
+async fn target_session_attrs_prefer_standby() {
+    smoke_test("host=localhost port=5433 user=postgres target_session_attrs=prefer-standby").await;
+}
+
+#[tokio::test]
+async fn load_balance_hosts_random() {
+    for _ in 0..5 {
+        smoke_test(
+            "host=foobar.invalid,localhost port=5433 user=postgres load_balance_hosts=random",
+        )
+        .await;
+    }
+}
+
 #[tokio::test]
 async fn cancel_query() {
     let client = connect("host=localhost port=5433 user=postgres").await;
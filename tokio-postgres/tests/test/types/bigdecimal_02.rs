@@ -0,0 +1,41 @@
+use bigdecimal_02::BigDecimal;
+
+use crate::connect;
+use crate::types::test_type;
+
+#[tokio::test]
+async fn test_big_decimal_params() {
+    fn make_check(s: &str) -> (Option<BigDecimal>, &str) {
+        (Some(s.parse().unwrap()), s)
+    }
+    test_type(
+        "NUMERIC",
+        &[
+            make_check("0"),
+            make_check("-1.50"),
+            make_check("12345678901234567890.0987654321"),
+            make_check("0.00000000000000000000000000000000000001"),
+            make_check("100000000000000000000000000000000000000000"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_big_decimal_special() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one("SELECT 'NaN'::NUMERIC", &[])
+        .await
+        .unwrap();
+    assert!(row.try_get::<_, BigDecimal>(0).is_err());
+
+    let value = "1e50".parse::<BigDecimal>().unwrap();
+    let row = client
+        .query_one("SELECT $1::NUMERIC::TEXT", &[&value])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &str>(0), format!("1{}", "0".repeat(50)));
+}
@@ -1,6 +1,7 @@
-use chrono_04::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_04::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use std::convert::TryFrom;
 use std::fmt;
-use tokio_postgres::types::{Date, FromSqlOwned, Timestamp};
+use tokio_postgres::types::{Date, FromSqlOwned, PgInterval, Timestamp};
 use tokio_postgres::Client;
 
 use crate::connect;
@@ -186,3 +187,17 @@ async fn test_special_params_without_wrapper() {
     assert_overflows::<NaiveDate>(&mut client, "'-infinity'", "date").await;
     assert_overflows::<NaiveDate>(&mut client, "'infinity'", "date").await;
 }
+
+#[test]
+fn interval_duration() {
+    let duration = Duration::try_from(PgInterval::new(0, 0, -1_500_000)).unwrap();
+    assert_eq!(duration, Duration::milliseconds(-1500));
+    assert_eq!(
+        PgInterval::try_from(Duration::nanoseconds(1_500_000_999)).unwrap(),
+        PgInterval::new(0, 0, 1_500_000)
+    );
+
+    assert!(Duration::try_from(PgInterval::new(1, 0, 0)).is_err());
+    assert!(Duration::try_from(PgInterval::new(0, -1, 0)).is_err());
+    assert!(PgInterval::try_from(Duration::max_value()).is_err());
+}
@@ -1,5 +1,6 @@
 use postgres_types::to_sql_checked;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::error::Error;
 use std::f32;
 use std::f64;
@@ -7,11 +8,16 @@ use std::fmt;
 use std::net::IpAddr;
 use std::result;
 use std::time::{Duration, UNIX_EPOCH};
-use tokio_postgres::types::{FromSql, FromSqlOwned, IsNull, Kind, ToSql, Type, WrongType};
+use tokio_postgres::types::{
+    FromSql, FromSqlOwned, IsNull, Kind, Multirange, PgInterval, PgNumeric, Range, RangeBound,
+    ToSql, Type, WrongType,
+};
 
 use crate::connect;
 use bytes::BytesMut;
 
+#[cfg(feature = "with-bigdecimal-0_2")]
+mod bigdecimal_02;
 #[cfg(feature = "with-bit-vec-0_6")]
 mod bit_vec_06;
 #[cfg(feature = "with-chrono-0_4")]
@@ -22,6 +28,8 @@ mod eui48_04;
 mod geo_types_04;
 #[cfg(feature = "with-geo-types-0_6")]
 mod geo_types_06;
+#[cfg(feature = "with-rust_decimal-1")]
+mod rust_decimal_1;
 #[cfg(feature = "with-serde_json-1")]
 mod serde_json_1;
 #[cfg(feature = "with-time-0_2")]
@@ -163,6 +171,311 @@ async fn test_f64_params() {
     .await;
 }
 
+#[tokio::test]
+async fn test_numeric_params() {
+    fn make_check(s: &str) -> (Option<PgNumeric>, String) {
+        (Some(s.parse().unwrap()), format!("'{}'", s))
+    }
+    let mut checks = vec![
+        make_check("0"),
+        make_check("0.000"),
+        make_check("1"),
+        make_check("-1.50"),
+        make_check("12345678901234567890.0987654321"),
+        make_check("0.00001"),
+        make_check("-100000000"),
+        make_check("1e-30"),
+        make_check("NaN"),
+        (None, "NULL".to_string()),
+    ];
+    let client = connect("user=postgres").await;
+    if supports_numeric_infinity(&client).await {
+        checks.push(make_check("Infinity"));
+        checks.push(make_check("-Infinity"));
+    }
+    test_type("NUMERIC", &checks).await;
+}
+
+#[tokio::test]
+async fn test_interval_params() {
+    test_type(
+        "INTERVAL",
+        &[
+            (Some(PgInterval::new(0, 0, 0)), "'0'"),
+            (
+                Some(PgInterval::new(14, 3, 14_400_000_001)),
+                "'1 year 2 months 3 days 04:00:00.000001'",
+            ),
+            (
+                Some(PgInterval::new(-1, -2, -3_000_000)),
+                "'-1 month -2 days -3 seconds'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
+
+#[test]
+fn interval_duration() {
+    let duration = Duration::try_from(PgInterval::new(0, 0, 1_500_000)).unwrap();
+    assert_eq!(duration, Duration::from_millis(1500));
+    assert_eq!(
+        PgInterval::try_from(Duration::from_nanos(1_500_000_999)).unwrap(),
+        PgInterval::new(0, 0, 1_500_000)
+    );
+
+    assert!(Duration::try_from(PgInterval::new(1, 0, 0)).is_err());
+    assert!(Duration::try_from(PgInterval::new(0, 1, 0)).is_err());
+    assert!(Duration::try_from(PgInterval::new(0, 0, -1)).is_err());
+    assert!(PgInterval::try_from(Duration::from_secs(u64::max_value())).is_err());
+}
+
+#[tokio::test]
+async fn test_int4range_params() {
+    test_type(
+        "INT4RANGE",
+        &[
+            (
+                Some(Range::new(
+                    RangeBound::Inclusive(1),
+                    RangeBound::Exclusive(4),
+                )),
+                "'[1,3]'",
+            ),
+            (
+                Some(Range::new(RangeBound::Unbounded, RangeBound::Exclusive(0))),
+                "'(,0)'",
+            ),
+            (
+                Some(Range::new(RangeBound::Inclusive(-5), RangeBound::Unbounded)),
+                "'(-6,)'",
+            ),
+            (
+                Some(Range::new(RangeBound::Unbounded, RangeBound::Unbounded)),
+                "'(,)'",
+            ),
+            (Some(Range::empty()), "'empty'"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_numrange_params() {
+    fn bound(s: &str) -> PgNumeric {
+        s.parse().unwrap()
+    }
+    test_type(
+        "NUMRANGE",
+        &[
+            (
+                Range::new(
+                    RangeBound::Exclusive(bound("1.5")),
+                    RangeBound::Inclusive(bound("2.25")),
+                ),
+                "'(1.5,2.25]'",
+            ),
+            (Range::empty(), "'empty'"),
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn range_helpers() {
+    let client = connect("user=postgres").await;
+    client
+        .batch_execute("CREATE TYPE pg_temp.float8range AS RANGE (subtype = float8)")
+        .await
+        .unwrap();
+
+    let ranges = [
+        "empty",
+        "(,)",
+        "[1,5)",
+        "[5,10)",
+        "[3,7]",
+        "(,3)",
+        "(3,)",
+        "[4,4]",
+        "(1.5,2.5)",
+        "[2.5,3)",
+    ];
+    for a in &ranges {
+        for b in &ranges {
+            let row = client
+                .query_one(
+                    &*format!(
+                        "SELECT '{0}'::float8range, '{1}'::float8range, \
+                         '{0}'::float8range @> '{1}'::float8range, \
+                         '{0}'::float8range && '{1}'::float8range, \
+                         '{0}'::float8range @> 3::float8",
+                        a, b
+                    ),
+                    &[],
+                )
+                .await
+                .unwrap();
+            let a = row.get::<_, Range<f64>>(0);
+            let b = row.get::<_, Range<f64>>(1);
+            assert_eq!(
+                a.contains_range(&b),
+                row.get::<_, bool>(2),
+                "{:?} @> {:?}",
+                a,
+                b
+            );
+            assert_eq!(a.overlaps(&b), row.get::<_, bool>(3), "{:?} && {:?}", a, b);
+            assert_eq!(a.contains(&3.), row.get::<_, bool>(4), "{:?} @> 3", a);
+        }
+    }
+}
+
+async fn server_version_num(client: &tokio_postgres::Client) -> i32 {
+    let row = client
+        .query_one("SHOW server_version_num", &[])
+        .await
+        .unwrap();
+    row.get::<_, &str>(0).parse().unwrap()
+}
+
+// multirange types were added in Postgres 14
+async fn supports_multiranges(client: &tokio_postgres::Client) -> bool {
+    server_version_num(client).await >= 140_000
+}
+
+// NUMERIC infinities were added in Postgres 14
+async fn supports_numeric_infinity(client: &tokio_postgres::Client) -> bool {
+    server_version_num(client).await >= 140_000
+}
+
+#[tokio::test]
+async fn test_int4multirange_params() {
+    let client = connect("user=postgres").await;
+    if !supports_multiranges(&client).await {
+        return;
+    }
+
+    let checks = [
+        (
+            Multirange::new(vec![
+                Range::new(RangeBound::Inclusive(1), RangeBound::Exclusive(4)),
+                Range::new(RangeBound::Inclusive(5), RangeBound::Unbounded),
+            ]),
+            "'{[1,3], (4,)}'",
+        ),
+        (Multirange::empty(), "'{}'"),
+        (Multirange::empty(), "'{empty}'"),
+    ];
+    for (val, repr) in &checks {
+        let row = client
+            .query_one(
+                &*format!("SELECT {}::INT4MULTIRANGE, $1::INT4MULTIRANGE", repr),
+                &[val],
+            )
+            .await
+            .unwrap();
+        assert_eq!(val, &row.get::<_, Multirange<i32>>(0));
+        assert_eq!(val, &row.get::<_, Multirange<i32>>(1));
+    }
+
+    // the server normalizes the multiranges it's sent
+    let row = client
+        .query_one(
+            "SELECT $1::INT4MULTIRANGE",
+            &[&Multirange::new(vec![
+                Range::new(RangeBound::Inclusive(5), RangeBound::Exclusive(8)),
+                Range::empty(),
+                Range::new(RangeBound::Inclusive(1), RangeBound::Exclusive(6)),
+            ])],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        Multirange::new(vec![Range::new(
+            RangeBound::Inclusive(1),
+            RangeBound::Exclusive(8)
+        )]),
+        row.get::<_, Multirange<i32>>(0)
+    );
+}
+
+#[tokio::test]
+async fn custom_multirange() {
+    let client = connect("user=postgres").await;
+    if !supports_multiranges(&client).await {
+        return;
+    }
+
+    client
+        .batch_execute("CREATE TYPE pg_temp.float8range AS RANGE (subtype = float8)")
+        .await
+        .unwrap();
+
+    let stmt = client.prepare("SELECT $1::float8multirange").await.unwrap();
+    let ty = &stmt.params()[0];
+    assert_eq!("float8multirange", ty.name());
+    match ty.kind() {
+        Kind::Multirange(range) => {
+            assert_eq!("float8range", range.name());
+            assert_eq!(&Kind::Range(Type::FLOAT8), range.kind());
+        }
+        kind => panic!("unexpected kind {:?}", kind),
+    }
+
+    let multirange = Multirange::new(vec![
+        Range::new(RangeBound::Inclusive(1.5), RangeBound::Exclusive(2.5)),
+        Range::new(RangeBound::Exclusive(3.), RangeBound::Unbounded),
+    ]);
+    let row = client.query_one(&stmt, &[&multirange]).await.unwrap();
+    let multirange = row.get::<_, Multirange<f64>>(0);
+    assert_eq!(2, multirange.ranges().len());
+    assert!(multirange.contains(&2.));
+    assert!(!multirange.contains(&3.));
+    assert!(multirange.overlaps(&Range::new(
+        RangeBound::Inclusive(2.5),
+        RangeBound::Inclusive(3.5)
+    )));
+}
+
+#[tokio::test]
+async fn numeric_display() {
+    let client = connect("user=postgres").await;
+
+    let mut values = vec![
+        "0",
+        "0.000",
+        "-1.50",
+        "12345678901234567890.0987654321",
+        "0.00001",
+        "-100000000",
+        "1.5e10",
+        "1e-30",
+        "NaN",
+    ];
+    if supports_numeric_infinity(&client).await {
+        values.push("-Infinity");
+    }
+    for s in values {
+        let row = client
+            .query_one(
+                &*format!("SELECT '{0}'::NUMERIC, '{0}'::NUMERIC::TEXT", s),
+                &[],
+            )
+            .await
+            .unwrap();
+        let numeric = row.get::<_, PgNumeric>(0);
+        assert_eq!(numeric.to_string(), row.get::<_, &str>(1));
+        assert_eq!(s.parse::<PgNumeric>().unwrap(), numeric);
+    }
+
+    assert_eq!(PgNumeric::from(-1234567i64).to_string(), "-1234567");
+    assert!("1.2.3".parse::<PgNumeric>().is_err());
+    assert!("".parse::<PgNumeric>().is_err());
+}
+
 #[tokio::test]
 async fn test_varchar_params() {
     test_type(
@@ -0,0 +1,60 @@
+use rust_decimal_1::Decimal;
+use tokio_postgres::types::PgNumeric;
+
+use crate::connect;
+use crate::types::{supports_numeric_infinity, test_type};
+
+#[tokio::test]
+async fn test_decimal_params() {
+    fn make_check(s: &str) -> (Option<Decimal>, &str) {
+        (Some(s.parse().unwrap()), s)
+    }
+    test_type(
+        "NUMERIC",
+        &[
+            make_check("0"),
+            make_check("0.000"),
+            make_check("-1.50"),
+            make_check("1234567890.0987654321"),
+            make_check("0.0000000000000000000000000001"),
+            make_check("79228162514264337593543950335"),
+            make_check("-79228162514264337593543950335"),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_decimal_out_of_range() {
+    let client = connect("user=postgres").await;
+
+    let mut values = vec![
+        "'NaN'",
+        "79228162514264337593543950336",
+        "0.00000000000000000000000000001",
+    ];
+    if supports_numeric_infinity(&client).await {
+        values.push("'Infinity'");
+    }
+    for s in values {
+        let row = client
+            .query_one(&*format!("SELECT {}::NUMERIC", s), &[])
+            .await
+            .unwrap();
+        assert!(row.try_get::<_, Decimal>(0).is_err());
+    }
+
+    // trailing zeros past the largest scale are dropped
+    let row = client
+        .query_one("SELECT 1.00000000000000000000000000000000::NUMERIC", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, Decimal>(0), Decimal::new(1, 0));
+
+    let row = client
+        .query_one("SELECT $1::NUMERIC", &[&Decimal::new(-12345, 2)])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, PgNumeric>(0).to_string(), "-123.45");
+}
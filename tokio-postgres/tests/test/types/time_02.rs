@@ -1,5 +1,6 @@
-use time_02::{OffsetDateTime, PrimitiveDateTime};
-use tokio_postgres::types::{Date, Timestamp};
+use std::convert::TryFrom;
+use time_02::{Duration, OffsetDateTime, PrimitiveDateTime};
+use tokio_postgres::types::{Date, PgInterval, Timestamp};
 
 use crate::types::test_type;
 
@@ -148,3 +149,17 @@ async fn test_time_params() {
     )
     .await;
 }
+
+#[test]
+fn interval_duration() {
+    let duration = Duration::try_from(PgInterval::new(0, 0, -1_500_000)).unwrap();
+    assert_eq!(duration, Duration::milliseconds(-1500));
+    assert_eq!(
+        PgInterval::try_from(Duration::nanoseconds(1_500_000_999)).unwrap(),
+        PgInterval::new(0, 0, 1_500_000)
+    );
+
+    assert!(Duration::try_from(PgInterval::new(1, 0, 0)).is_err());
+    assert!(Duration::try_from(PgInterval::new(0, -1, 0)).is_err());
+    assert!(PgInterval::try_from(Duration::max_value()).is_err());
+}